@@ -0,0 +1,63 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+//! Measures the time and the allocations of scanning a buffer in which every signature matches, so that the cost per
+//! match can be compared with copying the signature name of each match.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use player_id::{ConfigSyntax, PlayerId, SignatureMatch};
+
+const SIGNATURE_COUNT: usize = 200;
+const ITERATIONS: usize = 1000;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts the allocations, so that the allocations per match can be reported next to the time.
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn main() {
+    let lines = (0..SIGNATURE_COUNT)
+        .flat_map(|index| [format!("Benchmark_Player_{index:03}"), format!("A9 {index:02X} 8D 18 D4")])
+        .collect::<Vec<String>>();
+    let signature_ids = PlayerId::load_config_lines(&lines, None, ConfigSyntax::default()).unwrap();
+    let buffer = (0..SIGNATURE_COUNT).flat_map(|index| [0xA9, index as u8, 0x8D, 0x18, 0xD4]).collect::<Vec<u8>>();
+    let match_count = PlayerId::find_players_in_buffer(&buffer, &signature_ids, true).len();
+
+    let (elapsed, allocations) = measure(|| PlayerId::find_players_in_buffer(&buffer, &signature_ids, true));
+    let (_, name_copy_allocations) = measure(|| {
+        let matches = PlayerId::find_players_in_buffer(&buffer, &signature_ids, true);
+        matches.iter().map(|signature_match: &SignatureMatch| signature_match.signature_name.to_string()).collect::<Vec<String>>()
+    });
+
+    println!("find_players_in_buffer: {match_count} matches in {:.1} us per scan\r", elapsed / ITERATIONS as f64 * 1_000_000.0);
+    println!("Allocations per match: {:.2}\r", allocations as f64 / (ITERATIONS * match_count) as f64);
+    println!("Allocations per match when copying the names: {:.2}\r", name_copy_allocations as f64 / (ITERATIONS * match_count) as f64);
+}
+
+/// Runs the scan for all iterations and returns the elapsed seconds and the number of allocations.
+fn measure<T>(scan: impl Fn() -> T) -> (f64, usize) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start_time = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(scan());
+    }
+    (start_time.elapsed().as_secs_f64(), ALLOCATIONS.load(Ordering::Relaxed) - allocations)
+}
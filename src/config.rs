@@ -2,8 +2,58 @@
 // Licensed under the MIT license. See the LICENSE file for the terms and conditions.
 
 use std::env;
+use std::fs;
+use std::ops::Range;
+use std::time::{Duration, SystemTime};
+
+use player_id::{ConfigSyntax, LineEnding};
+
+use super::timestamp;
+
+const MAX_INFO_TAG_LENGTH: usize = 9;
+const DEFAULT_HISTOGRAM_BUCKET_SIZE: usize = 0x100;
+const DEFAULT_CONFLICT_PERCENTAGE: usize = 50;
+const DEFAULT_TED_CONFIG_FILE_NAME: &str = "tedid.cfg";
+const BYTES_PER_MEGABYTE: usize = 1024 * 1024;
+
+/// Common command lines with a description, shown with the --examples option.
+pub const EXAMPLES: [(&str, &str); 7] = [
+    ("Scan all SID files in a directory and its sub directories", "player-id -s C64Music/*.sid"),
+    ("Scan HVSC, using the location in the HVSC environment variable", "player-id -h"),
+    ("Scan HVSC for multiple players per file", "player-id -h -m"),
+    ("Scan for files with a specific player and show where it's found", "player-id -pSoundMonitor -x -s C64Music/*.sid"),
+    ("Show the info of a player", "player-id info SoundMonitor"),
+    ("Verify the signatures and the info of a config file", "player-id verify -fsidid.cfg"),
+    ("Write the signatures of a config file in the new format", "player-id convert new -fsidid.cfg")
+];
+
+pub enum SummaryFormat {
+    Pretty,
+    KeyValue
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Scan,
+    Verify,
+    Convert { new_format: bool },
+    Info,
+    List,
+    ListInfo,
+    Which,
+    DumpMasks,
+    DumpParsed,
+    ConfigStats,
+    Examples,
+    SortConfig,
+    SuggestSignature,
+    TestConfig,
+    Bundle,
+    FalsePositiveCheck
+}
 
 pub struct Config {
+    pub command: Command,
     pub cpu_threads: usize,
     pub display_hex_offset: bool,
     pub list_unidentified: bool,
@@ -11,95 +61,487 @@ pub struct Config {
     pub recursive: bool,
     pub scan_for_multiple: bool,
     pub scan_hvsc: bool,
-    pub show_player_info: bool,
     pub truncate_filenames: bool,
-    pub verify_signatures: bool,
     pub player_name: Option<String>,
     pub config_file: Option<String>,
+    pub info_file: Option<String>,
     pub base_path: String,
     pub filename: String,
-    pub convert_file_format: Option<String>
+    pub info_tags: Option<Vec<String>>,
+    pub summary_format: SummaryFormat,
+    pub modified_since: Option<SystemTime>,
+    pub offset_histogram: Option<usize>,
+    pub use_builtin_config: bool,
+    pub conflict_percentage: Option<usize>,
+    pub line_ending: LineEnding,
+    pub silent: bool,
+    pub info_filter: Option<Vec<String>>,
+    pub stride: usize,
+    pub show_song_count: bool,
+    pub absolute_paths: bool,
+    pub show_wildcard_density: bool,
+    pub show_song_lengths: bool,
+    pub show_hint: bool,
+    pub show_confidence: bool,
+    pub fail_fast: bool,
+    pub from_init: bool,
+    pub stats_include_unidentified: bool,
+    pub try_configs: Option<String>,
+    pub ted: bool,
+    pub both_offsets: bool,
+    pub flat: bool,
+    pub timeout: Option<Duration>,
+    pub json: bool,
+    pub any_order: bool,
+    pub skip_regions: Vec<Range<usize>>,
+    pub unique_tunes: bool,
+    pub exec_command: Option<String>,
+    pub config_syntax: ConfigSyntax,
+    pub convert_report: bool,
+    pub sid_only: bool,
+    pub show_source: bool,
+    pub first_offset_only: bool,
+    pub negatives_directory: Option<String>,
+    pub false_positives_directory: Option<String>,
+    pub relocatable: bool,
+    pub max_memory: Option<usize>,
+    pub per_player_files: bool,
+    pub heuristic: bool,
+    pub unidentified_label: Option<String>,
+    pub skipped_label: Option<String>,
+    pub file_types: bool,
+    pub bundle_file: Option<String>,
+    pub concatenated: bool,
+    pub repeat: Option<usize>,
+    pub name_only: bool
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            command: Command::Scan,
+            cpu_threads: rayon::current_num_threads(),
+            display_hex_offset: false,
+            list_unidentified: false,
+            only_list_unidentified: false,
+            recursive: false,
+            scan_for_multiple: false,
+            scan_hvsc: false,
+            truncate_filenames: false,
+            player_name: None,
+            config_file: None,
+            info_file: None,
+            base_path: "".to_string(),
+            filename: "".to_string(),
+            info_tags: None,
+            summary_format: SummaryFormat::Pretty,
+            modified_since: None,
+            offset_histogram: None,
+            use_builtin_config: true,
+            conflict_percentage: None,
+            line_ending: LineEnding::CrLf,
+            silent: false,
+            info_filter: None,
+            stride: 1,
+            show_song_count: false,
+            absolute_paths: false,
+            show_wildcard_density: false,
+            show_song_lengths: false,
+            show_hint: false,
+            show_confidence: false,
+            fail_fast: false,
+            from_init: false,
+            stats_include_unidentified: false,
+            try_configs: None,
+            ted: false,
+            both_offsets: false,
+            flat: false,
+            timeout: None,
+            json: false,
+            any_order: false,
+            skip_regions: vec![],
+            unique_tunes: false,
+            exec_command: None,
+            config_syntax: ConfigSyntax::default(),
+            convert_report: false,
+            sid_only: false,
+            show_source: false,
+            first_offset_only: false,
+            negatives_directory: None,
+            false_positives_directory: None,
+            relocatable: false,
+            max_memory: None,
+            per_player_files: false,
+            heuristic: false,
+            unidentified_label: None,
+            skipped_label: None,
+            file_types: false,
+            bundle_file: None,
+            concatenated: false,
+            repeat: None,
+            name_only: false
+        }
+    }
 }
 
 impl Config {
     pub fn read() -> Result<Config, String> {
-        let max_threads = rayon::current_num_threads();
-        let mut cpu_threads = max_threads;
-        let mut display_hex_offset = false;
-        let mut list_unidentified = false;
-        let mut only_list_unidentified = false;
-        let mut recursive = false;
-        let mut scan_for_multiple = false;
-        let mut scan_hvsc = false;
-        let mut show_player_info = false;
-        let mut truncate_filenames = false;
-        let mut verify_signatures = false;
-        let mut config_file = None;
-        let mut player_name = None;
-        let mut convert_file_format = None;
-
-        for argument in env::args().filter(|arg| arg.len() > 1 && arg.starts_with('-')) {
+        Self::parse(&env::args().skip(1).collect::<Vec<String>>())
+    }
+
+    pub fn parse(arguments: &[String]) -> Result<Config, String> {
+        let mut config = Config::default();
+        let max_threads = config.cpu_threads;
+
+        let (command, arguments) = Self::split_command(arguments)?;
+        let explicit_command = command.is_some();
+        let mut commands = command.into_iter().collect::<Vec<Command>>();
+
+        for argument in arguments.iter().filter(|arg| arg.len() > 1 && arg.starts_with('-')) {
+            if let Some(long_option) = argument.strip_prefix("--") {
+                let (option_name, option_value) = Self::split_long_option(long_option);
+                match option_name {
+                    "info-tags" => config.info_tags = Some(Self::parse_info_tags(option_value)?),
+                    "summary-format" => config.summary_format = Self::parse_summary_format(option_value)?,
+                    "since" => config.modified_since = Some(Self::parse_since_date(option_value)?),
+                    "newer-than" => config.modified_since = Some(Self::get_modified_time(option_value)?),
+                    "offset-histogram" => config.offset_histogram = Some(Self::parse_bucket_size(option_value)?),
+                    "no-builtin" => config.use_builtin_config = false,
+                    "line-ending" => config.line_ending = Self::parse_line_ending(option_value)?,
+                    "silent" => config.silent = true,
+                    "stride" => config.stride = Self::parse_stride(option_value)?,
+                    "songs" => config.show_song_count = true,
+                    "absolute-paths" => config.absolute_paths = true,
+                    "wildcard-stats" => config.show_wildcard_density = true,
+                    "songlengths" => config.show_song_lengths = true,
+                    "hint" => config.show_hint = true,
+                    "confidence" => config.show_confidence = true,
+                    "fail-fast" => config.fail_fast = true,
+                    "from-init" => config.from_init = true,
+                    "ted" => config.ted = true,
+                    "flat" => config.flat = true,
+                    "json" => config.json = true,
+                    "any-order" => config.any_order = true,
+                    "relocatable" => config.relocatable = true,
+                    "unique-tunes" => config.unique_tunes = true,
+                    "report" => config.convert_report = true,
+                    "sid-only" => config.sid_only = true,
+                    "show-source" => config.show_source = true,
+                    "first-offset-only" => config.first_offset_only = true,
+                    "strict" => config.config_syntax.strict = true,
+                    "min-line-length" => config.config_syntax.min_line_length = Self::parse_length(option_value)?,
+                    "min-name-length" => config.config_syntax.min_name_length = Self::parse_min_name_length(option_value)?,
+                    "exec" => config.exec_command = Some(Self::parse_exec_command(option_value)?),
+                    "skip-region" => config.skip_regions.push(Self::parse_skip_region(option_value)?),
+                    "timeout-ms" => config.timeout = Some(Self::parse_timeout(option_value)?),
+                    "max-memory" => config.max_memory = Some(Self::parse_max_memory(option_value)?),
+                    "both-offsets" => {
+                        config.both_offsets = true;
+                        config.display_hex_offset = true;
+                    },
+                    "stats-include-unidentified" => config.stats_include_unidentified = true,
+                    "per-player-files" => config.per_player_files = true,
+                    "heuristic" => config.heuristic = true,
+                    "file-types" => config.file_types = true,
+                    "concatenated" => config.concatenated = true,
+                    "name-only" => config.name_only = true,
+                    "repeat" => config.repeat = Some(Self::parse_argument_number("Repeat count", option_value)? as usize),
+                    "unidentified-label" => config.unidentified_label = Some(Self::parse_label(option_name, option_value)?),
+                    "skipped-label" => config.skipped_label = Some(Self::parse_label(option_name, option_value)?),
+                    "info-file" => config.info_file = Some(option_value.to_string()),
+                    "try-configs" => config.try_configs = Some(Self::parse_directory(option_name, option_value)?),
+                    "which" => commands.push(Command::Which),
+                    "dump-masks" => commands.push(Command::DumpMasks),
+                    "dump-parsed" => commands.push(Command::DumpParsed),
+                    "examples" => commands.push(Command::Examples),
+                    "config-stats" => commands.push(Command::ConfigStats),
+                    "sort-config" => commands.push(Command::SortConfig),
+                    "test-config" => commands.push(Command::TestConfig),
+                    "bundle" => {
+                        config.bundle_file = Some(Self::parse_bundle_file(option_value)?);
+                        commands.push(Command::Bundle);
+                    },
+                    "suggest-signature" => {
+                        config.negatives_directory = Some(Self::parse_directory(option_name, option_value)?);
+                        commands.push(Command::SuggestSignature);
+                    },
+                    "false-positive-check" => {
+                        config.false_positives_directory = Some(Self::parse_directory(option_name, option_value)?);
+                        commands.push(Command::FalsePositiveCheck);
+                    },
+                    "list-info" => {
+                        if !option_value.is_empty() {
+                            config.info_filter = Some(Self::parse_info_tags(option_value)?);
+                        }
+                        commands.push(Command::ListInfo);
+                    },
+                    "conflicts" => {
+                        config.conflict_percentage = Some(Self::parse_percentage(option_value, DEFAULT_CONFLICT_PERCENTAGE)?);
+                        config.scan_for_multiple = true;
+                    },
+                    _ => return Err(format!("Unknown option: {argument}"))
+                }
+                continue;
+            }
+
             match &argument[1..2] {
-                "c" => cpu_threads = Self::parse_argument_number("Max threads", &argument[2..])? as usize,
-                "f" => config_file = Some(argument[2..].to_string()),
-                "h" => scan_hvsc = true,
-                "m" => scan_for_multiple = true,
-                "n" => show_player_info = true,
-                "o" => only_list_unidentified = true,
-                "p" => player_name = Some(argument[2..].to_string()),
-                "t" => truncate_filenames = true,
-                "s" => recursive = true,
-                "u" => list_unidentified = true,
-                "v" => verify_signatures = true,
-                "w" => convert_file_format = Some(argument[2..].to_string()),
-                "x" => display_hex_offset = true,
+                "c" => config.cpu_threads = Self::parse_argument_number("Max threads", &argument[2..])? as usize,
+                "f" => config.config_file = Some(argument[2..].to_string()),
+                "h" => config.scan_hvsc = true,
+                "i" => config.info_file = Some(argument[2..].to_string()),
+                "m" => config.scan_for_multiple = true,
+                "n" => commands.push(Command::Info),
+                "o" => config.only_list_unidentified = true,
+                "p" => config.player_name = Some(argument[2..].to_string()),
+                "t" => config.truncate_filenames = true,
+                "s" => config.recursive = true,
+                "u" => config.list_unidentified = true,
+                "v" => commands.push(Command::Verify),
+                "w" => commands.push(Self::parse_file_format(&argument[2..])?),
+                "x" => config.display_hex_offset = true,
                 _ => return Err(format!("Unknown option: {argument}"))
             }
         }
 
-        if cpu_threads > max_threads {
-            cpu_threads = max_threads;
+        commands.dedup();
+        config.command = match commands.pop() {
+            Some(command) if commands.is_empty() => command,
+            Some(_) => return Err("Only one command can be used at a time: scan, verify, convert or info.".to_string()),
+            None => Command::Scan
+        };
+
+        if config.cpu_threads > max_threads {
+            config.cpu_threads = max_threads;
         }
 
-        let (mut base_path, mut filename) = Self::get_filename_and_base_path();
+        Self::set_positional_argument(&mut config, arguments, explicit_command)?;
 
-        if scan_hvsc {
-            Self::set_hvsc_config(&mut recursive, &mut base_path, &mut filename)?;
+        if config.scan_hvsc {
+            Self::set_hvsc_config(&mut config.recursive, &mut config.base_path, &mut config.filename)?;
         }
 
-        if config_file.is_none() {
-            config_file = env::var("SIDIDCFG").ok();
+        if config.config_file.is_none() {
+            config.config_file = if config.ted {
+                Some(DEFAULT_TED_CONFIG_FILE_NAME.to_string())
+            } else {
+                env::var("SIDIDCFG").ok()
+            };
         }
 
-        if show_player_info {
-            Self::validate_player_info_option(show_player_info, player_name.as_ref())?;
+        if config.command == Command::Info {
+            Self::validate_player_info_option(config.player_name.as_ref())?;
+        } else if config.command == Command::DumpMasks {
+            Self::validate_dump_masks_option(config.player_name.as_ref())?;
+        } else if config.offset_histogram.is_some() {
+            Self::validate_offset_histogram_option(config.player_name.as_ref())?;
         } else {
-            Self::validate_player_name(player_name.as_ref())?;
-        }
-
-        Self::validate_file_format_option(&convert_file_format)?;
-
-        Ok(Config {
-            cpu_threads,
-            config_file,
-            display_hex_offset,
-            list_unidentified,
-            recursive,
-            scan_for_multiple,
-            scan_hvsc,
-            show_player_info,
-            truncate_filenames,
-            only_list_unidentified,
-            verify_signatures,
-            player_name,
-            base_path,
-            filename,
-            convert_file_format
-        })
-    }
-
-    fn validate_player_info_option(show_player_info: bool, player_name: Option<&String>) -> Result<(), String> {
-        if show_player_info && (player_name.is_none() || player_name.unwrap().is_empty()) {
+            Self::validate_player_name(config.player_name.as_ref())?;
+        }
+
+        Ok(config)
+    }
+
+    fn split_command(arguments: &[String]) -> Result<(Option<Command>, &[String]), String> {
+        let command = match arguments.first().map(String::as_str) {
+            Some("scan") => Command::Scan,
+            Some("verify") => Command::Verify,
+            Some("info") => Command::Info,
+            Some("list") => Command::List,
+            Some("convert") => {
+                let file_format = match arguments.get(1).map(String::as_str) {
+                    Some("new") => "n",
+                    Some("old") => "o",
+                    _ => return Err("Output format should be specified with: convert new or convert old".to_string())
+                };
+                return Ok((Some(Self::parse_file_format(file_format)?), &arguments[2..]));
+            },
+            _ => return Ok((None, arguments))
+        };
+        Ok((Some(command), &arguments[1..]))
+    }
+
+    fn set_positional_argument(config: &mut Config, arguments: &[String], explicit_command: bool) -> Result<(), String> {
+        let Some(argument) = arguments.last().filter(|argument| !argument.starts_with('-')) else {
+            return Ok(());
+        };
+
+        match config.command {
+            Command::Scan | Command::SuggestSignature => (config.base_path, config.filename) = Self::split_file_path(argument.trim()),
+            Command::Info if config.player_name.is_none() => config.player_name = Some(argument.to_string()),
+            _ if explicit_command => return Err(format!("Unexpected argument: {argument}")),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn parse_bucket_size(bucket_size: &str) -> Result<usize, String> {
+        if bucket_size.is_empty() {
+            return Ok(DEFAULT_HISTOGRAM_BUCKET_SIZE);
+        }
+
+        let bucket_size = if let Some(hex_value) = bucket_size.strip_prefix('$') {
+            usize::from_str_radix(hex_value, 16).ok()
+        } else {
+            bucket_size.parse::<usize>().ok()
+        };
+
+        match bucket_size {
+            Some(bucket_size) if bucket_size > 0 => Ok(bucket_size),
+            _ => Err("Bucket size must be a valid number higher than 0.".to_string())
+        }
+    }
+
+    fn parse_stride(stride: &str) -> Result<usize, String> {
+        match stride.parse::<usize>() {
+            Ok(stride) if stride > 1 => Ok(stride),
+            _ => Err("Stride must be a valid number higher than 1.".to_string())
+        }
+    }
+
+    fn parse_timeout(timeout: &str) -> Result<Duration, String> {
+        match timeout.parse::<u64>() {
+            Ok(timeout) => Ok(Duration::from_millis(timeout)),
+            _ => Err("Timeout must be a valid number of milliseconds.".to_string())
+        }
+    }
+
+    fn parse_max_memory(max_memory: &str) -> Result<usize, String> {
+        match max_memory.parse::<usize>().ok().and_then(|max_memory| max_memory.checked_mul(BYTES_PER_MEGABYTE)) {
+            Some(max_memory) if max_memory > 0 => Ok(max_memory),
+            _ => Err("Max memory must be a valid number of megabytes higher than 0.".to_string())
+        }
+    }
+
+    fn parse_skip_region(skip_region: &str) -> Result<Range<usize>, String> {
+        let skip_region = skip_region.split_once(':')
+            .and_then(|(start, end)| Some(Self::parse_offset(start)?..Self::parse_offset(end)?));
+
+        match skip_region {
+            Some(skip_region) if skip_region.start < skip_region.end => Ok(skip_region),
+            _ => Err("Skip region must be a start and end offset separated by a colon, with the start before the end.".to_string())
+        }
+    }
+
+    fn parse_offset(offset: &str) -> Option<usize> {
+        if let Some(hex_offset) = offset.strip_prefix('$') {
+            usize::from_str_radix(hex_offset, 16).ok()
+        } else {
+            offset.parse::<usize>().ok()
+        }
+    }
+
+    fn parse_length(length: &str) -> Result<usize, String> {
+        match length.parse::<usize>() {
+            Ok(length) if length > 0 => Ok(length),
+            _ => Err("Length must be a valid number higher than 0.".to_string())
+        }
+    }
+
+    fn parse_min_name_length(length: &str) -> Result<usize, String> {
+        match length.parse::<usize>() {
+            Ok(length) if length >= 3 => Ok(length),
+            _ => Err("Minimum name length must be a valid number of at least 3.".to_string())
+        }
+    }
+
+    fn parse_exec_command(exec_command: &str) -> Result<String, String> {
+        if exec_command.trim().is_empty() {
+            return Err("Command to execute is missing.".to_string());
+        }
+        Ok(exec_command.to_string())
+    }
+
+    fn parse_percentage(percentage: &str, default_percentage: usize) -> Result<usize, String> {
+        if percentage.is_empty() {
+            return Ok(default_percentage);
+        }
+
+        match percentage.trim_end_matches('%').parse::<usize>() {
+            Ok(percentage) if (1..=100).contains(&percentage) => Ok(percentage),
+            _ => Err("Percentage must be a number between 1 and 100.".to_string())
+        }
+    }
+
+    fn validate_offset_histogram_option(player_name: Option<&String>) -> Result<(), String> {
+        match player_name {
+            Some(name) if !name.is_empty() => Ok(()),
+            _ => Err("Offset histogram can only be used when -p option is provided with a player name.".to_string())
+        }
+    }
+
+    fn validate_dump_masks_option(player_name: Option<&String>) -> Result<(), String> {
+        match player_name {
+            Some(name) if !name.is_empty() => Ok(()),
+            _ => Err("Dumping masks can only be used when -p option is provided with a player name.".to_string())
+        }
+    }
+
+    fn parse_directory(option_name: &str, directory: &str) -> Result<String, String> {
+        if directory.is_empty() {
+            return Err(format!("Directory should be specified with --{option_name}={{dir}}"));
+        }
+        Ok(directory.to_string())
+    }
+
+    fn parse_bundle_file(bundle_file: &str) -> Result<String, String> {
+        if bundle_file.is_empty() {
+            return Err("Bundle file should be specified with --bundle={file}".to_string());
+        }
+        Ok(bundle_file.to_string())
+    }
+
+    fn parse_label(option_name: &str, label: &str) -> Result<String, String> {
+        if label.trim().is_empty() {
+            return Err(format!("Label should be specified with --{option_name}={{text}}"));
+        }
+        Ok(label.to_string())
+    }
+
+    fn parse_since_date(date: &str) -> Result<SystemTime, String> {
+        timestamp::parse_timestamp(date)
+            .ok_or_else(|| format!("Invalid date '{date}', date should be specified as YYYY-MM-DD or YYYY-MM-DD HH:MM:SS."))
+    }
+
+    fn get_modified_time(filename: &str) -> Result<SystemTime, String> {
+        fs::metadata(filename)
+            .and_then(|metadata| metadata.modified())
+            .map_err(|_| format!("Could not determine modification time of file: {filename}"))
+    }
+
+    fn parse_summary_format(summary_format: &str) -> Result<SummaryFormat, String> {
+        match summary_format {
+            "pretty" => Ok(SummaryFormat::Pretty),
+            "kv" => Ok(SummaryFormat::KeyValue),
+            _ => Err("Summary format should be specified with --summary-format=pretty or --summary-format=kv".to_string())
+        }
+    }
+
+    fn parse_line_ending(line_ending: &str) -> Result<LineEnding, String> {
+        match line_ending {
+            "lf" => Ok(LineEnding::Lf),
+            "crlf" => Ok(LineEnding::CrLf),
+            "auto" => Ok(LineEnding::Auto),
+            _ => Err("Line ending should be specified with --line-ending=lf, --line-ending=crlf or --line-ending=auto".to_string())
+        }
+    }
+
+    fn split_long_option(long_option: &str) -> (&str, &str) {
+        long_option.split_once('=').unwrap_or((long_option, ""))
+    }
+
+    fn parse_info_tags(info_tags: &str) -> Result<Vec<String>, String> {
+        let tags = info_tags.split(',')
+            .map(|tag| tag.trim().trim_end_matches(':').to_ascii_uppercase())
+            .collect::<Vec<String>>();
+
+        if tags.iter().any(|tag| tag.is_empty() || tag.len() > MAX_INFO_TAG_LENGTH || tag.contains(' ')) {
+            return Err(format!("Info tags should be separated by a comma and be 1 to {MAX_INFO_TAG_LENGTH} characters long without spaces."));
+        }
+        Ok(tags)
+    }
+
+    fn validate_player_info_option(player_name: Option<&String>) -> Result<(), String> {
+        if player_name.is_none() || player_name.unwrap().is_empty() {
             return Err("Player info can only be used when -p option is provided with a player name.".to_string());
         }
         Ok(())
@@ -112,9 +554,10 @@ impl Config {
         }
     }
 
-    fn validate_file_format_option(file_format: &Option<String>) -> Result<(), String> {
-        match file_format.as_deref() {
-            None | Some("o") | Some("n") => Ok(()),
+    fn parse_file_format(file_format: &str) -> Result<Command, String> {
+        match file_format {
+            "o" => Ok(Command::Convert { new_format: false }),
+            "n" => Ok(Command::Convert { new_format: true }),
             _ => Err("Output format should be specified with -wo for old format or -wn for new format".to_string())
         }
     }
@@ -132,15 +575,6 @@ impl Config {
         Ok(())
     }
 
-    fn get_filename_and_base_path() -> (String, String) {
-        let filename = env::args().last().unwrap();
-        if !filename.starts_with('-') {
-            Self::split_file_path(filename.trim())
-        } else {
-            ("".to_string(), "".to_string())
-        }
-    }
-
     fn parse_argument_number(arg_name: &str, arg_value: &str) -> Result<u32, String> {
         arg_value.parse::<u32>()
             .map_err(|_| format!("{arg_name} must be a valid number."))
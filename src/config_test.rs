@@ -98,3 +98,142 @@ fn split_file_path_dot() {
     assert_eq!(base_path, ".".to_string());
     assert_eq!(file, ".".to_string());
 }
+
+#[test]
+fn parse_info_tags_custom() {
+    let tags = Config::parse_info_tags("NAME,version:, Source").unwrap();
+
+    assert_eq!(tags, vec!["NAME".to_string(), "VERSION".to_string(), "SOURCE".to_string()]);
+}
+
+#[test]
+fn parse_info_tags_too_long() {
+    assert!(Config::parse_info_tags("NAME,DESCRIPTION").is_err());
+}
+
+fn parse(arguments: &[&str]) -> Result<Config, String> {
+    Config::parse(&arguments.iter().map(|argument| argument.to_string()).collect::<Vec<String>>())
+}
+
+#[test]
+fn parse_command_default_scan() {
+    let config = parse(&["-s", "test/*.sid"]).unwrap();
+
+    assert_eq!(config.command, Command::Scan);
+    assert!(config.recursive);
+    assert_eq!(config.base_path, "test".to_string());
+    assert_eq!(config.filename, "*.sid".to_string());
+}
+
+#[test]
+fn parse_command_scan() {
+    let config = parse(&["scan", "-m", "*.sid"]).unwrap();
+
+    assert_eq!(config.command, Command::Scan);
+    assert!(config.scan_for_multiple);
+    assert_eq!(config.filename, "*.sid".to_string());
+}
+
+#[test]
+fn parse_command_verify() {
+    assert_eq!(parse(&["verify"]).unwrap().command, Command::Verify);
+    assert_eq!(parse(&["-v"]).unwrap().command, Command::Verify);
+}
+
+#[test]
+fn parse_command_convert() {
+    assert_eq!(parse(&["convert", "new"]).unwrap().command, Command::Convert { new_format: true });
+    assert_eq!(parse(&["convert", "old"]).unwrap().command, Command::Convert { new_format: false });
+    assert_eq!(parse(&["-wn"]).unwrap().command, Command::Convert { new_format: true });
+    assert!(parse(&["convert"]).is_err());
+    assert!(parse(&["-wx"]).is_err());
+}
+
+#[test]
+fn parse_command_info() {
+    let config = parse(&["info", "SoundMonitor"]).unwrap();
+
+    assert_eq!(config.command, Command::Info);
+    assert_eq!(config.player_name, Some("SoundMonitor".to_string()));
+
+    let config = parse(&["-n", "-pSoundMonitor"]).unwrap();
+
+    assert_eq!(config.command, Command::Info);
+    assert_eq!(config.player_name, Some("SoundMonitor".to_string()));
+
+    assert!(parse(&["info"]).is_err());
+}
+
+#[test]
+fn parse_command_list() {
+    assert_eq!(parse(&["list"]).unwrap().command, Command::List);
+    assert!(parse(&["list", "unexpected"]).is_err());
+}
+
+#[test]
+fn parse_command_conflicting_commands() {
+    assert!(parse(&["-v", "-n", "-pSoundMonitor"]).is_err());
+    assert!(parse(&["list", "-v"]).is_err());
+    assert_eq!(parse(&["verify", "-v"]).unwrap().command, Command::Verify);
+}
+
+#[test]
+fn parse_info_file() {
+    assert_eq!(parse(&["info", "-icustom.nfo", "SoundMonitor"]).unwrap().info_file, Some("custom.nfo".to_string()));
+    assert_eq!(parse(&["verify", "--info-file=custom.nfo"]).unwrap().info_file, Some("custom.nfo".to_string()));
+    assert_eq!(parse(&["verify"]).unwrap().info_file, None);
+}
+
+#[test]
+fn parse_ted_config_file() {
+    let config = parse(&["--ted", "*.prg"]).unwrap();
+    assert!(config.ted);
+    assert_eq!(config.config_file, Some("tedid.cfg".to_string()));
+
+    assert_eq!(parse(&["--ted", "-fcustom.cfg", "*.prg"]).unwrap().config_file, Some("custom.cfg".to_string()));
+}
+
+#[test]
+fn parse_skip_regions() {
+    let config = parse(&["--skip-region=$7C:$100", "--skip-region=16:32", "*.sid"]).unwrap();
+    assert_eq!(config.skip_regions, vec![0x7C..0x100, 16..32]);
+
+    assert!(parse(&["--skip-region=$100:$7C", "*.sid"]).is_err());
+    assert!(parse(&["--skip-region=$7C", "*.sid"]).is_err());
+}
+
+#[test]
+fn parse_max_memory() {
+    assert_eq!(parse(&["--max-memory=64", "*.sid"]).unwrap().max_memory, Some(64 * 1024 * 1024));
+
+    assert!(parse(&["--max-memory=0", "*.sid"]).is_err());
+    assert!(parse(&["--max-memory=lots", "*.sid"]).is_err());
+}
+
+#[test]
+fn parse_repeat() {
+    assert_eq!(parse(&["--repeat=3", "*.sid"]).unwrap().repeat, Some(3));
+
+    assert!(parse(&["--repeat=0", "*.sid"]).is_err());
+}
+
+#[test]
+fn parse_labels() {
+    let config = parse(&["--unidentified-label=UNKNOWN", "--skipped-label=SKIPPED", "*.sid"]).unwrap();
+    assert_eq!(config.unidentified_label.as_deref(), Some("UNKNOWN"));
+    assert_eq!(config.skipped_label.as_deref(), Some("SKIPPED"));
+
+    assert!(parse(&["--unidentified-label=", "*.sid"]).is_err());
+}
+
+#[test]
+fn parse_examples() {
+    for (_, command_line) in EXAMPLES {
+        let arguments = command_line.split_ascii_whitespace().skip(1).map(|argument| argument.to_string()).collect::<Vec<String>>();
+
+        match Config::parse(&arguments) {
+            Ok(_) => {},
+            Err(message) => assert_eq!(message, "HVSC environment variable not found.", "{command_line}")
+        }
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+//! C compatible interface for identifying players in memory buffers.
+//!
+//! A scanner is created from the content of a config file with `player_id_scanner_new`. Buffers
+//! can then be scanned with `player_id_scan`, which returns the matched player names and the
+//! offsets of the signatures found. Every object returned by this interface is owned by the
+//! library and must be released with the corresponding free function.
+
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+use std::slice;
+
+use crate::player_id::{ConfigSyntax, PlayerId, SignatureConfig};
+
+pub struct PlayerIdScanner {
+    signature_ids: Vec<SignatureConfig>
+}
+
+#[repr(C)]
+pub struct PlayerIdMatch {
+    pub name: *mut c_char,
+    pub offsets: *mut usize,
+    pub offsets_len: usize
+}
+
+#[repr(C)]
+pub struct PlayerIdResults {
+    pub matches: *mut PlayerIdMatch,
+    pub matches_len: usize
+}
+
+/// Creates a scanner from the content of a config file.
+///
+/// Returns a null pointer when the config is not valid or doesn't contain any signature.
+///
+/// # Safety
+///
+/// `config` must be a valid pointer to a null-terminated UTF-8 string. The returned scanner must
+/// be released with `player_id_scanner_free`.
+#[no_mangle]
+pub unsafe extern "C" fn player_id_scanner_new(config: *const c_char) -> *mut PlayerIdScanner {
+    if config.is_null() {
+        return ptr::null_mut();
+    }
+
+    let Ok(config) = CStr::from_ptr(config).to_str() else {
+        return ptr::null_mut();
+    };
+
+    let lines = config.lines().map(|line| line.to_string()).collect::<Vec<String>>();
+    match PlayerId::load_config_lines(&lines, None, ConfigSyntax::default()) {
+        Ok(signature_ids) => Box::into_raw(Box::new(PlayerIdScanner { signature_ids })),
+        Err(_) => ptr::null_mut()
+    }
+}
+
+/// Scans a buffer for players and returns the matches found.
+///
+/// Returns a null pointer when the scanner is null. The buffer is scanned from the first byte,
+/// SID or PRG headers are not skipped.
+///
+/// # Safety
+///
+/// `scanner` must be a pointer returned by `player_id_scanner_new` and `buffer` must point to at
+/// least `buffer_len` readable bytes, or be null when `buffer_len` is 0. The returned results must
+/// be released with `player_id_results_free`.
+#[no_mangle]
+pub unsafe extern "C" fn player_id_scan(scanner: *const PlayerIdScanner, buffer: *const u8, buffer_len: usize, scan_for_multiple: bool) -> *mut PlayerIdResults {
+    if scanner.is_null() || (buffer.is_null() && buffer_len > 0) {
+        return ptr::null_mut();
+    }
+
+    let buffer = if buffer_len > 0 { slice::from_raw_parts(buffer, buffer_len) } else { &[] };
+    let matches = PlayerId::find_players_in_buffer(buffer, &(*scanner).signature_ids, scan_for_multiple);
+
+    let matches = matches.into_iter()
+        .map(|signature_match| {
+            let offsets_len = signature_match.indexes.len();
+            PlayerIdMatch {
+                name: CString::new(signature_match.signature_name.as_bytes()).unwrap_or_default().into_raw(),
+                offsets: Box::into_raw(signature_match.indexes.into_boxed_slice()) as *mut usize,
+                offsets_len
+            }
+        })
+        .collect::<Vec<PlayerIdMatch>>()
+        .into_boxed_slice();
+
+    let matches_len = matches.len();
+    Box::into_raw(Box::new(PlayerIdResults { matches: Box::into_raw(matches) as *mut PlayerIdMatch, matches_len }))
+}
+
+/// Releases results returned by `player_id_scan`.
+///
+/// # Safety
+///
+/// `results` must be a pointer returned by `player_id_scan` or null, and must not be used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn player_id_results_free(results: *mut PlayerIdResults) {
+    if results.is_null() {
+        return;
+    }
+
+    let results = Box::from_raw(results);
+    let matches = Box::from_raw(ptr::slice_from_raw_parts_mut(results.matches, results.matches_len));
+
+    for signature_match in matches.iter() {
+        drop(CString::from_raw(signature_match.name));
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(signature_match.offsets, signature_match.offsets_len)));
+    }
+}
+
+/// Releases a scanner created by `player_id_scanner_new`.
+///
+/// # Safety
+///
+/// `scanner` must be a pointer returned by `player_id_scanner_new` or null, and must not be used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn player_id_scanner_free(scanner: *mut PlayerIdScanner) {
+    if !scanner.is_null() {
+        drop(Box::from_raw(scanner));
+    }
+}
+
+#[cfg(test)]
+#[path = "./ffi_test.rs"]
+mod ffi_test;
@@ -0,0 +1,50 @@
+use super::*;
+
+const CONFIG: &str = "Test_Player\nA9 00 8D ?? D4\n\nOther_Player\nA2 ?? BD && 9D 00 D4\n";
+
+fn scan(scanner: *const PlayerIdScanner, buffer: &[u8], scan_for_multiple: bool) -> Vec<(String, Vec<usize>)> {
+    unsafe {
+        let results = player_id_scan(scanner, buffer.as_ptr(), buffer.len(), scan_for_multiple);
+        assert!(!results.is_null());
+
+        let matches = slice::from_raw_parts((*results).matches, (*results).matches_len).iter()
+            .map(|signature_match| (
+                CStr::from_ptr(signature_match.name).to_str().unwrap().to_string(),
+                slice::from_raw_parts(signature_match.offsets, signature_match.offsets_len).to_vec()
+            ))
+            .collect();
+
+        player_id_results_free(results);
+        matches
+    }
+}
+
+#[test]
+fn scan_buffer_round_trip() {
+    let config = CString::new(CONFIG).unwrap();
+    let buffer = [0x00, 0xA2, 0x01, 0xBD, 0xA9, 0x00, 0x8D, 0x05, 0xD4, 0x9D, 0x00, 0xD4];
+
+    unsafe {
+        let scanner = player_id_scanner_new(config.as_ptr());
+        assert!(!scanner.is_null());
+
+        assert_eq!(scan(scanner, &buffer, false), vec![("Test_Player".to_string(), vec![4])]);
+        assert_eq!(scan(scanner, &buffer, true), vec![
+            ("Test_Player".to_string(), vec![4]),
+            ("Other_Player".to_string(), vec![1, 9])
+        ]);
+        assert!(scan(scanner, &[], true).is_empty());
+
+        player_id_scanner_free(scanner);
+    }
+}
+
+#[test]
+fn scanner_with_invalid_config() {
+    let config = CString::new("not a config").unwrap();
+
+    unsafe {
+        assert!(player_id_scanner_new(config.as_ptr()).is_null());
+        assert!(player_id_scanner_new(ptr::null()).is_null());
+    }
+}
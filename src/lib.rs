@@ -0,0 +1,17 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+mod player_id;
+#[path = "./utils/lha_file.rs"] mod lha_file;
+#[path = "./utils/prg_dump.rs"] mod prg_dump;
+#[path = "./utils/sid_file.rs"] mod sid_file;
+#[path = "./utils/vsf_file.rs"] mod vsf_file;
+#[doc(hidden)]
+#[path = "./utils/log.rs"] pub mod log;
+#[cfg(test)]
+#[path = "./utils/fixture.rs"] mod fixture;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+pub use player_id::{Confidence, ConfigSyntax, FileMatches, FileType, LineEnding, PlayerId, PlayerInfo, ScanStart, Scanner, SignatureConfig, SignatureExpectation, SignatureInfo, SignatureMatch, DEFAULT_INFO_TAGS};
@@ -2,22 +2,36 @@
 // Licensed under the MIT license. See the LICENSE file for the terms and conditions.
 
 mod config;
-mod player_id;
 #[path = "./utils/hvsc.rs"] mod hvsc;
-#[path = "./utils/sid_file.rs"] mod sid_file;
+#[path = "./utils/songlengths.rs"] mod songlengths;
+#[path = "./utils/timestamp.rs"] mod timestamp;
+#[cfg(test)]
+#[path = "./utils/fixture.rs"] mod fixture;
 
-use self::config::Config;
-use self::player_id::{PlayerId, SignatureConfig, SignatureMatch};
+use self::config::{Command, Config, SummaryFormat, EXAMPLES};
+use player_id::{log_error, log_info, Confidence, ConfigSyntax, FileMatches, FileType, PlayerId, PlayerInfo, ScanStart, Scanner, SignatureConfig, SignatureInfo, SignatureMatch};
 
-use std::cmp::min;
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::env;
-use std::process::exit;
-use std::time::Instant;
+use std::fs;
+use std::path::{self, Path, PathBuf};
+use std::process::{self, exit};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime};
 
 use rayon::prelude::*;
 
 const DEFAULT_FILENAME_COL_WIDTH: usize = 56;
+const HISTOGRAM_BAR_WIDTH: usize = 40;
+const HINT_SAMPLE_SIZE: usize = 100;
+const UNIDENTIFIED_PLAYER_NAME: &str = "(unidentified)";
+const UNIDENTIFIED_LABEL: &str = ">> UNIDENTIFIED <<";
+const LIKELY_SID_PLAYER_LABEL: &str = ">> likely SID player (unidentified) <<";
+const TIMEOUT_LABEL: &str = ">> TIMEOUT <<";
+const EMPTY_LABEL: &str = ">> EMPTY <<";
+const SUGGESTED_SIGNATURE_NAME: &str = "Suggested_Player";
+const ALL_FILES_PATTERN: &str = "**";
 
 fn main() {
     if env::args().count() <= 1 {
@@ -34,106 +48,190 @@ fn main() {
     }
 }
 
-pub struct FileMatches {
-    pub matches: Vec<SignatureMatch>,
-    pub filename: String,
-}
-
 fn run() -> Result<(), String> {
     let config = Config::read()?;
+    player_id::log::set_silent(config.silent);
 
-    if config.verify_signatures {
-        PlayerId::verify_signatures(config.config_file.as_ref())?;
-        PlayerId::verify_signature_info(config.config_file.as_ref())?;
-        return Ok(());
+    match config.command {
+        Command::Scan => scan(&config),
+        Command::Verify => {
+            PlayerId::verify_signatures_and_info(config.config_file.as_ref(), config.info_file.as_ref(), config.info_tags.as_ref(), config.config_syntax)?;
+            Ok(())
+        },
+        Command::Convert { new_format } => PlayerId::convert_file_format(config.config_file.as_ref(), new_format, config.line_ending, config.convert_report, config.config_syntax),
+        Command::Info => display_player_info(&config),
+        Command::List => list_players(&config),
+        Command::ListInfo => list_player_info(&config),
+        Command::Which => display_config_paths(&config),
+        Command::DumpMasks => dump_masks(&config),
+        Command::DumpParsed => dump_parsed_signatures(&config),
+        Command::ConfigStats => display_config_stats(&config),
+        Command::Examples => {
+            print_examples();
+            Ok(())
+        },
+        Command::SortConfig => PlayerId::sort_config_file(config.config_file.as_ref(), config.line_ending, config.config_syntax),
+        Command::SuggestSignature => suggest_signature(&config),
+        Command::TestConfig => test_config(&config),
+        Command::Bundle => PlayerId::write_bundle(config.config_file.as_ref(), config.info_file.as_ref(), Path::new(config.bundle_file.as_ref().unwrap()), config.line_ending, config.config_syntax),
+        Command::FalsePositiveCheck => check_false_positives(&config)
     }
+}
 
-    if config.show_player_info {
-        display_player_info(&config)?;
-        return Ok(());
+fn scan(config: &Config) -> Result<(), String> {
+    if config.scan_hvsc {
+        log_info!("Scanning HVSC location: {}\r", config.base_path);
     }
 
-    if let Some(convert_file_format) = config.convert_file_format {
-        PlayerId::convert_file_format(config.config_file.as_ref(), convert_file_format.eq("n"))?;
-        return Ok(());
+    log_info!("Processing...\r");
+
+    let start_time = Instant::now();
+
+    if let Some(configs_directory) = &config.try_configs {
+        return scan_with_configs(config, Path::new(configs_directory), start_time);
     }
 
-    if config.scan_hvsc {
-        eprintln!("Scanning HVSC location: {}\r", config.base_path);
+    let scanner = get_scanner(config);
+    let mut signature_ids = load_signatures(config)?;
+    if config.any_order {
+        set_any_order(&mut signature_ids);
+    }
+    if config.relocatable {
+        PlayerId::mask_absolute_addresses(&mut signature_ids);
     }
 
-    eprintln!("Processing...\r");
+    let files = get_matched_filenames(config);
+    let files = if config.absolute_paths { to_absolute_paths(files) } else { files };
 
-    let start_time = Instant::now();
+    if files.is_empty() {
+        log_info!("{}\r", get_no_files_found_message(config));
+        return Ok(());
+    }
 
-    let signature_ids = load_signatures(&config)?;
-    let files = get_matched_filenames(&config);
+    if let Some(repeat) = config.repeat {
+        repeat_scan(config, &files, &signature_ids, &scanner, repeat);
+        return Ok(());
+    }
 
-    if files.is_empty() {
-        eprintln!("No file(s) found.\r");
+    if config.concatenated {
+        scan_concatenated_files(config, &files, &signature_ids)?;
+        output_elapsed_time(start_time);
         return Ok(());
     }
 
     let mut identified_players = 0;
     let mut identified_files = 0;
     let mut unidentified_files = 0;
+    let mut unique_tunes = None;
+    let mut file_type_totals = None;
+
+    let song_lengths = if config.show_song_lengths { Some(load_song_lengths(&files)?) } else { None };
 
     let pool = rayon::ThreadPoolBuilder::new().num_threads(config.cpu_threads).build().unwrap();
+
+    if let Some(bucket_size) = config.offset_histogram {
+        let matches = pool.install(|| scan_files(&files, &signature_ids, false, false, false, &scanner));
+        let offsets = matches.iter().filter_map(|file_matches| get_first_offset(&file_matches.matches)).collect::<Vec<usize>>();
+
+        println!("{}", format_offset_histogram(config.player_name.as_ref().unwrap(), bucket_size, &offsets));
+        output_elapsed_time(start_time);
+        return Ok(());
+    }
+
+    if config.name_only {
+        let mut matches = pool.install(|| scan_files(&files, &signature_ids, config.scan_for_multiple, config.heuristic, false, &scanner));
+        for file_matches in &mut matches {
+            sort_by_specificity(&mut file_matches.matches);
+            if let Some(line) = get_name_only_line(file_matches, config) {
+                println!("{line}\r");
+            }
+        }
+        return Ok(());
+    }
+
+    let digest = config.unique_tunes || song_lengths.is_some();
     pool.install(|| {
-        let matches: Vec<FileMatches> = files
-            .par_iter()
-            .filter_map(|filename| {
-                let matches = PlayerId::find_players_in_file(filename, &signature_ids, config.scan_for_multiple);
-
-                ((matches.is_empty() && (config.only_list_unidentified || config.list_unidentified)) ||
-                (!matches.is_empty() && !config.only_list_unidentified)).then_some(FileMatches {
-                    matches,
-                    filename: filename.to_owned(),
-                })
+        let matches = if config.fail_fast {
+            scan_files_until_unidentified(&files, &signature_ids, config.scan_for_multiple, config.heuristic, digest, &scanner)?
+        } else {
+            scan_files(&files, &signature_ids, config.scan_for_multiple, config.heuristic, digest, &scanner)
+        };
+
+        if config.unique_tunes {
+            unique_tunes = Some(count_unique_tunes(&matches));
+        }
+        if config.file_types {
+            let file_types = matches.par_iter().map(|file_matches| (PlayerId::get_file_type(&file_matches.filename), !file_matches.matches.is_empty())).collect::<Vec<(FileType, bool)>>();
+            file_type_totals = Some(format_file_type_totals(&file_types));
+        }
+
+        let mut matches: Vec<FileMatches> = matches
+            .into_iter()
+            .filter(|file_matches| {
+                file_matches.timed_out || file_matches.empty ||
+                (file_matches.matches.is_empty() && (config.only_list_unidentified || config.list_unidentified)) ||
+                (!file_matches.matches.is_empty() && !config.only_list_unidentified)
             })
             .collect();
 
-        let filename_strip_length = get_filename_strip_length(&config.base_path, &files);
+        if config.scan_for_multiple {
+            for file_matches in &mut matches {
+                sort_by_specificity(&mut file_matches.matches);
+            }
+        }
+
+        let filename_strip_length = if config.absolute_paths { 0 } else { get_filename_strip_length(&config.base_path, &files) };
         let filename_width = calculate_filename_width(config.truncate_filenames, &matches, filename_strip_length);
 
         for file_matches in &matches {
-            let filename = if file_matches.filename.len() > filename_strip_length {
-                &file_matches.filename[filename_strip_length..]
+            let filename = get_display_filename(&file_matches.filename, filename_strip_length);
+            let filename = if config.truncate_filenames {
+                filename.chars().take(filename_width).collect()
             } else {
-                &file_matches.filename
+                filename
             };
 
-            let filename_size = if config.truncate_filenames {
-                min(filename.len(), filename_width)
+            let filename = if config.show_song_count {
+                let song_count = PlayerId::get_song_count(&file_matches.filename).map_or("-".to_string(), |song_count| song_count.to_string());
+                format!("{:<0width$} {:>5}", filename, song_count, width = filename_width)
             } else {
-                filename.len()
+                format!("{:<0width$}", filename, width = filename_width)
             };
 
-            if file_matches.matches.is_empty() {
-                unidentified_files += 1;
+            let load_address = if config.both_offsets {
+                PlayerId::get_load_address(&file_matches.filename, get_scan_start(config))
+            } else {
+                None
+            };
 
-                println!("{:<0width$} >> UNIDENTIFIED <<\r", filename[..filename_size].replace('\\', "/"), width = filename_width);
+            if file_matches.timed_out {
+                println!("{} {}\r", filename, config.skipped_label.as_deref().unwrap_or(TIMEOUT_LABEL));
+            } else if file_matches.empty {
+                println!("{} {}\r", filename, config.skipped_label.as_deref().unwrap_or(EMPTY_LABEL));
+            } else if file_matches.matches.is_empty() {
+                println!("{} {}\r", filename, get_unidentified_label(config, file_matches.likely_sid_player));
             } else {
                 identified_files += 1;
                 identified_players += file_matches.matches.len();
 
-                for (index, player) in file_matches.matches.iter().enumerate() {
-                    let player_name = if config.display_hex_offset {
-                        let player_indexes = player.indexes.iter().map(|index| format!("${index:04X}")).collect::<Vec<String>>();
-                        format!("{} {}", player.signature_name, player_indexes.join(" "))
-                    } else {
-                        player.signature_name.to_string()
-                    };
-
-                    if index == 0 {
-                        println!("{:<0width$} {}\r", filename[..filename_size].replace('\\', "/"), player_name, width = filename_width);
-                    } else {
-                        println!("{:<0width$} {}\r", "", player_name, width = filename_width);
-                    }
+                let mut player_names = file_matches.matches.iter()
+                    .map(|player| format_player_name(player, config, load_address))
+                    .collect::<Vec<String>>();
+
+                if let Some(song_lengths) = song_lengths.as_ref().and_then(|song_lengths| get_song_lengths(song_lengths, file_matches)) {
+                    player_names[0] = format!("{} [{song_lengths}]", player_names[0]);
+                }
+
+                for line in format_player_lines(&filename, &player_names, config.flat) {
+                    println!("{line}\r");
                 }
             }
         }
 
+        if let Some(exec_command) = &config.exec_command {
+            run_exec_command(exec_command, &matches);
+        }
+
         if identified_files == 0 {
             unidentified_files = matches.len();
         } else {
@@ -141,85 +239,752 @@ fn run() -> Result<(), String> {
         }
 
         if identified_files > 0 {
-            output_occurrence_statistics(&signature_ids, &matches);
+            let unidentified_row = config.stats_include_unidentified.then_some(unidentified_files);
+            println!("{}", format_occurrence_statistics(&signature_ids, &matches, unidentified_row));
+
+            if config.per_player_files {
+                println!("{}", format_per_player_files(&signature_ids, &matches, filename_strip_length));
+            }
         }
-    });
 
-    println!("\r\nSummary:\r");
-    println!("Identified players    {identified_players:>9}\r");
-    println!("Identified files      {identified_files:>9}\r");
-    println!("Unidentified files    {unidentified_files:>9}\r");
-    println!("Total files processed {:>9}\r", files.len());
+        if let Some(conflict_percentage) = config.conflict_percentage {
+            output_conflicts(&matches, conflict_percentage);
+        }
+
+        if config.show_hint && !config.scan_for_multiple {
+            let sampled_matches = scan_files(&get_hint_sample(&matches), &signature_ids, true, false, false, &scanner);
+            if let Some(hint) = get_multiple_match_hint(&sampled_matches) {
+                log_info!("\r\n{hint}\r");
+            }
+        }
+        Ok::<(), String>(())
+    })?;
+
+    if let Some(file_type_totals) = file_type_totals {
+        println!("{file_type_totals}");
+    }
+
+    println!("\r\n{}", format_summary(&config.summary_format, identified_players, identified_files, unidentified_files, files.len(), unique_tunes));
+
+    output_elapsed_time(start_time);
+    Ok(())
+}
+
+fn scan_with_configs(config: &Config, configs_directory: &Path, start_time: Instant) -> Result<(), String> {
+    let mut configs = load_configs_in_directory(configs_directory, config.config_syntax)?;
+    if config.any_order {
+        configs.iter_mut().for_each(|(_, signature_ids)| set_any_order(signature_ids));
+    }
+    if config.relocatable {
+        configs.iter_mut().for_each(|(_, signature_ids)| PlayerId::mask_absolute_addresses(signature_ids));
+    }
+    let files = get_matched_filenames(config);
+    let files = if config.absolute_paths { to_absolute_paths(files) } else { files };
+
+    if files.is_empty() {
+        log_info!("{}\r", get_no_files_found_message(config));
+        return Ok(());
+    }
+
+    let scanner = get_scanner(config);
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(config.cpu_threads).build().unwrap();
+    let matches_per_config = pool.install(|| find_matches_per_config(&files, &configs, config.scan_for_multiple, &scanner));
+    let likely_sid_players = pool.install(|| files.par_iter().zip(&matches_per_config)
+        .map(|(path, config_matches)| config.heuristic && config_matches.is_empty() && PlayerId::is_likely_sid_player(path))
+        .collect::<Vec<bool>>());
+
+    let filename_strip_length = if config.absolute_paths { 0 } else { get_filename_strip_length(&config.base_path, &files) };
+    let mut identified_files_per_config: HashMap<&str, usize> = HashMap::new();
+
+    for ((path, config_matches), likely_sid_player) in files.iter().zip(&matches_per_config).zip(likely_sid_players) {
+        let filename = format!("{:<0width$}", get_display_filename(path, filename_strip_length), width = DEFAULT_FILENAME_COL_WIDTH);
+
+        if config_matches.is_empty() {
+            if config.list_unidentified || config.only_list_unidentified {
+                println!("{} {}\r", filename, get_unidentified_label(config, likely_sid_player));
+            }
+            continue;
+        }
+
+        for (index, (config_name, matches)) in config_matches.iter().enumerate() {
+            *identified_files_per_config.entry(config_name).or_insert(0) += 1;
+
+            if config.only_list_unidentified {
+                continue;
+            }
+
+            let player_names = matches.iter().map(|player| player.signature_name.as_ref()).collect::<Vec<&str>>().join(", ");
+            if index == 0 {
+                println!("{} {}: {}\r", filename, config_name, player_names);
+            } else {
+                println!("{:<0width$} {}: {}\r", "", config_name, player_names, width = filename.chars().count());
+            }
+        }
+    }
+
+    println!("\r\nIdentified by config      Count\r");
+    println!("-------------------------------\r");
+    for (config_name, _) in &configs {
+        println!("{:<24} {:>6}\r", config_name, identified_files_per_config.get(config_name.as_str()).unwrap_or(&0));
+    }
 
     output_elapsed_time(start_time);
     Ok(())
 }
 
+/// Returns the label of a file in which no player is identified, which with the heuristic enabled tells apart the
+/// files that write to the SID chip like a player does.
+fn get_unidentified_label(config: &Config, likely_sid_player: bool) -> &str {
+    if likely_sid_player {
+        LIKELY_SID_PLAYER_LABEL
+    } else {
+        config.unidentified_label.as_deref().unwrap_or(UNIDENTIFIED_LABEL)
+    }
+}
+
+/// Scans the files the given number of times with the same signatures to measure the throughput, and writes the time
+/// of each scan and the average time to stderr. The first scan is left out of the average when there is more than one,
+/// as it includes reading the files from disk instead of from the cache of the operating system.
+fn repeat_scan(config: &Config, files: &[PathBuf], signature_ids: &Vec<SignatureConfig>, scanner: &Scanner, repeat: usize) {
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(config.cpu_threads).build().unwrap();
+
+    let mut scan_times = vec![];
+    for iteration in 1..=repeat {
+        let start_time = Instant::now();
+        let matches = pool.install(|| scan_files(files, signature_ids, config.scan_for_multiple, false, false, scanner));
+        let scan_time = start_time.elapsed();
+
+        let identified_files = matches.iter().filter(|file_matches| !file_matches.matches.is_empty()).count();
+        eprintln!("Iteration {iteration}: {identified_files} of {} files identified in {} ms\r", files.len(), scan_time.as_millis());
+        scan_times.push(scan_time);
+    }
+
+    let warm_scan_times = if scan_times.len() > 1 { &scan_times[1..] } else { &scan_times[..] };
+    let average_time = warm_scan_times.iter().sum::<Duration>() / warm_scan_times.len() as u32;
+    eprintln!("Average time: {} ms\r", average_time.as_millis());
+}
+
+/// Scans each file as a dump of concatenated programs and shows the players per program. The programs are counted as
+/// files in the summary, and an error is returned when none of the files is a valid dump.
+fn scan_concatenated_files(config: &Config, files: &[PathBuf], signature_ids: &Vec<SignatureConfig>) -> Result<(), String> {
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(config.cpu_threads).build().unwrap();
+    let matches_per_file = pool.install(|| {
+        files.par_iter()
+            .map(|filename| {
+                let data = fs::read(filename).map_err(|error| format!("Could not read file: {error}"))?;
+                PlayerId::find_players_in_dump(&data, signature_ids, config.scan_for_multiple)
+            })
+            .collect::<Vec<Result<Vec<Vec<SignatureMatch>>, String>>>()
+    });
+
+    let mut identified_players = 0;
+    let mut identified_segments = 0;
+    let mut total_segments = 0;
+    let mut invalid_files = 0;
+
+    let filename_strip_length = if config.absolute_paths { 0 } else { get_filename_strip_length(&config.base_path, files) };
+    for (filename, matches_per_segment) in files.iter().zip(matches_per_file) {
+        let filename = get_display_filename(filename, filename_strip_length);
+        let matches_per_segment = match matches_per_segment {
+            Ok(matches_per_segment) => matches_per_segment,
+            Err(message) => {
+                log_error!("{filename}: {message}\r");
+                invalid_files += 1;
+                continue;
+            }
+        };
+
+        total_segments += matches_per_segment.len();
+        for (index, matches) in matches_per_segment.iter().enumerate() {
+            let segment_name = format!("{:<0width$}", format!("{filename}#{}", index + 1), width = DEFAULT_FILENAME_COL_WIDTH);
+            if matches.is_empty() {
+                if config.list_unidentified || config.only_list_unidentified {
+                    println!("{} {}\r", segment_name, config.unidentified_label.as_deref().unwrap_or(UNIDENTIFIED_LABEL));
+                }
+            } else {
+                identified_segments += 1;
+                identified_players += matches.len();
+                if !config.only_list_unidentified {
+                    let player_names = matches.iter().map(|player| format_player_name(player, config, None)).collect::<Vec<String>>();
+                    println!("{} {}\r", segment_name, player_names.join(", "));
+                }
+            }
+        }
+    }
+
+    if invalid_files == files.len() {
+        return Err("None of the files is a dump of concatenated programs.".to_string());
+    }
+
+    println!("\r\n{}", format_summary(&config.summary_format, identified_players, identified_segments, total_segments - identified_segments, total_segments, None));
+    Ok(())
+}
+
+/// Lets all signatures match when their sub signatures are found in any order.
+fn set_any_order(signature_ids: &mut [SignatureConfig]) {
+    signature_ids.iter_mut().for_each(|signature| signature.any_order = true);
+}
+
+fn load_configs_in_directory(configs_directory: &Path, config_syntax: ConfigSyntax) -> Result<Vec<(String, Vec<SignatureConfig>)>, String> {
+    let mut config_paths = fs::read_dir(configs_directory)
+        .map_err(|error| format!("Could not read config directory {}: {error}", configs_directory.display()))?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("cfg")))
+        .collect::<Vec<PathBuf>>();
+    config_paths.sort();
+
+    if config_paths.is_empty() {
+        return Err(format!("No config files found in directory: {}", configs_directory.display()));
+    }
+
+    config_paths.iter()
+        .map(|config_path| {
+            let config_name = config_path.file_name().unwrap().to_string_lossy().to_string();
+            let signature_ids = PlayerId::load_config_file(config_path, None, config_syntax).map_err(|error| format!("{config_name}: {error}"))?;
+            Ok((config_name, signature_ids))
+        })
+        .collect()
+}
+
+/// Returns for each file the name of each config that identified the file, together with the players found.
+fn find_matches_per_config(files: &[PathBuf], configs: &[(String, Vec<SignatureConfig>)], scan_for_multiple: bool, scanner: &Scanner) -> Vec<Vec<(String, Vec<SignatureMatch>)>> {
+    files
+        .par_iter()
+        .map(|filename| {
+            configs.iter()
+                .map(|(config_name, signature_ids)| (config_name.to_owned(), scan_file(filename, signature_ids, scan_for_multiple, false, false, scanner).matches))
+                .filter(|(_, matches)| !matches.is_empty())
+                .collect()
+        })
+        .collect()
+}
+
+fn scan_files(files: &[PathBuf], signature_ids: &Vec<SignatureConfig>, scan_for_multiple: bool, heuristic: bool, digest: bool, scanner: &Scanner) -> Vec<FileMatches> {
+    files
+        .par_iter()
+        .map(|filename| scan_file(filename, signature_ids, scan_for_multiple, heuristic, digest, scanner))
+        .collect()
+}
+
+/// Scans files until a file is found in which no player is identified. Files that aren't scanned yet are then skipped
+/// and an error is returned with the name of the unidentified file.
+fn scan_files_until_unidentified(files: &[PathBuf], signature_ids: &Vec<SignatureConfig>, scan_for_multiple: bool, heuristic: bool, digest: bool, scanner: &Scanner) -> Result<Vec<FileMatches>, String> {
+    let cancelled = AtomicBool::new(false);
+
+    let matches = files
+        .par_iter()
+        .map(|filename| {
+            if cancelled.load(Ordering::Relaxed) {
+                return None;
+            }
+
+            let file_matches = scan_file(filename, signature_ids, scan_for_multiple, heuristic, digest, scanner);
+            if file_matches.matches.is_empty() {
+                cancelled.store(true, Ordering::Relaxed);
+            }
+            Some(file_matches)
+        })
+        .collect::<Vec<Option<FileMatches>>>();
+
+    match matches.iter().flatten().find(|file_matches| file_matches.matches.is_empty()) {
+        Some(file_matches) => Err(format!("Unidentified file found: {}", file_matches.filename.display())),
+        None => Ok(matches.into_iter().flatten().collect())
+    }
+}
+
+fn get_scan_start(config: &Config) -> ScanStart {
+    if config.ted {
+        ScanStart::Program
+    } else if config.from_init {
+        ScanStart::Init
+    } else {
+        ScanStart::Data
+    }
+}
+
+fn get_scanner(config: &Config) -> Scanner {
+    let mut scanner = Scanner::new();
+    scanner.stride = config.stride;
+    scanner.scan_start = get_scan_start(config);
+    scanner.timeout = config.timeout;
+    scanner.skip_regions = config.skip_regions.clone();
+    scanner.set_max_memory(config.max_memory);
+    scanner
+}
+
+/// Scans a file for players. When `digest` is set, the MD5 digest of the content of the file is computed as well.
+fn scan_file(filename: &Path, signature_ids: &Vec<SignatureConfig>, scan_for_multiple: bool, heuristic: bool, digest: bool, scanner: &Scanner) -> FileMatches {
+    let result = if digest {
+        scanner.try_find_players_and_digest_in_file(filename, signature_ids, scan_for_multiple).map(|(matches, digest)| (matches, Some(digest)))
+    } else {
+        scanner.try_find_players_in_file(filename, signature_ids, scan_for_multiple).map(|matches| (matches, None))
+    };
+    let (matches, content_digest) = result.unwrap_or_else(|message| {
+        log_error!("{}: {message}\r", filename.display());
+        (Some(vec![]), None)
+    });
+
+    FileMatches {
+        likely_sid_player: heuristic && matches.as_ref().is_some_and(Vec::is_empty) && PlayerId::is_likely_sid_player(filename),
+        timed_out: matches.is_none(),
+        matches: matches.unwrap_or_default(),
+        filename: filename.to_owned(),
+        empty: fs::metadata(filename).is_ok_and(|metadata| metadata.len() == 0),
+        content_digest,
+    }
+}
+
+/// Orders the matches from most to least specific, so that the most likely player comes first. Strong matches come
+/// before weak matches and within each, matches of signatures with more bytes that aren't wildcards come first.
+fn sort_by_specificity(matches: &mut [SignatureMatch]) {
+    matches.sort_by_key(|player| (player.confidence == Confidence::Weak, Reverse(player.fixed_byte_count)));
+}
+
+/// Runs the command for each identified file, in parallel with at most as many commands at the same time as there are
+/// threads in the current thread pool. Commands that can't be started or fail are reported, but don't stop the others.
+fn run_exec_command(exec_command: &str, player_info: &[FileMatches]) {
+    player_info
+        .par_iter()
+        .filter(|file_matches| !file_matches.matches.is_empty())
+        .for_each(|file_matches| {
+            let arguments = get_exec_arguments(exec_command, &file_matches.filename, &file_matches.matches);
+            match process::Command::new(&arguments[0]).args(&arguments[1..]).status() {
+                Ok(status) if !status.success() => {
+                    let exit_code = status.code().map_or("none".to_string(), |exit_code| exit_code.to_string());
+                    log_error!("{}: command failed with exit code {exit_code}\r", file_matches.filename.display());
+                },
+                Err(error) => log_error!("{}: could not run command {}: {error}\r", file_matches.filename.display(), arguments[0]),
+                _ => {}
+            }
+        });
+}
+
+/// Splits the command into its arguments and substitutes {file} with the filename and {player} with the comma separated
+/// names of the players found in the file.
+fn get_exec_arguments(exec_command: &str, filename: &Path, players: &[SignatureMatch]) -> Vec<String> {
+    let player_names = players.iter().map(|player| player.signature_name.as_ref()).collect::<Vec<&str>>().join(",");
+
+    exec_command.split_ascii_whitespace()
+        .map(|argument| argument.replace("{file}", &filename.display().to_string()).replace("{player}", &player_names))
+        .collect()
+}
+
+/// Formats the name of a player as shown in the scan output, including the requested offsets and statistics.
+fn format_player_name(player: &SignatureMatch, config: &Config, load_address: Option<(usize, usize)>) -> String {
+    let player_name = if config.show_confidence && player.confidence == Confidence::Weak {
+        format!("{}?", player.signature_name)
+    } else {
+        player.signature_name.to_string()
+    };
+
+    let player_name = if config.display_hex_offset {
+        let offset_count = if config.first_offset_only { 1 } else { player.indexes.len() };
+        let player_indexes = player.indexes.iter().take(offset_count).map(|&index| format_offset(index, load_address)).collect::<Vec<String>>();
+        format!("{} {}", player_name, player_indexes.join(" "))
+    } else {
+        player_name
+    };
+
+    let player_name = if config.show_wildcard_density && !player.wildcard_used {
+        format!("{} (exact)", player_name)
+    } else if config.show_wildcard_density {
+        format!("{} ({:.0}% wildcards)", player_name, player.wildcard_density * 100.0)
+    } else {
+        player_name
+    };
+
+    let player_name = match &player.archive_path {
+        Some(archive_path) => format!("{} [{}]", player_name, archive_path),
+        None => player_name
+    };
+
+    match &player.source {
+        Some(source) if config.show_source => format!("{} (from {})", player_name, source),
+        _ => player_name
+    }
+}
+
+/// Formats the players found in a file, with a line per player, or all players on one line when flat is set.
+fn format_player_lines(filename: &str, player_names: &[String], flat: bool) -> Vec<String> {
+    if flat {
+        return vec![format!("{} {}", filename, player_names.join(", "))];
+    }
+
+    player_names.iter().enumerate()
+        .map(|(index, player_name)| {
+            if index == 0 {
+                format!("{} {}", filename, player_name)
+            } else {
+                format!("{:<0width$} {}", "", player_name, width = filename.chars().count())
+            }
+        })
+        .collect()
+}
+
+/// Gets the name of the first player found in a file without the filename and offsets, or the label of an
+/// unidentified, timed out or empty file when unidentified files are listed.
+fn get_name_only_line(file_matches: &FileMatches, config: &Config) -> Option<String> {
+    let list_unidentified = config.list_unidentified || config.only_list_unidentified;
+
+    if let Some(player) = file_matches.matches.first() {
+        (!config.only_list_unidentified).then(|| player.signature_name.to_string())
+    } else if !list_unidentified {
+        None
+    } else if file_matches.timed_out {
+        Some(config.skipped_label.as_deref().unwrap_or(TIMEOUT_LABEL).to_string())
+    } else if file_matches.empty {
+        Some(config.skipped_label.as_deref().unwrap_or(EMPTY_LABEL).to_string())
+    } else {
+        Some(get_unidentified_label(config, file_matches.likely_sid_player).to_string())
+    }
+}
+
+/// Formats an offset in the file, together with the memory address of the offset when the load address is given.
+fn format_offset(offset: usize, load_address: Option<(usize, usize)>) -> String {
+    match load_address {
+        Some((data_offset, load_address)) if offset >= data_offset => {
+            format!("file:${:04X} / mem:${:04X}", offset, load_address + offset - data_offset)
+        },
+        _ => format!("${offset:04X}")
+    }
+}
+
+fn get_first_offset(matches: &[SignatureMatch]) -> Option<usize> {
+    matches.first().and_then(|player| player.indexes.first()).copied()
+}
+
+fn build_offset_histogram(offsets: &[usize], bucket_size: usize) -> BTreeMap<usize, usize> {
+    let mut histogram = BTreeMap::new();
+    for offset in offsets {
+        *histogram.entry(offset / bucket_size * bucket_size).or_insert(0) += 1;
+    }
+    histogram
+}
+
+fn format_offset_histogram(player_name: &str, bucket_size: usize, offsets: &[usize]) -> String {
+    let histogram = build_offset_histogram(offsets, bucket_size);
+    let max_count = histogram.values().copied().max().unwrap_or(0);
+
+    let mut lines = vec![
+        format!("Offset histogram for: {player_name} (bucket size ${bucket_size:04X})\r\n\r"),
+        "Offset              Count\r".to_string(),
+        "-------------------------\r".to_string()
+    ];
+
+    for (bucket_start, count) in histogram {
+        let bar_length = (count * HISTOGRAM_BAR_WIDTH).div_ceil(max_count);
+        lines.push(format!("${:04X}-${:04X} {:>9} {}\r", bucket_start, bucket_start + bucket_size - 1, count, "#".repeat(bar_length)));
+    }
+
+    lines.push(format!("\r\nFiles matched {:>11}\r", offsets.len()));
+    lines.join("\n")
+}
+
+/// Formats the summary of a scan. The unique tunes are given as the number of identified and the total number of files
+/// with distinct content, and are only shown when given.
+fn format_summary(summary_format: &SummaryFormat, identified_players: usize, identified_files: usize, unidentified_files: usize, total_files: usize, unique_tunes: Option<(usize, usize)>) -> String {
+    match (summary_format, unique_tunes) {
+        (SummaryFormat::Pretty, None) => format!("Summary:\r\n\
+            Identified players    {identified_players:>9}\r\n\
+            Identified files      {identified_files:>9}\r\n\
+            Unidentified files    {unidentified_files:>9}\r\n\
+            Total files processed {total_files:>9}\r"),
+        (SummaryFormat::Pretty, Some((identified_unique_tunes, unique_tunes))) => format!("{}\n\
+            Identified unique tunes {identified_unique_tunes:>7}\r\n\
+            Unique tunes processed {unique_tunes:>8}\r", format_summary(summary_format, identified_players, identified_files, unidentified_files, total_files, None)),
+        (SummaryFormat::KeyValue, None) => format!("identified_players={identified_players} identified_files={identified_files} \
+            unidentified_files={unidentified_files} total={total_files}\r"),
+        (SummaryFormat::KeyValue, Some((identified_unique_tunes, unique_tunes))) => format!("identified_players={identified_players} identified_files={identified_files} \
+            unidentified_files={unidentified_files} total={total_files} identified_unique_tunes={identified_unique_tunes} unique_tunes={unique_tunes}\r")
+    }
+}
+
+/// Returns a table with the number of identified files and the total number of files of each file type.
+fn format_file_type_totals(file_types: &[(FileType, bool)]) -> String {
+    let mut lines = vec![
+        "\r\nFile type      Identified     Total\r".to_string(),
+        "------------------------------------\r".to_string()
+    ];
+
+    for (file_type, type_name) in [(FileType::Sid, "SID"), (FileType::Prg, "PRG"), (FileType::Other, "Other")] {
+        let files = file_types.iter().filter(|(other_type, _)| *other_type == file_type);
+        let identified_files = files.clone().filter(|(_, identified)| *identified).count();
+        lines.push(format!("{:<14} {:>10} {:>9}\r", type_name, identified_files, files.count()));
+    }
+    lines.join("\n")
+}
+
+/// Counts the identified files and all files with distinct content, so that tunes that are stored more than once, like
+/// in different directories of HVSC, are counted once. Files are grouped by the digest of their content and only the
+/// files with the same digest are read again, to compare their bytes. Files without a digest are not counted.
+fn count_unique_tunes(file_matches: &[FileMatches]) -> (usize, usize) {
+    let mut files_per_digest: HashMap<[u8; 16], Vec<&FileMatches>> = HashMap::new();
+    for file_matches in file_matches {
+        if let Some(content_digest) = file_matches.content_digest {
+            files_per_digest.entry(content_digest).or_default().push(file_matches);
+        }
+    }
+
+    files_per_digest
+        .par_iter()
+        .map(|(_, files)| count_distinct_contents(files))
+        .reduce(|| (0, 0), |(identified, total), (other_identified, other_total)| (identified + other_identified, total + other_total))
+}
+
+/// Counts the identified and all distinct contents of files with the same digest. Files that can't be read are not
+/// counted, unless there is no other file with the digest.
+fn count_distinct_contents(files: &[&FileMatches]) -> (usize, usize) {
+    if let [file_matches] = files {
+        return (usize::from(!file_matches.matches.is_empty()), 1);
+    }
+
+    let mut contents: Vec<(Vec<u8>, bool)> = vec![];
+    for file_matches in files {
+        let Ok(data) = fs::read(&file_matches.filename) else {
+            continue;
+        };
+        let identified = !file_matches.matches.is_empty();
+        match contents.iter_mut().find(|(content, _)| *content == data) {
+            Some((_, any_identified)) => *any_identified |= identified,
+            None => contents.push((data, identified))
+        }
+    }
+    (contents.iter().filter(|(_, identified)| *identified).count(), contents.len())
+}
+
 fn output_elapsed_time(start_time: Instant) {
     let time_millis = start_time.elapsed().as_millis();
     let time_seconds = time_millis / 1000;
     let seconds = time_seconds % 60;
     let minutes = time_seconds / 60 % 60;
     let hours = time_seconds / 60 / 60;
-    eprintln!("\r\nTotal time: {:0>2}:{:0>2}:{:0>2} (+{} milliseconds)\r", hours, minutes, seconds, time_millis % 1000);
+    log_info!("\r\nTotal time: {:0>2}:{:0>2}:{:0>2} (+{} milliseconds)\r", hours, minutes, seconds, time_millis % 1000);
 }
 
-fn output_occurrence_statistics(signature_ids: &Vec<SignatureConfig>, player_info: &Vec<FileMatches>) {
-    println!("\r\nDetected players          Count\r");
-    println!("-------------------------------\r");
+fn format_occurrence_statistics(signature_ids: &Vec<SignatureConfig>, player_info: &Vec<FileMatches>, unidentified_files: Option<usize>) -> String {
+    let mut lines = vec![
+        "\r\nDetected players          Count\r".to_string(),
+        "-------------------------------\r".to_string()
+    ];
 
+    // players are counted by their name in uppercase, so signatures with the same name in a different casing are
+    // counted as one player, which is shown with the casing of its first signature in the config file
     let mut player_occurrence = HashMap::new();
     for players in player_info {
-        for player in &players.matches {
-            *player_occurrence.entry(player.signature_name.to_owned()).or_insert(0) += 1;
+        let player_names = players.matches.iter().map(|player| player.signature_name.to_ascii_uppercase()).collect::<HashSet<String>>();
+        for player_name in player_names {
+            *player_occurrence.entry(player_name).or_insert(0) += 1;
         }
     }
 
-    let mut previous_player_name = "";
+    let mut players_added = HashSet::new();
     for signature_id in signature_ids {
-        if signature_id.signature_name.ne(previous_player_name) {
-            previous_player_name = &signature_id.signature_name;
-            if let Some(occurrence) = player_occurrence.get(&signature_id.signature_name) {
-                println!("{:<24} {:>6}\r", signature_id.signature_name, occurrence);
+        let player_name = signature_id.signature_name.to_ascii_uppercase();
+        if let Some(occurrence) = player_occurrence.get(&player_name) {
+            if players_added.insert(player_name) {
+                lines.push(format!("{:<24} {:>6}\r", signature_id.signature_name, occurrence));
+            }
+        }
+    }
+
+    if let Some(unidentified_files) = unidentified_files {
+        lines.push(format!("{:<24} {:>6}\r", UNIDENTIFIED_PLAYER_NAME, unidentified_files));
+    }
+    lines.join("\n")
+}
+
+/// Lists the files each detected player matched, in the order of the players in the config file. Players are grouped by
+/// their name in uppercase, the same as in the occurrence statistics.
+fn format_per_player_files(signature_ids: &[SignatureConfig], player_info: &[FileMatches], filename_strip_length: usize) -> String {
+    let mut lines = vec![
+        "\r\nFiles per player\r".to_string(),
+        "-------------------------------\r".to_string()
+    ];
+
+    let mut player_files: HashMap<String, Vec<&Path>> = HashMap::new();
+    for players in player_info {
+        let player_names = players.matches.iter().map(|player| player.signature_name.to_ascii_uppercase()).collect::<HashSet<String>>();
+        for player_name in player_names {
+            player_files.entry(player_name).or_default().push(&players.filename);
+        }
+    }
+
+    for signature_id in signature_ids {
+        if let Some(files) = player_files.remove(&signature_id.signature_name.to_ascii_uppercase()) {
+            lines.push(format!("{} ({})\r", signature_id.signature_name, files.len()));
+            lines.extend(files.iter().map(|filename| format!("  {}\r", get_display_filename(filename, filename_strip_length))));
+        }
+    }
+    lines.join("\n")
+}
+
+fn get_hint_sample(player_info: &[FileMatches]) -> Vec<PathBuf> {
+    let identified_files = player_info.iter().filter(|file_matches| !file_matches.matches.is_empty()).collect::<Vec<&FileMatches>>();
+    let step = identified_files.len().div_ceil(HINT_SAMPLE_SIZE).max(1);
+
+    identified_files.iter().step_by(step).map(|file_matches| file_matches.filename.to_owned()).collect()
+}
+
+fn get_multiple_match_hint(sampled_matches: &[FileMatches]) -> Option<String> {
+    let files_with_multiple_players = sampled_matches.iter().filter(|file_matches| file_matches.matches.len() > 1).count();
+
+    (files_with_multiple_players > 0).then(|| format!("Hint: {} of {} sampled files match more than one player, run with -m for more detail.",
+        files_with_multiple_players, sampled_matches.len()))
+}
+
+fn find_conflicts(player_info: &[FileMatches], conflict_percentage: usize) -> Vec<(String, String, usize, usize, usize)> {
+    let mut player_occurrence: HashMap<&str, usize> = HashMap::new();
+    let mut pair_occurrence: HashMap<(&str, &str), usize> = HashMap::new();
+
+    for players in player_info {
+        let mut player_names = players.matches.iter()
+            .map(|player| player.signature_name.as_ref())
+            .filter(|player_name| !player_name.starts_with('('))
+            .collect::<Vec<&str>>();
+        player_names.sort_unstable();
+        player_names.dedup();
+
+        for (index, player_name) in player_names.iter().enumerate() {
+            *player_occurrence.entry(player_name).or_insert(0) += 1;
+            for other_player_name in &player_names[index + 1..] {
+                *pair_occurrence.entry((player_name, other_player_name)).or_insert(0) += 1;
             }
         }
     }
+
+    let mut conflicts = pair_occurrence.into_iter()
+        .filter_map(|((player_name, other_player_name), overlap)| {
+            let occurrence = player_occurrence[player_name];
+            let other_occurrence = player_occurrence[other_player_name];
+            (overlap * 100 >= occurrence.min(other_occurrence) * conflict_percentage)
+                .then(|| (player_name.to_string(), other_player_name.to_string(), overlap, occurrence, other_occurrence))
+        })
+        .collect::<Vec<(String, String, usize, usize, usize)>>();
+
+    conflicts.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)).then_with(|| a.1.cmp(&b.1)));
+    conflicts
+}
+
+fn output_conflicts(player_info: &[FileMatches], conflict_percentage: usize) {
+    println!("\r\nConflicting players (overlap >= {conflict_percentage}%)\r");
+    println!("--------------------------------------------------------------------------------\r");
+
+    let conflicts = find_conflicts(player_info, conflict_percentage);
+    if conflicts.is_empty() {
+        println!("No conflicts found.\r");
+    }
+
+    for (player_name, other_player_name, overlap, occurrence, other_occurrence) in conflicts {
+        println!("{player_name:<24} {other_player_name:<24} {overlap:>6} of {occurrence:>6} / {other_occurrence:>6}\r");
+    }
 }
 
 fn load_signatures(config: &Config) -> Result<Vec<SignatureConfig>, String> {
-    let config_path = PlayerId::get_config_path(config.config_file.as_ref())?;
-    println!("Using config file: {}\r\n\r", config_path.display());
+    let config_paths = match PlayerId::get_config_paths(config.config_file.as_ref()) {
+        Err(_) if config.use_builtin_config && config.config_file.is_none() => {
+            log_info!("No config file found, using built-in config.\r");
+            return PlayerId::load_builtin_config(config.player_name.as_ref());
+        },
+        config_paths => config_paths?
+    };
+    for config_path in &config_paths {
+        log_info!("Using config file: {}\r", config_path.display());
+    }
+    log_info!("\r");
 
-    PlayerId::load_config_file(&config_path, config.player_name.as_ref())
+    PlayerId::load_config_files(&config_paths, config.player_name.as_ref(), config.config_syntax)
 }
 
-fn get_matched_filenames(config: &Config) -> Vec<String> {
+fn get_matched_filenames(config: &Config) -> Vec<PathBuf> {
     if config.filename.is_empty() {
         return vec![];
     }
 
-    let max_depth = if config.recursive { usize::MAX } else { 1 };
+    let files = find_files(&config.base_path, &config.filename, config.recursive);
+
+    let files = if let Some(modified_since) = config.modified_since {
+        filter_modified_since(files, modified_since)
+    } else {
+        files
+    };
+
+    if config.sid_only {
+        let file_count = files.len();
+        let files = filter_sid_files(files);
+        if files.len() < file_count {
+            log_info!("Skipped {} file(s) that aren't SID files\r", file_count - files.len());
+        }
+        files
+    } else {
+        files
+    }
+}
+
+fn filter_sid_files(files: Vec<PathBuf>) -> Vec<PathBuf> {
+    files.into_par_iter()
+        .filter(|filename| PlayerId::is_sid_file(filename))
+        .collect()
+}
 
-    globwalk::GlobWalkerBuilder::from_patterns(&config.base_path, &[&config.filename])
+fn find_files(base_path: &str, filename_pattern: &str, recursive: bool) -> Vec<PathBuf> {
+    let max_depth = if recursive { usize::MAX } else { 1 };
+
+    globwalk::GlobWalkerBuilder::from_patterns(base_path, &[filename_pattern])
         .max_depth(max_depth)
         .case_insensitive(true)
         .sort_by(|a, b| a.file_name().cmp(b.file_name()))
         .build().unwrap()
         .filter_map(Result::ok)
-        .map(|entry| entry.path().display().to_string())
+        .filter(|entry| entry.path().is_file())
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+/// Explains why no files are found, so that e.g. a pattern that was expanded by the shell or a missing -s option
+/// can be spotted.
+fn get_no_files_found_message(config: &Config) -> String {
+    if config.filename.is_empty() {
+        return "No file(s) found, no file pattern provided.".to_string();
+    }
+
+    let search_description = format!("pattern '{}' in: {}", config.filename, config.base_path);
+
+    if !Path::new(&config.base_path).is_dir() {
+        format!("No file(s) found, path doesn't exist: {}", config.base_path)
+    } else if config.sid_only && !find_files(&config.base_path, &config.filename, config.recursive).is_empty() {
+        format!("No SID file(s) found using {search_description}")
+    } else if config.modified_since.is_some() && !find_files(&config.base_path, &config.filename, config.recursive).is_empty() {
+        format!("No file(s) found that are modified since the given date, using {search_description}")
+    } else if !config.recursive && !find_files(&config.base_path, &config.filename, true).is_empty() {
+        format!("No file(s) found using {search_description}\r\nFiles in sub directories match the pattern, use the -s option to scan recursively.")
+    } else if fs::read_dir(&config.base_path).is_ok_and(|mut entries| entries.next().is_none()) {
+        format!("No file(s) found, directory is empty: {}", config.base_path)
+    } else {
+        format!("No file(s) found using {search_description}")
+    }
+}
+
+fn filter_modified_since(files: Vec<PathBuf>, modified_since: SystemTime) -> Vec<PathBuf> {
+    files.into_iter()
+        .filter(|filename| {
+            fs::metadata(filename)
+                .and_then(|metadata| metadata.modified())
+                .is_ok_and(|modified| modified >= modified_since)
+        })
         .collect()
 }
 
 fn calculate_filename_width(truncate_filenames: bool, players_found: &[FileMatches], filename_strip_length: usize) -> usize {
     if !truncate_filenames {
-        if let Some(longest_filename_length) = players_found.iter().map(|fm| fm.filename.len()).max() {
+        if let Some(longest_filename_length) = players_found.iter().map(|fm| fm.filename.as_os_str().len()).max() {
             return (longest_filename_length - filename_strip_length).max(DEFAULT_FILENAME_COL_WIDTH);
         }
     }
     DEFAULT_FILENAME_COL_WIDTH
 }
 
-fn get_filename_strip_length(base_path: &str, files: &[String]) -> usize {
+fn get_filename_strip_length(base_path: &str, files: &[PathBuf]) -> usize {
     if let Some(first_file) = files.first() {
         if let Some(hvsc_root) = hvsc::get_hvsc_root(first_file) {
             return hvsc_root.len() + 1;
@@ -228,28 +993,374 @@ fn get_filename_strip_length(base_path: &str, files: &[String]) -> usize {
     if base_path == "." { 2 } else { 0 }
 }
 
-fn display_player_info(config: &Config) -> Result<(), String> {
-    let config_path = PlayerId::get_info_file_path(config.config_file.as_ref())?;
+fn load_song_lengths(files: &[PathBuf]) -> Result<HashMap<String, String>, String> {
+    let hvsc_root = files.first()
+        .and_then(|file| hvsc::get_hvsc_root(file))
+        .ok_or("Song lengths can only be shown for files in HVSC.")?;
+
+    songlengths::load_song_lengths(Path::new(&hvsc_root))
+}
+
+fn get_song_lengths(song_lengths: &HashMap<String, String>, file_matches: &FileMatches) -> Option<String> {
+    songlengths::get_song_lengths(song_lengths, &file_matches.content_digest?).cloned()
+}
+
+fn to_absolute_paths(files: Vec<PathBuf>) -> Vec<PathBuf> {
+    files.into_iter()
+        .map(|file| path::absolute(&file).unwrap_or(file))
+        .collect()
+}
+
+fn get_display_filename(filename: &Path, filename_strip_length: usize) -> String {
+    let filename = filename.to_string_lossy().replace('\\', "/");
+    if filename.len() > filename_strip_length {
+        filename.get(filename_strip_length..).map_or(filename.to_string(), str::to_string)
+    } else {
+        filename
+    }
+}
+
+fn display_config_paths(config: &Config) -> Result<(), String> {
+    match PlayerId::get_config_paths(config.config_file.as_ref()) {
+        Ok(config_paths) => config_paths.iter().for_each(|config_path| println!("Config file: {}\r", config_path.display())),
+        Err(_) if config.use_builtin_config && config.config_file.is_none() => println!("Config file: <built-in>\r"),
+        Err(message) => println!("Config file: {message}\r")
+    }
+
+    match PlayerId::get_info_file_path(config.config_file.as_ref(), config.info_file.as_ref()) {
+        Ok(info_path) => println!("Info file:   {}\r", info_path.display()),
+        Err(message) => println!("Info file:   {message}\r")
+    }
+    Ok(())
+}
+
+fn list_players(config: &Config) -> Result<(), String> {
+    let signature_ids = load_signatures(config)?;
+
+    let mut previous_player_name = "";
+    for signature_id in &signature_ids {
+        if signature_id.signature_name.as_ref().ne(previous_player_name) {
+            previous_player_name = &signature_id.signature_name;
+            println!("{}\r", signature_id.signature_name);
+        }
+    }
+    Ok(())
+}
+
+fn dump_masks(config: &Config) -> Result<(), String> {
+    let signature_ids = load_signatures(config)?;
+
+    println!("{}", format_bndm_masks(&signature_ids));
+    Ok(())
+}
+
+fn dump_parsed_signatures(config: &Config) -> Result<(), String> {
+    let signature_ids = load_signatures(config)?;
+
+    for line in format_parsed_signatures(&signature_ids) {
+        println!("{line}\r");
+    }
+    Ok(())
+}
+
+/// Scans the directory of each EXPECT annotation of the config file and checks that the signature matches the expected
+/// number of files. An error is returned when any of the expectations isn't met.
+fn test_config(config: &Config) -> Result<(), String> {
+    let config_path = PlayerId::get_config_path(config.config_file.as_ref())?;
+    log_info!("Using config file: {}\r\n\r", config_path.display());
+
+    let mut signature_ids = PlayerId::load_config_file(&config_path, None, config.config_syntax)?;
+    if config.any_order {
+        set_any_order(&mut signature_ids);
+    }
+    if config.relocatable {
+        PlayerId::mask_absolute_addresses(&mut signature_ids);
+    }
+
+    let expectations = PlayerId::load_config_expectations(&config_path)?;
+    if expectations.is_empty() {
+        log_info!("No EXPECT annotations found in config file.\r");
+        return Ok(());
+    }
+
+    let scanner = get_scanner(config);
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(config.cpu_threads).build().unwrap();
+    let mut failed_count = 0;
+
+    for expectation in &expectations {
+        let result = if !signature_ids.iter().any(|signature_id| signature_id.signature_name.as_ref() == expectation.signature_name) {
+            Err("signature not found in config file".to_string())
+        } else if !expectation.directory.is_dir() {
+            Err(format!("directory not found: {}", expectation.directory.display()))
+        } else {
+            let files = find_files(&expectation.directory.to_string_lossy(), ALL_FILES_PATTERN, true);
+            let matches = pool.install(|| scan_files(&files, &signature_ids, true, false, false, &scanner));
+            let file_count = count_files_with_player(&matches, &expectation.signature_name);
+
+            if file_count == expectation.file_count {
+                Ok(file_count)
+            } else {
+                Err(format!("matches {file_count} file(s) instead of {} in: {}", expectation.file_count, expectation.directory.display()))
+            }
+        };
+
+        match result {
+            Ok(file_count) => println!("PASS: {} matches {file_count} file(s) in: {}\r", expectation.signature_name, expectation.directory.display()),
+            Err(message) => {
+                failed_count += 1;
+                println!("FAIL: {} {message} [line: {}]\r", expectation.signature_name, expectation.line_number);
+            }
+        }
+    }
+
+    println!("\r\n{} passed, {failed_count} failed\r", expectations.len() - failed_count);
+
+    if failed_count > 0 {
+        return Err("Config test failed.".to_string());
+    }
+    Ok(())
+}
+
+/// Scans a directory of files that don't contain any of the players and shows each signature that matches a file, as
+/// these matches are false positives. An error is returned when any false positive is found, or when a file can't be
+/// checked because it can't be read or the scan times out.
+fn check_false_positives(config: &Config) -> Result<(), String> {
+    let negatives_directory = config.false_positives_directory.as_deref().unwrap_or_default();
+
+    let mut signature_ids = load_signatures(config)?;
+    if config.any_order {
+        set_any_order(&mut signature_ids);
+    }
+    if config.relocatable {
+        PlayerId::mask_absolute_addresses(&mut signature_ids);
+    }
+
+    let files = find_files(negatives_directory, ALL_FILES_PATTERN, config.recursive);
+    if files.is_empty() {
+        log_info!("No files found in: {negatives_directory}\r");
+        return Ok(());
+    }
+
+    let scanner = get_scanner(config);
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(config.cpu_threads).build().unwrap();
+    let results = pool.install(|| files.par_iter()
+        .map(|filename| scanner.try_find_players_in_file(filename, &signature_ids, true))
+        .collect::<Vec<Result<Option<Vec<SignatureMatch>>, String>>>());
+
+    let mut false_positive_count = 0;
+    let mut unchecked_count = 0;
+    for (filename, result) in files.iter().zip(results) {
+        match result {
+            Ok(Some(matches)) => {
+                for player in &matches {
+                    false_positive_count += 1;
+                    println!("FALSE POSITIVE: {} in: {}\r", player.signature_name, filename.display());
+                }
+            },
+            Ok(None) => {
+                unchecked_count += 1;
+                println!("NOT CHECKED: {} (scan timed out)\r", filename.display());
+            },
+            Err(message) => {
+                unchecked_count += 1;
+                println!("NOT CHECKED: {} ({message})\r", filename.display());
+            }
+        }
+    }
+
+    println!("\r\n{false_positive_count} false positive(s) in {} file(s)\r", files.len());
+    if unchecked_count > 0 {
+        println!("{unchecked_count} file(s) not checked\r");
+    }
+
+    if false_positive_count > 0 || unchecked_count > 0 {
+        return Err("False positive check failed.".to_string());
+    }
+    Ok(())
+}
+
+fn count_files_with_player(file_matches: &[FileMatches], player_name: &str) -> usize {
+    file_matches.iter()
+        .filter(|file_matches| file_matches.matches.iter().any(|player| player.signature_name.as_ref() == player_name))
+        .count()
+}
+
+fn suggest_signature(config: &Config) -> Result<(), String> {
+    let positive_files = get_matched_filenames(config);
+    if positive_files.is_empty() {
+        return Err(get_no_files_found_message(config));
+    }
+
+    let negatives_directory = config.negatives_directory.as_deref().unwrap_or_default();
+    if !Path::new(negatives_directory).is_dir() {
+        return Err(format!("Could not read directory: {negatives_directory}"));
+    }
+    let negative_files = find_files(negatives_directory, ALL_FILES_PATTERN, config.recursive);
+
+    let Some(signature) = PlayerId::suggest_signature(&positive_files, &negative_files, SUGGESTED_SIGNATURE_NAME)? else {
+        return Err(format!("No byte sequence found that is in all {} file(s) and in none of the {} file(s) in: {negatives_directory}", positive_files.len(), negative_files.len()));
+    };
+
+    println!("{}\r", signature.signature_name);
+    println!("{}\r", PlayerId::format_signature_value(&signature, true));
+    Ok(())
+}
+
+fn format_parsed_signatures(signature_ids: &[SignatureConfig]) -> Vec<String> {
+    let mut lines = signature_ids.iter()
+        .map(|signature_id| format!("{}: {}", signature_id.signature_name, PlayerId::format_signature_value(signature_id, true)))
+        .collect::<Vec<String>>();
+
+    lines.sort();
+    lines
+}
+
+fn format_bndm_masks(signature_ids: &[SignatureConfig]) -> String {
+    let mut lines = vec![];
+
+    for signature_id in signature_ids {
+        lines.push(format!("Masks for: {}\r", signature_id.signature_name));
+
+        for (index, bndm_config) in signature_id.bndm_configs.iter().enumerate() {
+            let width = bndm_config.pattern.len().min(usize::BITS as usize);
+            let pattern = bndm_config.pattern.iter()
+                .map(|&byte| if Some(byte) == bndm_config.wildcard { "??".to_string() } else { format!("{byte:02X}") })
+                .collect::<Vec<String>>()
+                .join(" ");
+            let wildcard = bndm_config.wildcard.map_or("none".to_string(), |wildcard| format!("${wildcard:02X}"));
+            let wildcard_mask = bndm_config.wildcard.map_or(0, |wildcard| bndm_config.masks[wildcard as usize]);
+
+            lines.push(format!("\r\nSegment {}: {}\r", index + 1, pattern));
+            lines.push(format!("Wildcard: {wildcard}\r"));
+
+            for (byte, mask) in bndm_config.masks.iter().enumerate().filter(|(_, &mask)| mask != wildcard_mask) {
+                lines.push(format!("  ${byte:02X}: {mask:0width$b}\r"));
+            }
+            if wildcard_mask != 0 {
+                lines.push(format!("  other: {wildcard_mask:0width$b}\r"));
+            }
+        }
+        lines.push("\r".to_string());
+    }
+    lines.join("\n")
+}
+
+fn display_config_stats(config: &Config) -> Result<(), String> {
+    let signature_ids = load_signatures(config)?;
+
+    println!("{}", format_wildcard_stats(&signature_ids));
+    Ok(())
+}
+
+fn format_wildcard_stats(signature_ids: &[SignatureConfig]) -> String {
+    let mut wildcard_occurrence = BTreeMap::new();
+    let mut segments_without_wildcard = 0;
+
+    for bndm_config in signature_ids.iter().flat_map(|signature_id| &signature_id.bndm_configs) {
+        match bndm_config.wildcard.filter(|wildcard| bndm_config.pattern.contains(wildcard)) {
+            Some(wildcard) => *wildcard_occurrence.entry(wildcard).or_insert(0) += 1,
+            None => segments_without_wildcard += 1
+        }
+    }
+
+    let mut lines = vec![
+        "Wildcard        Count\r".to_string(),
+        "---------------------\r".to_string()
+    ];
+
+    for (wildcard, count) in &wildcard_occurrence {
+        lines.push(format!("${:02X} {:>17}\r", wildcard, count));
+    }
+
+    lines.push(format!("\r\nDistinct wildcards {:>11}\r", wildcard_occurrence.len()));
+    lines.push(format!("Segments without wildcards {:>3}\r", segments_without_wildcard));
+    lines.join("\n")
+}
+
+fn list_player_info(config: &Config) -> Result<(), String> {
+    let config_path = PlayerId::get_info_file_path(config.config_file.as_ref(), config.info_file.as_ref())?;
     println!("Using info file: {}\r\n\r", config_path.display());
 
-    let player_infos = PlayerId::load_info_file(&config_path)?;
+    let player_infos = PlayerId::load_info_file(&config_path, config.config_syntax)?;
+    for player_info in format_player_infos(&player_infos, config.info_filter.as_ref()) {
+        println!("{player_info}\r\n\r");
+    }
+    Ok(())
+}
+
+fn format_player_infos(player_infos: &[SignatureInfo], info_filter: Option<&Vec<String>>) -> Vec<String> {
+    player_infos.iter()
+        .filter_map(|(signature_name, info_lines)| {
+            let info_lines = match info_filter {
+                Some(info_tags) => filter_info_lines(info_lines, info_tags),
+                None => info_lines.iter().collect()
+            };
+
+            (!info_lines.is_empty()).then(|| {
+                let info_lines = info_lines.iter().map(|line| line.as_str()).collect::<Vec<&str>>();
+                format!("{}\r\n{}", signature_name, info_lines.join("\r\n"))
+            })
+        })
+        .collect()
+}
+
+fn filter_info_lines<'a>(info_lines: &'a [String], info_tags: &[String]) -> Vec<&'a String> {
+    let mut include_line = false;
+    info_lines.iter()
+        .filter(|line| {
+            if line.get(9..11) == Some(": ") {
+                let tag = line[..9].trim();
+                include_line = info_tags.iter().any(|info_tag| info_tag.eq_ignore_ascii_case(tag));
+            }
+            include_line
+        })
+        .collect()
+}
+
+fn display_player_info(config: &Config) -> Result<(), String> {
+    let config_path = PlayerId::get_info_file_path(config.config_file.as_ref(), config.info_file.as_ref())?;
+    if !config.json {
+        println!("Using info file: {}\r\n\r", config_path.display());
+    }
+
+    let player_infos = PlayerId::load_info_file(&config_path, config.config_syntax)?;
     let player_name = config.player_name.as_ref().unwrap();
 
-    if let Some((signature_name, info_lines)) = PlayerId::find_player_info(&player_infos, player_name) {
-        println!("Player info:\r\n\r\n{}\r\n{}\r", signature_name, info_lines.join("\r\n"));
+    if let Some(signature_info) = PlayerId::find_player_info(&player_infos, player_name) {
+        let player_info = PlayerInfo::parse(signature_info);
+        if config.json {
+            println!("{}\r", player_info.to_json());
+            return Ok(());
+        }
+        println!("Player info:\r\n\r\n{}\r\n{}\r", player_info.signature_name, player_info.to_info_lines().join("\r\n"));
     } else {
         eprintln!("No info found for player ID: {}\r", &player_name);
     }
     Ok(())
 }
 
+fn print_examples() {
+    println!("Examples:\r");
+    for (description, command_line) in EXAMPLES {
+        println!("\r\n  {description}:\r");
+        println!("    {command_line}\r");
+    }
+}
+
 fn print_usage() {
     println!("C64 Music Player Identifier (PI) v2.01 - Copyright (c) 2012-2023 Wilfred Bos\r\n\r");
-    println!("Usage: player-id <options> <file_path_pattern>\r\n\r");
+    println!("Usage: player-id [command] <options> <file_path_pattern>\r\n\r");
+    println!("<Commands>\r");
+    println!("  scan: scan files for players [Default]\r");
+    println!("  verify: verify signatures [same as -v]\r");
+    println!("  convert new|old: write signatures in new or old format [same as -wn or -wo]\r");
+    println!("  info <player_name>: show player info [same as -n -p<player_name>]\r");
+    println!("  list: list all player names of the config file\r\n\r");
     println!("<Options>\r");
     println!("  -c{{max_threads}}: set the maximum CPU threads to be used [Default is all]\r");
     println!("  -f{{config_file}}: config file [Default SIDIDCFG env. var. / sidid.cfg file]\r");
     println!("  -h: scan HVSC location [Uses HVSC env. variable for HVSC path]\r");
+    println!("  -i{{info_file}}: info file [Default is config file with .nfo extension]\r");
     println!("  -m: scan for multiple signatures\r");
     println!("  -n: show player info [use together with -p option]\r");
     println!("  -o: list only unidentified files\r");
@@ -261,4 +1372,63 @@ fn print_usage() {
     println!("  -wn: write signatures in new format");
     println!("  -wo: write signatures in old format");
     println!("  -x: display hexadecimal offset of signature found\r");
+    println!("  --no-builtin: don't use the built-in config when no config file is found\r");
+    println!("  --offset-histogram[={{bucket_size}}]: show histogram of offsets of player [use together with -p option]\r");
+    println!("  --conflicts[={{percentage}}]: report players matching the same files [Default is 50%, implies -m]\r");
+    println!("  --silent: don't show informational messages, only errors and results\r");
+    println!("  --list-info[={{tags}}]: show info of all players, optionally only the given comma separated tags\r");
+    println!("  --stride={{n}}: also search for players stored at every n-th byte [experimental, slower]\r");
+    println!("  --ted: scan Plus/4 (TED) program files with the tedid.cfg config file\r");
+    println!("  --json: show player info as JSON [use together with -n option]\r");
+    println!("  --max-memory={{mb}}: maximum size in megabytes of the files that are read at the same time\r");
+    println!("  --timeout-ms={{n}}: stop scanning a file after n milliseconds and mark it as TIMEOUT\r");
+    println!("  --relocatable: also find players that are relocated, by ignoring the absolute addresses in signatures\r");
+    println!("  --any-order: match signatures with AND operators when the sub signatures are found in any order\r");
+    println!("  --skip-region={{start}}:{{end}}: don't match signatures in the bytes from start up to end, can be used more than once\r");
+    println!("  --unique-tunes: also count files with distinct content in the summary, e.g. to count tunes stored more than once in HVSC once\r");
+    println!("  --exec={{command}}: run the command for each identified file, {{file}} and {{player}} are replaced with the filename and players\r");
+    println!("  --min-line-length={{n}}: minimum length of a line in the config file, shorter lines end a signature [Default is 2]\r");
+    println!("  --min-name-length={{n}}: minimum length of a signature name in the config file, at least 3 [Default is 3]\r");
+    println!("  --strict: stop with an error when a signature contains a value that isn't a hexadecimal byte\r");
+    println!("  --flat: show all players found in a file on one line, separated by a comma\r");
+    println!("  --both-offsets: show offsets of players in the file and in memory [implies -x]\r");
+    println!("  --from-init: start scanning SID files at the init address, offsets are relative to the init address\r");
+    println!("  --heuristic: label unidentified files that write to the SID chip as likely SID player\r");
+    println!("  --per-player-files: list the files of each detected player after the player statistics\r");
+    println!("  --name-only: only show the name of the first player found in each identified file, one per line\r");
+    println!("  --concatenated: scan each file as concatenated programs that each start with a load and end address\r");
+    println!("  --file-types: show the number of identified and total files per file type (SID/PRG/other)\r");
+    println!("  --unidentified-label={{text}}: show the text instead of >> UNIDENTIFIED << for unidentified files\r");
+    println!("  --skipped-label={{text}}: show the text instead of >> TIMEOUT << and >> EMPTY << for skipped files\r");
+    println!("  --stats-include-unidentified: include unidentified files as a row in the player statistics\r");
+    println!("  --songs: show the number of songs (subtunes) of SID files\r");
+    println!("  --sid-only: only scan files with a PSID or RSID header\r");
+    println!("  --show-source: show the config file of the signature that matched\r");
+    println!("  --first-offset-only: only show the offset of the first part of a signature with AND operators\r");
+    println!("  --absolute-paths: show absolute paths of the files instead of relative paths\r");
+    println!("  --wildcard-stats: show the percentage of wildcards in the signature that matched, or exact when no wildcards are used\r");
+    println!("  --confidence: mark matches of weak signatures with a ? after the player name\r");
+    println!("  --songlengths: show song lengths of identified files from the HVSC song lengths database\r");
+    println!("  --fail-fast: stop scanning and exit with an error as soon as an unidentified file is found\r");
+    println!("  --hint: hint when files match more than one player while not scanning with -m\r");
+    println!("  --sort-config: sort the signatures in the config file by name\r");
+    println!("  --dump-parsed: show each parsed signature on a single line, sorted by name\r");
+    println!("  --config-stats: show the distinct wildcard bytes used by the signatures and how often each is used\r");
+    println!("  --test-config: check the EXPECT annotations of the config file by scanning their directories\r");
+    println!("  --false-positive-check={{dir}}: scan the files in the directory, that contain no players, and show each signature that matches\r");
+    println!("  --bundle={{file}}: write the config file and the info file into a single bundle file\r");
+    println!("  --suggest-signature={{dir}}: suggest a signature that is in all files and in none of the files in the directory\r");
+    println!("  --try-configs={{dir}}: scan with every config file in the directory and show which config identified each file\r");
+    println!("  --which: show which config and info file will be used\r");
+    println!("  --examples: show examples of common command lines\r");
+    println!("  --line-ending={{ending}}: line ending when writing signatures: lf, crlf or auto [Default is crlf]\r");
+    println!("  --report: list the signatures of which the operators changed when converting\r");
+    println!("  --newer-than={{file}}: scan only files modified after the given file was modified\r");
+    println!("  --since={{date}}: scan only files modified since date [YYYY-MM-DD or YYYY-MM-DD HH:MM:SS in UTC]\r");
+    println!("  --info-tags={{tags}}: comma separated list of allowed info tags in order, only COMMENT can have multiple lines [use together with -v option]\r");
+    println!("  --summary-format={{format}}: summary format: pretty or kv (key=value on one line) [Default is pretty]\r");
 }
+
+#[cfg(test)]
+#[path = "./main_test.rs"]
+mod main_test;
@@ -0,0 +1,449 @@
+use super::*;
+use crate::fixture::Fixture;
+
+#[test]
+fn format_summary_key_value() {
+    let summary = format_summary(&SummaryFormat::KeyValue, 12, 10, 3, 13, None);
+
+    assert_eq!(summary, "identified_players=12 identified_files=10 unidentified_files=3 total=13\r");
+}
+
+#[test]
+fn format_summary_pretty() {
+    let summary = format_summary(&SummaryFormat::Pretty, 12, 10, 3, 13, None);
+
+    assert_eq!(summary, "Summary:\r\n\
+        Identified players           12\r\n\
+        Identified files             10\r\n\
+        Unidentified files            3\r\n\
+        Total files processed        13\r");
+}
+
+#[test]
+fn format_summary_unique_tunes() {
+    let summary = format_summary(&SummaryFormat::Pretty, 12, 10, 3, 13, Some((8, 10)));
+
+    assert!(summary.ends_with("Total files processed        13\r\n\
+        Identified unique tunes       8\r\n\
+        Unique tunes processed       10\r"));
+
+    let summary = format_summary(&SummaryFormat::KeyValue, 12, 10, 3, 13, Some((8, 10)));
+
+    assert_eq!(summary, "identified_players=12 identified_files=10 unidentified_files=3 total=13 identified_unique_tunes=8 unique_tunes=10\r");
+}
+
+#[test]
+fn format_file_type_totals_sid_and_prg_buffers() {
+    let signature_ids = PlayerId::load_config_lines(&["Test_Player".to_string(), "A9 00 8D".to_string()], None, ConfigSyntax::default()).unwrap();
+    let mut sid_buffer = b"PSID".to_vec();
+    sid_buffer.resize(0x7C, 0);
+
+    let buffers: [(&str, Vec<u8>); 5] = [
+        ("a.sid", [&sid_buffer[..], &[0xA9, 0x00, 0x8D]].concat()),
+        ("b.sid", [&sid_buffer[..], &[0xEA, 0xEA, 0xEA]].concat()),
+        ("c.prg", vec![0x00, 0x10, 0xA9, 0x00, 0x8D]),
+        ("d.prg", vec![0x00, 0x10, 0xA9, 0x00, 0x8D]),
+        ("e.bin", vec![0xEA, 0xEA, 0xEA])
+    ];
+    let file_types = buffers.iter()
+        .map(|(filename, buffer)| (PlayerId::get_buffer_file_type(buffer, Path::new(filename)), !PlayerId::find_players_in_buffer(buffer, &signature_ids, false).is_empty()))
+        .collect::<Vec<(FileType, bool)>>();
+
+    assert_eq!(format_file_type_totals(&file_types).split('\n').skip(3).collect::<Vec<&str>>(), vec![
+        "SID                     1         2\r",
+        "PRG                     2         2\r",
+        "Other                   0         1\r"
+    ]);
+}
+
+#[test]
+fn count_unique_tunes_duplicate_files() {
+    let fixture = Fixture::new();
+    let signature_ids = PlayerId::load_config_lines(&["Test_Player".to_string(), "A9 00 8D".to_string()], None, ConfigSyntax::default()).unwrap();
+
+    let buffers: [&[u8]; 4] = [&[0xA9, 0x00, 0x8D], &[0xA9, 0x00, 0x8D], &[0xEA, 0xEA, 0xEA], &[0xEA, 0xEA, 0xEA]];
+    let mut file_matches = buffers.iter().enumerate()
+        .map(|(index, buffer)| {
+            let filename = fixture.write_file(&format!("{index}.bin"), buffer);
+            FileMatches { matches: PlayerId::find_players_in_buffer(buffer, &signature_ids, false), filename, timed_out: false, empty: false, likely_sid_player: false, content_digest: Some(md5::compute(buffer).0) }
+        })
+        .collect::<Vec<FileMatches>>();
+
+    assert_eq!(count_unique_tunes(&file_matches), (1, 2));
+
+    // files with the same digest but different content are still told apart
+    let filename = fixture.write_file("4.bin", &[0xA9, 0x00, 0x8D, 0x60]);
+    file_matches.push(FileMatches { matches: vec![], filename, timed_out: false, empty: false, likely_sid_player: false, content_digest: file_matches[2].content_digest });
+    assert_eq!(count_unique_tunes(&file_matches), (1, 3));
+}
+
+#[test]
+fn filter_modified_since_excludes_older_files() {
+    let fixture = Fixture::new();
+    let old_file = fixture.write_file("old.sid", &[0]);
+    let new_file = fixture.write_file("new.sid", &[0]);
+
+    let modified_since = timestamp::parse_timestamp("2020-01-01").unwrap();
+    fs::File::options().write(true).open(&old_file).unwrap()
+        .set_modified(timestamp::parse_timestamp("2019-12-31").unwrap()).unwrap();
+
+    let files = vec![old_file.clone(), new_file.clone()];
+    assert_eq!(filter_modified_since(files, modified_since), vec![new_file]);
+}
+
+#[cfg(unix)]
+#[test]
+fn find_players_in_file_with_non_utf8_filename() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let fixture = Fixture::new();
+    let filename = fixture.path.join(std::ffi::OsStr::from_bytes(b"tune\xE9.prg"));
+    fs::write(&filename, [0x00, 0x10, 0xA9, 0x00, 0x8D, 0x18, 0xD4]).unwrap();
+
+    let lines = vec!["Test_Player".to_string(), "A9 00 8D 18 D4".to_string()];
+    let signature_ids = PlayerId::load_config_lines(&lines, None, ConfigSyntax::default()).unwrap();
+    let matches = PlayerId::try_find_players_in_file(&filename, &signature_ids, false);
+    let display_filename = get_display_filename(&filename, fixture.path.to_string_lossy().len() + 1);
+
+    assert_eq!(matches.unwrap()[0].indexes, vec![2]);
+    assert_eq!(display_filename, "tune\u{FFFD}.prg");
+}
+
+#[test]
+fn build_offset_histogram_from_buffers() {
+    let lines = vec!["Test_Player".to_string(), "A9 00 8D 18 D4".to_string()];
+    let signature_ids = PlayerId::load_config_lines(&lines, None, ConfigSyntax::default()).unwrap();
+
+    let signature = [0xA9, 0x00, 0x8D, 0x18, 0xD4];
+    let buffers = [0x0010, 0x0080, 0x0120, 0x0300].map(|offset| {
+        let mut buffer = vec![0; offset];
+        buffer.extend_from_slice(&signature);
+        buffer
+    });
+
+    let offsets = buffers.iter()
+        .filter_map(|buffer| get_first_offset(&PlayerId::find_players_in_buffer(buffer, &signature_ids, false)))
+        .collect::<Vec<usize>>();
+
+    let histogram = build_offset_histogram(&offsets, 0x100);
+
+    assert_eq!(histogram.into_iter().collect::<Vec<(usize, usize)>>(), vec![(0x0000, 2), (0x0100, 1), (0x0300, 1)]);
+}
+
+#[test]
+fn format_offset_histogram_buckets() {
+    let histogram = format_offset_histogram("Test_Player", 0x100, &[0x0010, 0x0080, 0x0120]);
+
+    assert!(histogram.contains("$0000-$00FF         2 ########################################\r"));
+    assert!(histogram.contains("$0100-$01FF         1 ####################\r"));
+}
+
+#[test]
+fn find_conflicts_overlapping_signatures() {
+    let lines = "Loose_Player\nA9 00 8D\n\nStrict_Player\nA9 00 8D 18 D4\n\nOther_Player\nA2 00 BD"
+        .lines().map(|line| line.to_string()).collect::<Vec<String>>();
+    let signature_ids = PlayerId::load_config_lines(&lines, None, ConfigSyntax::default()).unwrap();
+
+    let buffers: [&[u8]; 4] = [
+        &[0xA9, 0x00, 0x8D, 0x18, 0xD4],
+        &[0xA9, 0x00, 0x8D, 0x18, 0xD4, 0xA2, 0x00, 0xBD],
+        &[0xA9, 0x00, 0x8D, 0x20, 0xD0],
+        &[0xA2, 0x00, 0xBD]
+    ];
+
+    let player_info = buffers.iter()
+        .map(|buffer| FileMatches { matches: PlayerId::find_players_in_buffer(buffer, &signature_ids, true), filename: PathBuf::new(), timed_out: false, empty: false, likely_sid_player: false, content_digest: None })
+        .collect::<Vec<FileMatches>>();
+
+    let conflicts = find_conflicts(&player_info, 50);
+
+    assert_eq!(conflicts, vec![
+        ("Loose_Player".to_string(), "Strict_Player".to_string(), 2, 3, 2),
+        ("Loose_Player".to_string(), "Other_Player".to_string(), 1, 3, 2),
+        ("Other_Player".to_string(), "Strict_Player".to_string(), 1, 2, 2)
+    ]);
+    assert_eq!(find_conflicts(&player_info, 100).len(), 1);
+}
+
+fn get_test_player_infos() -> Vec<SignatureInfo> {
+    vec![
+        ("Zeta_Player".to_string(), vec![
+            "   AUTHOR: Someone".to_string(),
+            "  COMMENT: First comment line".to_string(),
+            "           second comment line: with a colon".to_string()
+        ]),
+        ("Alpha_Player".to_string(), vec![
+            "     NAME: Alpha".to_string(),
+            "REFERENCE: https://csdb.dk/release/?id=1".to_string()
+        ]),
+        ("Middle_Player".to_string(), vec![
+            "   AUTHOR: Someone else".to_string()
+        ])
+    ]
+}
+
+#[test]
+fn format_player_infos_in_file_order() {
+    let player_infos = format_player_infos(&get_test_player_infos(), None);
+
+    assert_eq!(player_infos, vec![
+        "Zeta_Player\r\n   AUTHOR: Someone\r\n  COMMENT: First comment line\r\n           second comment line: with a colon".to_string(),
+        "Alpha_Player\r\n     NAME: Alpha\r\nREFERENCE: https://csdb.dk/release/?id=1".to_string(),
+        "Middle_Player\r\n   AUTHOR: Someone else".to_string()
+    ]);
+}
+
+#[test]
+fn format_player_infos_filtered_by_tag() {
+    let player_infos = format_player_infos(&get_test_player_infos(), Some(&vec!["COMMENT".to_string()]));
+    assert_eq!(player_infos, vec![
+        "Zeta_Player\r\n  COMMENT: First comment line\r\n           second comment line: with a colon".to_string()
+    ]);
+
+    let player_infos = format_player_infos(&get_test_player_infos(), Some(&vec!["AUTHOR".to_string()]));
+    assert_eq!(player_infos, vec![
+        "Zeta_Player\r\n   AUTHOR: Someone".to_string(),
+        "Middle_Player\r\n   AUTHOR: Someone else".to_string()
+    ]);
+}
+
+#[test]
+fn to_absolute_paths_unstripped() {
+    let files = to_absolute_paths(vec![PathBuf::from("./C64Music/MUSICIANS/tune.sid")]);
+    let expected_filename = env::current_dir().unwrap().join("C64Music/MUSICIANS/tune.sid");
+
+    assert!(files[0].is_absolute());
+    assert_eq!(get_display_filename(&files[0], 0), expected_filename.to_string_lossy().replace('\\', "/"));
+}
+
+#[test]
+fn get_multiple_match_hint_when_more_players_match() {
+    let lines = "Short_Player\nA9 00 8D\n\nLong_Player\nA9 00 8D 18 D4 60".lines().map(|line| line.to_string()).collect::<Vec<String>>();
+    let signature_ids = PlayerId::load_config_lines(&lines, None, ConfigSyntax::default()).unwrap();
+
+    let buffers: [&[u8]; 2] = [&[0xA9, 0x00, 0x8D, 0x18, 0xD4, 0x60], &[0xA9, 0x00, 0x8D, 0x20, 0xD0]];
+    let to_file_matches = |scan_for_multiple| buffers.iter()
+        .map(|buffer| FileMatches { matches: PlayerId::find_players_in_buffer(buffer, &signature_ids, scan_for_multiple), filename: PathBuf::new(), timed_out: false, empty: false, likely_sid_player: false, content_digest: None })
+        .collect::<Vec<FileMatches>>();
+
+    assert_eq!(get_multiple_match_hint(&to_file_matches(false)), None);
+    assert_eq!(get_multiple_match_hint(&to_file_matches(true)),
+        Some("Hint: 1 of 2 sampled files match more than one player, run with -m for more detail.".to_string()));
+}
+
+#[test]
+fn get_hint_sample_only_identified_files() {
+    let signature_ids = PlayerId::load_config_lines(&["Test_Player".to_string(), "A9 00 8D".to_string()], None, ConfigSyntax::default()).unwrap();
+    let player_info = (0..250).map(|index| {
+        let buffer: &[u8] = if index % 2 == 0 { &[0x00] } else { &[0xA9, 0x00, 0x8D] };
+        FileMatches { matches: PlayerId::find_players_in_buffer(buffer, &signature_ids, false), filename: PathBuf::from(format!("{index}.sid")), timed_out: false, empty: false, likely_sid_player: false, content_digest: None }
+    }).collect::<Vec<FileMatches>>();
+
+    let sample = get_hint_sample(&player_info);
+
+    assert_eq!(sample.len(), 63);
+    assert_eq!(sample[0], PathBuf::from("1.sid"));
+    assert_eq!(sample[1], PathBuf::from("5.sid"));
+}
+
+#[test]
+fn format_bndm_masks_pattern_bits() {
+    let lines = "Test_Player\nA9 00 8D ?? D4 && A9 A9".lines().map(|line| line.to_string()).collect::<Vec<String>>();
+    let signature_ids = PlayerId::load_config_lines(&lines, None, ConfigSyntax::default()).unwrap();
+
+    let masks = format_bndm_masks(&signature_ids);
+
+    assert!(masks.contains("Masks for: Test_Player\r"));
+    assert!(masks.contains("Segment 1: A9 00 8D ?? D4\r"));
+    assert!(masks.contains("  $A9: 10010\r"));
+    assert!(masks.contains("  $00: 01010\r"));
+    assert!(masks.contains("  $8D: 00110\r"));
+    assert!(masks.contains("  $D4: 00011\r"));
+    assert!(masks.contains("  other: 00010\r"));
+    assert!(masks.contains("Segment 2: A9 A9\r"));
+    assert!(masks.contains("  $A9: 11\r"));
+}
+
+#[test]
+fn format_player_name_first_offset_only() {
+    let lines = "Test_Player\nA9 00 8D && 18 D4".lines().map(|line| line.to_string()).collect::<Vec<String>>();
+    let signature_ids = PlayerId::load_config_lines(&lines, None, ConfigSyntax::default()).unwrap();
+    let matches = PlayerId::find_players_in_buffer(&[0xA9, 0x00, 0x8D, 0xEA, 0x18, 0xD4], &signature_ids, false);
+
+    let mut config = Config { display_hex_offset: true, ..Config::default() };
+    assert_eq!(format_player_name(&matches[0], &config, None), "Test_Player $0000 $0004");
+
+    config.first_offset_only = true;
+    assert_eq!(format_player_name(&matches[0], &config, None), "Test_Player $0000");
+}
+
+#[test]
+fn sort_by_specificity_most_specific_first() {
+    let lines = "Short_Player\nA9 00 8D\n\nLong_Player\nA9 00 8D 18 D4 A2 00 BD\n\nMedium_Player\nA9 00 8D 18 D4 A2 ?? ??\n\nAny_Player\nA9 00 8D 18 D4 A2 00 BD && 00 01 02 ANY{1}"
+        .lines().map(|line| line.to_string()).collect::<Vec<String>>();
+    let signature_ids = PlayerId::load_config_lines(&lines, None, ConfigSyntax::default()).unwrap();
+    let mut matches = PlayerId::find_players_in_buffer(&[0xA9, 0x00, 0x8D, 0x18, 0xD4, 0xA2, 0x00, 0xBD], &signature_ids, true);
+
+    sort_by_specificity(&mut matches);
+
+    let names = matches.iter().map(|player| player.signature_name.to_string()).collect::<Vec<String>>();
+    assert_eq!(names, vec!["Long_Player", "Medium_Player", "Any_Player", "Short_Player"]);
+}
+
+#[test]
+fn format_parsed_signatures_sorted_by_name() {
+    let lines = "Second_Player\nA9 ?? 8D 18 D4 END\nA2 00 BD && 9D 00 D4 MINSIZE=512\n\nFirst_Player\nA9 00 8D ANY{1} && 8D 18 D4"
+        .lines().map(|line| line.to_string()).collect::<Vec<String>>();
+    let signature_ids = PlayerId::load_config_lines(&lines, None, ConfigSyntax::default()).unwrap();
+
+    assert_eq!(format_parsed_signatures(&signature_ids), vec![
+        "First_Player: A9 00 8D && 8D 18 D4 ANY{1}",
+        "Second_Player: A2 00 BD && 9D 00 D4 MINSIZE=512",
+        "Second_Player: A9 ?? 8D 18 D4"
+    ]);
+}
+
+#[test]
+fn format_wildcard_stats_distinct_wildcards() {
+    let lines = "First_Player\nA9 ?? 8D\n\nSecond_Player\n00 ?? 01 && 00 01 02 ?? 04\n\nThird_Player\n00 01 ?? 02 && A9 00 8D"
+        .lines().map(|line| line.to_string()).collect::<Vec<String>>();
+    let signature_ids = PlayerId::load_config_lines(&lines, None, ConfigSyntax::default()).unwrap();
+
+    let stats = format_wildcard_stats(&signature_ids);
+
+    assert!(stats.contains("$00                 1\r"));
+    assert!(stats.contains("$02                 1\r"));
+    assert!(stats.contains("$03                 2\r"));
+    assert!(stats.contains("Distinct wildcards           3\r"));
+    assert!(stats.contains("Segments without wildcards   1\r"));
+}
+
+#[test]
+fn format_occurrence_statistics_unidentified_row() {
+    let lines = "Test_Player\nA9 00 8D".lines().map(|line| line.to_string()).collect::<Vec<String>>();
+    let signature_ids = PlayerId::load_config_lines(&lines, None, ConfigSyntax::default()).unwrap();
+    let player_info = [[0xA9, 0x00, 0x8D], [0xA9, 0x00, 0x8D], [0xEA, 0xEA, 0xEA]].iter()
+        .map(|buffer| FileMatches { matches: PlayerId::find_players_in_buffer(buffer, &signature_ids, false), filename: PathBuf::new(), timed_out: false, empty: false, likely_sid_player: false, content_digest: None })
+        .collect::<Vec<FileMatches>>();
+    let unidentified_files = player_info.iter().filter(|file_matches| file_matches.matches.is_empty()).count();
+
+    let statistics = format_occurrence_statistics(&signature_ids, &player_info, Some(unidentified_files));
+    assert!(statistics.contains("Test_Player                   2\r"));
+    assert!(statistics.contains("(unidentified)                1\r"));
+
+    let statistics = format_occurrence_statistics(&signature_ids, &player_info, None);
+    assert!(!statistics.contains("(unidentified)"));
+}
+
+#[test]
+fn format_per_player_files_groups_files_by_player() {
+    let lines = "First_Player\nA9 00 8D\nSecond_Player\nA2 00 BD\nFIRST_PLAYER\n8D 18 D4".lines().map(|line| line.to_string()).collect::<Vec<String>>();
+    let signature_ids = PlayerId::load_config_lines(&lines, None, ConfigSyntax::default()).unwrap();
+    let player_info = [("C64Music/a.sid", &[0xA9, 0x00, 0x8D, 0x18, 0xD4][..]), ("C64Music/b.sid", &[0xA2, 0x00, 0xBD]), ("C64Music/c.sid", &[0xEA]), ("C64Music/d.sid", &[0x8D, 0x18, 0xD4, 0xA2, 0x00, 0xBD])].iter()
+        .map(|(filename, buffer)| FileMatches { matches: PlayerId::find_players_in_buffer(buffer, &signature_ids, true), filename: PathBuf::from(filename), timed_out: false, empty: false, likely_sid_player: false, content_digest: None })
+        .collect::<Vec<FileMatches>>();
+
+    let report = format_per_player_files(&signature_ids, &player_info, "C64Music/".len());
+
+    assert_eq!(report.split('\n').skip(3).collect::<Vec<&str>>(), vec![
+        "First_Player (2)\r", "  a.sid\r", "  d.sid\r",
+        "Second_Player (2)\r", "  b.sid\r", "  d.sid\r"
+    ]);
+}
+
+#[test]
+fn format_occurrence_statistics_mixed_case_names() {
+    let lines = "Test_Player\nA9 00 8D\n\nTEST_PLAYER\nA2 00 BD\n\nOther_Player\nEA EA EA".lines().map(|line| line.to_string()).collect::<Vec<String>>();
+    let signature_ids = PlayerId::load_config_lines(&lines, None, ConfigSyntax::default()).unwrap();
+    let player_info = [vec![0xA9, 0x00, 0x8D], vec![0xA2, 0x00, 0xBD], vec![0xA9, 0x00, 0x8D, 0xA2, 0x00, 0xBD]].iter()
+        .map(|buffer| FileMatches { matches: PlayerId::find_players_in_buffer(buffer, &signature_ids, true), filename: PathBuf::new(), timed_out: false, empty: false, likely_sid_player: false, content_digest: None })
+        .collect::<Vec<FileMatches>>();
+
+    let statistics = format_occurrence_statistics(&signature_ids, &player_info, None);
+    assert!(statistics.contains("Test_Player                   3\r"));
+    assert!(!statistics.contains("TEST_PLAYER"));
+    assert!(!statistics.contains("Other_Player"));
+}
+
+#[test]
+fn find_matches_per_config_two_configs() {
+    let fixture = Fixture::new();
+    fixture.write_file("first.cfg", b"First_Player\r\nA9 00 8D 18 D4\r\n");
+    fixture.write_file("second.cfg", b"Second_Player\r\nA2 00 BD 00 10\r\n");
+    fixture.write_file("readme.txt", b"not a config");
+    let first_file = fixture.write_file("first.prg", &[0x00, 0x10, 0xA9, 0x00, 0x8D, 0x18, 0xD4]);
+    let second_file = fixture.write_file("second.prg", &[0x00, 0x10, 0xA2, 0x00, 0xBD, 0x00, 0x10]);
+    let unidentified_file = fixture.write_file("unidentified.prg", &[0x00, 0x10, 0xEA, 0xEA, 0xEA]);
+
+    let configs = load_configs_in_directory(&fixture.path, ConfigSyntax::default()).unwrap();
+    let matches = find_matches_per_config(&[first_file, second_file, unidentified_file], &configs, false, &Scanner::new());
+
+    let to_names = |config_matches: &Vec<(String, Vec<SignatureMatch>)>| config_matches.iter()
+        .map(|(config_name, matches)| (config_name.to_string(), matches[0].signature_name.to_string()))
+        .collect::<Vec<(String, String)>>();
+
+    assert_eq!(configs.iter().map(|(config_name, _)| config_name.as_str()).collect::<Vec<&str>>(), vec!["first.cfg", "second.cfg"]);
+    assert_eq!(to_names(&matches[0]), vec![("first.cfg".to_string(), "First_Player".to_string())]);
+    assert_eq!(to_names(&matches[1]), vec![("second.cfg".to_string(), "Second_Player".to_string())]);
+    assert!(matches[2].is_empty());
+}
+
+#[test]
+fn format_offset_with_load_address() {
+    assert_eq!(format_offset(0x7E, None), "$007E");
+    assert_eq!(format_offset(0x7E, Some((0x7E, 0x1000))), "file:$007E / mem:$1000");
+    assert_eq!(format_offset(0x84, Some((0x7E, 0x1000))), "file:$0084 / mem:$1006");
+    assert_eq!(format_offset(0x10, Some((0x7E, 0x1000))), "$0010");
+}
+
+#[test]
+fn get_no_files_found_message_causes() {
+    let fixture = Fixture::new();
+    let directory = &fixture.path;
+    let empty_directory = directory.join("empty");
+    fs::create_dir_all(&empty_directory).unwrap();
+    fixture.write_file("sub/tune.sid", &[0x00]);
+    fixture.write_file("readme.txt", &[0x00]);
+
+    let mut config = Config { filename: "*.sid".to_string(), ..Config::default() };
+
+    config.base_path = directory.join("missing").display().to_string();
+    let missing_path_message = get_no_files_found_message(&config);
+
+    config.base_path = empty_directory.display().to_string();
+    let empty_directory_message = get_no_files_found_message(&config);
+
+    config.base_path = directory.display().to_string();
+    let not_recursive_message = get_no_files_found_message(&config);
+
+    config.filename = "*.prg".to_string();
+    let no_match_message = get_no_files_found_message(&config);
+
+    assert!(missing_path_message.starts_with("No file(s) found, path doesn't exist:"));
+    assert!(empty_directory_message.starts_with("No file(s) found, directory is empty:"));
+    assert!(not_recursive_message.starts_with("No file(s) found using pattern '*.sid' in:"));
+    assert!(not_recursive_message.contains("use the -s option to scan recursively."));
+    assert!(no_match_message.starts_with("No file(s) found using pattern '*.prg' in:"));
+    assert!(!no_match_message.contains("-s option"));
+}
+
+#[test]
+fn format_player_lines_flat() {
+    let player_names = ["Laxity_NewPlayer_V21".to_string(), "MoN/FutureComposer".to_string()];
+
+    let lines = format_player_lines("tune.sid", &player_names, true);
+    assert_eq!(lines, vec!["tune.sid Laxity_NewPlayer_V21, MoN/FutureComposer".to_string()]);
+
+    let lines = format_player_lines("tune.sid", &player_names, false);
+    assert_eq!(lines, vec!["tune.sid Laxity_NewPlayer_V21".to_string(), "         MoN/FutureComposer".to_string()]);
+}
+
+#[test]
+fn get_exec_arguments_substitutes_placeholders() {
+    let signature_ids = PlayerId::load_config_lines(&["First_Player".to_string(), "A9 00 8D".to_string(), "Second_Player".to_string(), "8D 18 D4".to_string()], None, ConfigSyntax::default()).unwrap();
+    let players = PlayerId::find_players_in_buffer(&[0xA9, 0x00, 0x8D, 0x18, 0xD4], &signature_ids, true);
+
+    let arguments = get_exec_arguments("tag-tool  --player={player} {file}", Path::new("tunes/tune.sid"), &players);
+
+    assert_eq!(arguments, vec!["tag-tool", "--player=First_Player,Second_Player", "tunes/tune.sid"]);
+}
@@ -3,57 +3,268 @@
 
 #![allow(dead_code)]
 
+mod bundle;
+mod heuristic;
+mod memory_budget;
+mod player_info;
+mod scanner;
 mod signature;
+mod suggest;
 mod validate;
 
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fs::{self, File, read};
 use std::io::{self, BufRead, BufReader, Read};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::mpsc::{self, sync_channel};
+use std::thread;
+use std::time::Duration;
 
 use encoding_rs::WINDOWS_1252;
 use encoding_rs_io::DecodeReaderBytesBuilder;
+use rayon::prelude::*;
 
-use super::sid_file;
+use super::{prg_dump, sid_file, vsf_file};
+use crate::{log, log_error, log_info};
 use signature::Signature;
-pub use signature::{SignatureConfig, SignatureInfo, SignatureMatch};
+pub use player_info::PlayerInfo;
+pub use scanner::Scanner;
+pub use signature::{Confidence, ConfigSyntax, SignatureConfig, SignatureExpectation, SignatureInfo, SignatureMatch};
 
 const DEFAULT_CONFIG_FILE_NAME: &str = "sidid.cfg";
+const BUILTIN_CONFIG: &[u8] = include_bytes!("../config/sidid.cfg");
+const CHUNKED_READ_THRESHOLD: u64 = 64 * 1024 * 1024;
+const READ_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+const HEADER_READ_SIZE: usize = 0x100;
+const PRG_LOAD_ADDRESS_SIZE: usize = 2;
+const STREAMING_CHANNEL_SIZE: usize = 64;
+
+pub const DEFAULT_INFO_TAGS: [&str; 5] = ["NAME", "AUTHOR", "RELEASED", "REFERENCE", "COMMENT"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineEnding {
+    Lf,
+    CrLf,
+    Auto
+}
 
 pub struct PlayerId {}
 
+/// The comment lines of a config file, by where they are written when the config file is rewritten.
+#[derive(Default)]
+struct ConfigComments {
+    header: Vec<String>,
+    per_signature: HashMap<String, Vec<String>>,
+    trailer: Vec<String>
+}
+
+/// The players found in a file.
+pub struct FileMatches {
+    pub matches: Vec<SignatureMatch>,
+    pub filename: PathBuf,
+    pub timed_out: bool,
+    pub empty: bool,
+    /// Whether no player is found but the file writes to the SID chip like a player does. Only set when the caller
+    /// checks for it.
+    pub likely_sid_player: bool,
+    /// The MD5 digest of the content of the file. Only set when the caller asks for it.
+    pub content_digest: Option<[u8; 16]>,
+}
+
+/// Where the search for players in a file starts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScanStart {
+    /// At the start of the data of SID files, after the load address of files with the prg extension, or else at the
+    /// start of the file.
+    Data,
+    /// At the init routine of SID files, with offsets of the matches relative to where the search started.
+    Init,
+    /// After the load address, for program files like those of the Plus/4 regardless of the file extension.
+    Program
+}
+
+/// The format of a file, detected from the header for SID files and from the extension for program files.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FileType {
+    Sid,
+    Prg,
+    Other
+}
+
 impl PlayerId {
     pub fn find_players_in_buffer(buffer: &[u8], signature_ids: &Vec<SignatureConfig>, scan_for_multiple: bool) -> Vec<SignatureMatch> {
         Signature::find_signatures(buffer, 0, signature_ids, scan_for_multiple)
     }
 
-    pub fn find_players_in_file(filename: &str, signature_ids: &Vec<SignatureConfig>, scan_for_multiple: bool) -> Vec<SignatureMatch> {
-        if let Ok(data) = read(filename) {
-            let data_offset = Self::get_data_offset(filename, &data);
+    /// Reads the signatures from the config text and finds the first player in the buffer, which is convenient for
+    /// tests and examples. An error is returned when the config text has no valid signatures.
+    ///
+    /// ```
+    /// use player_id::PlayerId;
+    ///
+    /// let matches = PlayerId::identify(&[0x00, 0xA9, 0x00, 0x8D, 0x18, 0xD4], "Test_Player\nA9 ?? 8D 18 D4").unwrap();
+    ///
+    /// assert_eq!(matches[0].signature_name.as_ref(), "Test_Player");
+    /// assert_eq!(matches[0].indexes, vec![1]);
+    /// ```
+    pub fn identify(buffer: &[u8], config_text: &str) -> Result<Vec<SignatureMatch>, String> {
+        let lines = config_text.lines().map(|line| line.to_string()).collect::<Vec<String>>();
+        let signature_ids = Self::load_config_lines(&lines, None, ConfigSyntax::default())?;
+        Ok(Self::find_players_in_buffer(buffer, &signature_ids, false))
+    }
 
-            Signature::find_signatures(&data, data_offset, signature_ids, scan_for_multiple)
-        } else {
-            vec![]
-        }
+    pub fn find_players_in_vsf(buffer: &[u8], signature_ids: &Vec<SignatureConfig>, scan_for_multiple: bool) -> Result<Vec<SignatureMatch>, String> {
+        let ram = vsf_file::get_ram(buffer)?;
+        Ok(Signature::find_signatures(ram, 0, signature_ids, scan_for_multiple))
+    }
+
+    /// Finds the players in each program of a dump of concatenated programs, where each program starts with its load
+    /// address and end address. The offsets of the matches are relative to the start of the data of each program.
+    pub fn find_players_in_dump(buffer: &[u8], signature_ids: &Vec<SignatureConfig>, scan_for_multiple: bool) -> Result<Vec<Vec<SignatureMatch>>, String> {
+        let segments = prg_dump::get_segments(buffer)?;
+        Ok(segments.iter().map(|segment| Signature::find_signatures(segment, 0, signature_ids, scan_for_multiple)).collect())
+    }
+
+    pub fn find_players_in_file(filename: &Path, signature_ids: &Vec<SignatureConfig>, scan_for_multiple: bool) -> Vec<SignatureMatch> {
+        Self::try_find_players_in_file(filename, signature_ids, scan_for_multiple).unwrap_or_default()
+    }
+
+    pub fn try_find_players_in_file(filename: &Path, signature_ids: &Vec<SignatureConfig>, scan_for_multiple: bool) -> Result<Vec<SignatureMatch>, String> {
+        Self::try_find_players_in_file_at_stride(filename, signature_ids, scan_for_multiple, 1)
+    }
+
+    /// Scans the files in parallel in the background and returns an iterator that yields the matches of each file as soon
+    /// as it has been scanned, so the order is not the order of the files. At most a fixed number of results is kept
+    /// waiting when the caller consumes them slower than they are found, and scanning stops when the iterator is
+    /// dropped. Files that can't be read yield no matches, just like `find_players_in_file`.
+    pub fn scan_files_streaming(files: Vec<PathBuf>, signature_ids: Arc<Vec<SignatureConfig>>, scan_for_multiple: bool) -> mpsc::IntoIter<FileMatches> {
+        let (sender, receiver) = sync_channel(STREAMING_CHANNEL_SIZE);
+
+        thread::spawn(move || {
+            // sending fails once the receiver is dropped, which stops the remaining files from being scanned
+            let _ = files.par_iter().try_for_each_with(sender, |sender, filename| {
+                sender.send(FileMatches {
+                    matches: Self::find_players_in_file(filename, &signature_ids, scan_for_multiple),
+                    filename: filename.to_owned(),
+                    timed_out: false,
+                    empty: fs::metadata(filename).is_ok_and(|metadata| metadata.len() == 0),
+                    likely_sid_player: false,
+                    content_digest: None,
+                })
+            });
+        });
+
+        receiver.into_iter()
+    }
+
+    /// Same as `try_find_players_in_file`, but signatures that aren't found are searched again with the given stride.
+    /// The stride is not applied to files that are large enough to be read in chunks.
+    pub fn try_find_players_in_file_at_stride(filename: &Path, signature_ids: &Vec<SignatureConfig>, scan_for_multiple: bool, stride: usize) -> Result<Vec<SignatureMatch>, String> {
+        Self::try_find_players_in_file_from(filename, signature_ids, scan_for_multiple, stride, ScanStart::Data)
+    }
+
+    /// Same as `try_find_players_in_file_at_stride`, but for SID files the search starts at the init routine instead of at
+    /// the start of the data, when the init address points into the data. The offsets of the matches are relative to
+    /// where the search started.
+    pub fn try_find_players_in_file_from_init(filename: &Path, signature_ids: &Vec<SignatureConfig>, scan_for_multiple: bool, stride: usize) -> Result<Vec<SignatureMatch>, String> {
+        Self::try_find_players_in_file_from(filename, signature_ids, scan_for_multiple, stride, ScanStart::Init)
+    }
+
+    /// Same as `try_find_players_in_file_at_stride`, but the search starts at the given start of the file.
+    pub fn try_find_players_in_file_from(filename: &Path, signature_ids: &Vec<SignatureConfig>, scan_for_multiple: bool, stride: usize, scan_start: ScanStart) -> Result<Vec<SignatureMatch>, String> {
+        Self::try_find_players_in_file_within(filename, signature_ids, scan_for_multiple, stride, scan_start, None)
+            .map(Option::unwrap_or_default)
+    }
+
+    /// Same as `try_find_players_in_file_from`, but gives up searching when the timeout has passed, in which case `None`
+    /// is returned. The timeout is checked between signatures, so a single signature search is never interrupted.
+    /// Files that are large enough to be read in chunks are always searched completely.
+    pub fn try_find_players_in_file_within(filename: &Path, signature_ids: &Vec<SignatureConfig>, scan_for_multiple: bool, stride: usize, scan_start: ScanStart, timeout: Option<Duration>) -> Result<Option<Vec<SignatureMatch>>, String> {
+        Self::try_find_players_in_file_skipping(filename, signature_ids, scan_for_multiple, stride, scan_start, timeout, &[])
+    }
+
+    /// Same as `try_find_players_in_file_within`, but players are not found when any of the bytes of their signature lie
+    /// within one of the skip regions. See `Scanner::try_find_players_in_file_skipping`.
+    pub fn try_find_players_in_file_skipping(filename: &Path, signature_ids: &Vec<SignatureConfig>, scan_for_multiple: bool, stride: usize, scan_start: ScanStart, timeout: Option<Duration>, skip_regions: &[Range<usize>]) -> Result<Option<Vec<SignatureMatch>>, String> {
+        let mut scanner = Scanner::new();
+        scanner.stride = stride;
+        scanner.scan_start = scan_start;
+        scanner.timeout = timeout;
+        scanner.skip_regions = skip_regions.to_vec();
+        scanner.try_find_players_in_file(filename, signature_ids, scan_for_multiple)
+    }
+
+    /// Replaces the operands of instructions with an absolute address in the signatures by wildcards, so that players
+    /// are also found when they are relocated to another address.
+    pub fn mask_absolute_addresses(signature_ids: &mut [SignatureConfig]) {
+        signature_ids.iter_mut().for_each(Signature::mask_absolute_addresses);
     }
 
     pub fn find_player_info<'a>(signature_infos: &'a [SignatureInfo], player_name: &str) -> Option<&'a SignatureInfo> {
         Signature::find_signature_info(signature_infos, player_name)
     }
 
-    pub fn is_config_file(filename: &str) -> bool {
+    pub fn is_config_file(filename: &str, syntax: ConfigSyntax) -> bool {
         if let Ok(path) = PlayerId::get_config_path_with_fallback(filename) {
             if let Ok(file) = File::open(path) {
                 let lines = Self::get_first_few_lines_from_file(file);
-                return Signature::is_config_file(&lines);
+                return Signature::is_config_file(&lines, syntax);
             }
         }
         false
     }
 
-    pub fn load_config_file(config_path: &PathBuf, player_name: Option<&String>) -> Result<Vec<SignatureConfig>, String> {
-        let lines = Self::read_text_file(config_path)?;
-        let signature_ids = Signature::read_config_lines(&lines, player_name)?;
+    /// Reads the signatures of the config file with the given syntax. The path of the config file is set as the source
+    /// of each signature, so that signatures of several config files can be told apart.
+    pub fn load_config_file(config_path: &PathBuf, player_name: Option<&String>, syntax: ConfigSyntax) -> Result<Vec<SignatureConfig>, String> {
+        Self::load_config_files(std::slice::from_ref(config_path), player_name, syntax)
+    }
+
+    /// Same as `load_config_file`, but the signatures of all config files are merged in the order of the files. When a
+    /// player name is given, it only has to be found in one of the config files.
+    pub fn load_config_files(config_paths: &[PathBuf], player_name: Option<&String>, syntax: ConfigSyntax) -> Result<Vec<SignatureConfig>, String> {
+        let mut signature_ids = vec![];
+
+        for config_path in config_paths {
+            let lines = Self::read_config_file_lines(config_path)?;
+            let mut config_signature_ids = Signature::read_config_lines(&lines, player_name, syntax)?;
+
+            let source: Arc<str> = Arc::from(config_path.display().to_string());
+            for signature_id in &mut config_signature_ids {
+                signature_id.source = Some(Arc::clone(&source));
+            }
+            signature_ids.append(&mut config_signature_ids);
+        }
+        Self::check_signatures_found(signature_ids, player_name)
+    }
+
+    /// Reads the EXPECT annotations of the config file. Directories that aren't absolute are relative to the directory of
+    /// the config file.
+    pub fn load_config_expectations(config_path: &PathBuf) -> Result<Vec<SignatureExpectation>, String> {
+        let lines = Self::read_config_file_lines(config_path)?;
+        let config_directory = config_path.parent().unwrap_or(Path::new(""));
+
+        let mut expectations = Signature::read_expectations(&lines)?;
+        for expectation in &mut expectations {
+            expectation.directory = config_directory.join(&expectation.directory);
+        }
+        Ok(expectations)
+    }
+
+    pub fn load_builtin_config(player_name: Option<&String>) -> Result<Vec<SignatureConfig>, String> {
+        let (config_text, _, _) = WINDOWS_1252.decode(BUILTIN_CONFIG);
+        let lines = config_text.lines().map(|line| line.to_string()).collect::<Vec<String>>();
+        Self::load_config_lines(&lines, player_name, ConfigSyntax::default())
+    }
+
+    pub fn load_config_lines(lines: &[String], player_name: Option<&String>, syntax: ConfigSyntax) -> Result<Vec<SignatureConfig>, String> {
+        Self::check_signatures_found(Signature::read_config_lines(lines, player_name, syntax)?, player_name)
+    }
+
+    fn check_signatures_found(signature_ids: Vec<SignatureConfig>, player_name: Option<&String>) -> Result<Vec<SignatureConfig>, String> {
         if signature_ids.is_empty() {
             return Err(if let Some(player_name) = player_name {
                 format!("No signature found with name: {}", player_name)
@@ -64,18 +275,29 @@ impl PlayerId {
         Ok(signature_ids)
     }
 
-    pub fn load_info_file(config_path: &PathBuf) -> Result<Vec<SignatureInfo>, String> {
-        let lines = Self::read_text_file(config_path)?;
-        let signature_infos = Signature::read_info_lines(&lines)?;
+    pub fn load_info_file(config_path: &PathBuf, syntax: ConfigSyntax) -> Result<Vec<SignatureInfo>, String> {
+        let lines = Self::read_info_file_lines(config_path)?;
+        let signature_infos = Signature::read_info_lines(&lines, syntax)?;
         if signature_infos.is_empty() {
             return Err("No info sections defined.".to_string());
         }
         Ok(signature_infos)
     }
 
-    pub fn get_info_file_path(config_file: Option<&String>) -> Result<PathBuf, String> {
-        let config_path_string = PlayerId::get_config_path(config_file)?.display().to_string().replace(".cfg", ".nfo");
-        PlayerId::get_config_path_with_fallback(&config_path_string)
+    pub fn get_info_file_path(config_file: Option<&String>, info_file: Option<&String>) -> Result<PathBuf, String> {
+        let info_file = match info_file {
+            Some(info_file) if info_file.is_empty() => return Err("No filename provided for info file.".to_string()),
+            Some(info_file) => info_file.to_string(),
+            None => {
+                let config_path = PlayerId::get_config_path(config_file)?;
+                if Self::is_bundle_file(&config_path) {
+                    return Ok(config_path);
+                }
+                config_path.display().to_string().replace(".cfg", ".nfo")
+            }
+        };
+
+        PlayerId::get_config_path_with_fallback(&info_file)
     }
 
     pub fn get_config_path(config_file: Option<&String>) -> Result<PathBuf, String> {
@@ -91,49 +313,289 @@ impl PlayerId {
         PlayerId::get_config_path_with_fallback(&config_file)
     }
 
-    pub fn convert_file_format(config_file: Option<&String>, new_format: bool) -> Result<(), String> {
-        let issues_found = Self::verify_signatures(config_file)?;
+    /// Same as `get_config_path`, but the file name of the config file can be a pattern like configs/*.cfg, in which case
+    /// the matching config files are returned sorted by name. The pattern is resolved relative to the current directory,
+    /// or else relative to the directory of the executable.
+    pub fn get_config_paths(config_file: Option<&String>) -> Result<Vec<PathBuf>, String> {
+        let Some(pattern) = config_file.filter(|config_file| config_file.contains(['*', '?', '['])) else {
+            return Ok(vec![Self::get_config_path(config_file)?]);
+        };
+
+        let pattern_path = Path::new(pattern);
+        let directory = pattern_path.parent().filter(|directory| !directory.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        let file_pattern = pattern_path.file_name().map(|file_pattern| file_pattern.to_string_lossy()).unwrap_or_default();
+
+        let mut config_paths = Self::find_config_files(directory, &file_pattern);
+        if config_paths.is_empty() && directory.is_relative() {
+            config_paths = Self::find_config_files(&Self::get_executable_directory()?.join(directory), &file_pattern);
+        }
+
+        if config_paths.is_empty() {
+            return Err(format!("No config files found matching: {pattern}"));
+        }
+        Ok(config_paths)
+    }
+
+    fn find_config_files(directory: &Path, file_pattern: &str) -> Vec<PathBuf> {
+        let Ok(walker) = globwalk::GlobWalkerBuilder::from_patterns(directory, &[file_pattern]).max_depth(1).case_insensitive(true).build() else {
+            return vec![];
+        };
+
+        let mut config_paths = walker
+            .filter_map(Result::ok)
+            .map(|entry| entry.into_path())
+            .filter(|path| path.is_file())
+            .collect::<Vec<PathBuf>>();
+        config_paths.sort();
+        config_paths
+    }
+
+    /// Rewrites the config file in the new or old format. When `report` is set the signatures whose operators changed are
+    /// listed, e.g. signatures that used AND or END in a file that otherwise uses &&.
+    pub fn convert_file_format(config_file: Option<&String>, new_format: bool, line_ending: LineEnding, report: bool, syntax: ConfigSyntax) -> Result<(), String> {
+        let issues_found = Self::verify_signatures(config_file, syntax)?;
+        if issues_found {
+            return Err("Issues found in config file.".to_string());
+        }
+
+        log_info!("\r\nWriting signatures in {} format.\r", if new_format { "new" } else { "old" });
+
+        let config_path = PlayerId::get_config_path(config_file)?;
+        log_info!("Writing config file to: {}\r", config_path.display());
+
+        let (lines, info_section) = Self::read_config_file_to_rewrite(&config_path)?;
+        let signature_ids = Signature::read_config_lines(&lines, None, syntax)?;
+
+        if report {
+            let converted_lines = Self::convert_ids_to_string(Signature::read_config_lines(&lines, None, syntax)?, new_format, LineEnding::Lf, &HashMap::new())
+                .lines().map(|line| line.to_string()).collect::<Vec<String>>();
+
+            for signature_name in Self::get_changed_operator_styles(&lines, &converted_lines, syntax) {
+                log_info!("Operators changed: {signature_name}\r");
+            }
+        }
+
+        Self::write_config_file(&config_path, signature_ids, new_format, line_ending, &Self::get_config_comments(&lines, syntax), info_section.as_deref())?;
+
+        log_info!("Done!\r");
+        Ok(())
+    }
+
+    /// Returns the names of the signatures of which the AND, && and END operators differ between the original and the
+    /// converted lines. The signatures are compared in order, so the converted lines must be in the same order.
+    fn get_changed_operator_styles(original_lines: &[String], converted_lines: &[String], syntax: ConfigSyntax) -> Vec<String> {
+        let original_operators = Self::get_operators_per_signature(original_lines, syntax);
+        let converted_operators = Self::get_operators_per_signature(converted_lines, syntax);
+
+        original_operators.into_iter()
+            .zip(converted_operators)
+            .filter(|(original, converted)| original.1 != converted.1)
+            .map(|(original, _)| original.0)
+            .collect()
+    }
+
+    fn get_operators_per_signature(lines: &[String], syntax: ConfigSyntax) -> Vec<(String, Vec<String>)> {
+        let mut operators_per_signature: Vec<(String, Vec<String>)> = vec![];
+
+        for (_, line) in Signature::join_continuation_lines(lines) {
+            let signature_text = line.trim();
+
+            if !Signature::is_signature_min_length(signature_text, syntax) {
+                continue;
+            }
+
+            if Signature::is_signature_name(signature_text, syntax) {
+                operators_per_signature.push((signature_text.to_string(), vec![]));
+            } else if let Some((_, operators)) = operators_per_signature.last_mut() {
+                operators.extend(signature_text.split_ascii_whitespace()
+                    .map(|word| word.to_ascii_uppercase())
+                    .filter(|word| word == "AND" || word == "&&" || word == "END"));
+            }
+        }
+        operators_per_signature
+    }
+
+    /// Writes the signatures of the config file and the info of the info file into a single bundle file, which can be
+    /// used as the config file and as the info file. The signatures are written in the new format, together with the
+    /// comments of the config file.
+    pub fn write_bundle(config_file: Option<&String>, info_file: Option<&String>, bundle_path: &Path, line_ending: LineEnding, syntax: ConfigSyntax) -> Result<(), String> {
+        let config_path = Self::get_config_path(config_file)?;
+        let config_lines = Self::read_config_file_lines(&config_path)?;
+        let signature_ids = Self::load_config_lines(&config_lines, None, syntax)?;
+        let signature_infos = Self::load_info_file(&Self::get_info_file_path(config_file, info_file)?, syntax)?;
+
+        let line_ending = if line_ending == LineEnding::Auto {
+            let data = read(&config_path).map_err(|_| format!("Error reading file: {}", config_path.display()))?;
+            Self::detect_line_ending(&data)
+        } else {
+            line_ending
+        };
+
+        let mut bundle_config_lines = Self::get_comment_lines(&config_lines);
+        if !bundle_config_lines.is_empty() {
+            bundle_config_lines.push("".to_string());
+        }
+        bundle_config_lines.extend(Self::convert_ids_to_string(signature_ids, true, line_ending, &HashMap::new()).lines().map(str::to_string));
+        bundle_config_lines.push("".to_string());
+
+        let mut bundle_info_lines = vec![];
+        for signature_info in &signature_infos {
+            bundle_info_lines.push(signature_info.0.to_string());
+            bundle_info_lines.extend(PlayerInfo::parse(signature_info).to_info_lines());
+            bundle_info_lines.push("".to_string());
+        }
+
+        let line_ending = if line_ending == LineEnding::Lf { "\n" } else { "\r\n" };
+        let bundle_lines = bundle::create_bundle(&bundle_config_lines, &bundle_info_lines);
+        fs::write(bundle_path, bundle_lines.join(line_ending) + line_ending).map_err(|write_error| format!("Error writing bundle file: {write_error}"))
+    }
+
+    /// Rewrites the config file with the signatures sorted by name. Sub signatures stay with the signature they follow.
+    /// The format of the file is kept, based on whether END tokens are used.
+    pub fn sort_config_file(config_file: Option<&String>, line_ending: LineEnding, syntax: ConfigSyntax) -> Result<(), String> {
+        let issues_found = Self::verify_signatures(config_file, syntax)?;
         if issues_found {
             return Err("Issues found in config file.".to_string());
         }
 
-        eprintln!("\r\nWriting signatures in {} format.\r", if new_format { "new" } else { "old" });
+        log_info!("\r\nSorting signatures by name.\r");
 
         let config_path = PlayerId::get_config_path(config_file)?;
-        eprintln!("Writing config file to: {}\r", config_path.display());
+        log_info!("Writing config file to: {}\r", config_path.display());
 
-        let lines = Self::read_text_file(&config_path)?;
-        let signature_ids = Signature::read_config_lines(&lines, None)?;
+        let (lines, info_section) = Self::read_config_file_to_rewrite(&config_path)?;
+        let new_format = !lines.iter().any(|line| Signature::has_end_marker(line.trim()));
+        let signature_ids = Self::sort_signature_ids(Signature::read_config_lines(&lines, None, syntax)?);
 
-        let output_string = Self::convert_ids_to_string(signature_ids, new_format);
+        Self::write_config_file(&config_path, signature_ids, new_format, line_ending, &Self::get_config_comments(&lines, syntax), info_section.as_deref())?;
 
-        let write_result = fs::write(config_path, output_string);
+        log_info!("Done!\r");
+        Ok(())
+    }
+
+    fn sort_signature_ids(signature_ids: Vec<SignatureConfig>) -> Vec<SignatureConfig> {
+        let mut signature_groups: Vec<Vec<SignatureConfig>> = vec![];
+
+        for signature in signature_ids {
+            match signature_groups.last_mut() {
+                Some(signature_group) if signature.signature_name.starts_with('(') || signature_group[0].signature_name == signature.signature_name => {
+                    signature_group.push(signature);
+                },
+                _ => signature_groups.push(vec![signature])
+            }
+        }
+
+        signature_groups.sort_by_key(|signature_group| signature_group[0].signature_name.to_ascii_lowercase());
+        signature_groups.into_iter().flatten().collect()
+    }
+
+    fn get_comment_lines(lines: &[String]) -> Vec<String> {
+        lines.iter().filter(|line| Signature::is_comment(line)).map(|line| line.trim_end().to_string()).collect()
+    }
+
+    /// Splits the comment lines of a config file into the comments at the start of the file, which are separated from
+    /// the first signature by an empty line, the comments before each signature name and the comments after the last
+    /// signature.
+    fn get_config_comments(lines: &[String], syntax: ConfigSyntax) -> ConfigComments {
+        let mut comments = ConfigComments::default();
+        let mut comment_lines = vec![];
+        let mut is_before_first_signature = true;
+
+        for line in lines {
+            let text = line.trim();
+            if Signature::is_comment(text) {
+                comment_lines.push(line.trim_end().to_string());
+            } else if text.is_empty() {
+                if is_before_first_signature {
+                    comments.header.append(&mut comment_lines);
+                }
+            } else if Signature::is_signature_name(text, syntax) {
+                is_before_first_signature = false;
+                if !comment_lines.is_empty() {
+                    comments.per_signature.entry(text.to_string()).or_default().append(&mut comment_lines);
+                }
+            }
+        }
+        comments.trailer = comment_lines;
+        comments
+    }
+
+    /// Writes the signatures to the config file, with each comment before the signature that it was written before, so
+    /// that comments stay with their signature when the signatures are sorted.
+    fn write_config_file(config_path: &Path, signature_ids: Vec<SignatureConfig>, new_format: bool, line_ending: LineEnding, comments: &ConfigComments, info_section: Option<&[u8]>) -> Result<(), String> {
+        let line_ending = if line_ending == LineEnding::Auto {
+            let data = read(config_path).map_err(|_| format!("Error reading file: {}", config_path.display()))?;
+            Self::detect_line_ending(&data)
+        } else {
+            line_ending
+        };
+
+        let mut output_string = Self::convert_ids_to_string(signature_ids, new_format, line_ending, &comments.per_signature);
+        let line_ending = if line_ending == LineEnding::Lf { "\n" } else { "\r\n" };
+        if !comments.header.is_empty() {
+            output_string = comments.header.join(line_ending) + line_ending + line_ending + &output_string;
+        }
+        if !comments.trailer.is_empty() {
+            output_string += &(line_ending.to_string() + &comments.trailer.join(line_ending) + line_ending);
+        }
+
+        let mut output = output_string.into_bytes();
+        if let Some(info_section) = info_section {
+            output = [bundle::CONFIG_SECTION_MARKER.as_bytes(), line_ending.as_bytes(), &output, info_section].concat();
+        }
+
+        let write_result = fs::write(config_path, output);
         if let Err(write_error) = write_result {
             return Err(format!("Error writing config file: {write_error}"));
         }
-
-        eprintln!("Done!\r");
         Ok(())
     }
 
-    fn convert_ids_to_string(signature_ids: Vec<SignatureConfig>, new_format: bool) -> String {
+    fn detect_line_ending(data: &[u8]) -> LineEnding {
+        let line_feeds = data.iter().filter(|&&byte| byte == b'\n').count();
+        let carriage_return_line_feeds = data.windows(2).filter(|&window| window == b"\r\n").count();
+
+        if line_feeds - carriage_return_line_feeds > carriage_return_line_feeds {
+            LineEnding::Lf
+        } else {
+            LineEnding::CrLf
+        }
+    }
+
+    /// Converts the signatures to the lines of a config file, with the given comment lines before each signature name.
+    fn convert_ids_to_string(signature_ids: Vec<SignatureConfig>, new_format: bool, line_ending: LineEnding, comments_per_signature: &HashMap<String, Vec<String>>) -> String {
+        let line_ending = if line_ending == LineEnding::Lf { "\n" } else { "\r\n" };
         let mut output_strings = vec![];
         let mut previous_signature_name = "".to_string();
+        let mut written_comments = HashSet::new();
 
         for signature in signature_ids {
-            if signature.signature_name.ne(&previous_signature_name) {
+            if signature.signature_name.as_ref().ne(&previous_signature_name) {
                 if !output_strings.is_empty() && !signature.signature_name.starts_with('(') {
-                    output_strings.push("\r\n".to_string() + &signature.signature_name);
-                } else {
-                    output_strings.push(signature.signature_name.to_owned());
+                    output_strings.push("".to_string());
                 }
+                if let Some(comment_lines) = comments_per_signature.get(signature.signature_name.as_ref()).filter(|_| written_comments.insert(signature.signature_name.to_string())) {
+                    output_strings.extend(comment_lines.iter().cloned());
+                }
+                output_strings.push(signature.signature_name.to_string());
             }
 
-            previous_signature_name = signature.signature_name;
-            let mut output_string = "".to_string();
+            previous_signature_name = signature.signature_name.to_string();
+            output_strings.push(Self::format_signature_value(&signature, new_format));
+        }
+        output_strings.join(line_ending) + line_ending
+    }
+
+    /// Formats the values of a parsed signature the way they are written to a config file.
+    pub fn format_signature_value(signature: &SignatureConfig, new_format: bool) -> String {
+        let mut output_string = "".to_string();
 
-            for bndm_config in signature.bndm_configs {
-                if !output_string.is_empty() {
+        if !signature.entry_opcodes.is_empty() {
+            output_string += &signature.entry_opcodes.iter().map(|opcode| format!("{opcode:02X}")).collect::<Vec<String>>().join(" ");
+            output_string += " ENTRY";
+        } else {
+            for (index, bndm_config) in signature.bndm_configs.iter().enumerate() {
+                if index > 0 {
                     output_string += if new_format { " && " } else { " AND " };
                 }
 
@@ -149,85 +611,248 @@ impl PlayerId {
                     .collect::<Vec<String>>()
                     .join(" ");
             }
+        }
 
-            if !new_format {
-                output_string += " END";
-            }
+        if let Some(any_threshold) = signature.any_threshold {
+            output_string += &format!(" ANY{{{any_threshold}}}");
+        }
+
+        if signature.min_size > 0 {
+            output_string += &format!(" MINSIZE={}", signature.min_size);
+        }
+
+        if signature.allow_overlap {
+            output_string += " OVERLAP";
+        }
 
-            output_strings.push(output_string);
+        if !new_format {
+            output_string += " END";
         }
-        output_strings.join("\r\n") + "\r\n"
+        output_string
     }
 
-    pub fn verify_signatures(config_file: Option<&String>) -> Result<bool, String> {
-        eprintln!("Checking signatures...\r");
+    pub fn verify_signatures(config_file: Option<&String>, syntax: ConfigSyntax) -> Result<bool, String> {
+        log_info!("Checking signatures...\r");
 
         let config_path = PlayerId::get_config_path(config_file)?;
-        eprintln!("Verify config file: {}\r\n\r", config_path.display());
+        log_info!("Verify config file: {}\r\n\r", config_path.display());
 
-        let lines = Self::read_text_file(&config_path)?;
-        let issues_found = validate::verify_config_file(&lines)?;
+        let lines = Self::read_config_file_lines(&config_path)?;
+        let issues_found = validate::verify_config_file(&lines, syntax)?;
 
         if !issues_found {
-            eprintln!("No issues found in configuration.\r");
+            log_info!("No issues found in configuration.\r");
+        }
+
+        let ordered_signature_count = Self::read_config_lines_to_verify(&lines, syntax).map_or(0, |signatures| validate::count_ordered_signatures(&signatures));
+        if ordered_signature_count > 0 {
+            log_info!("Note: {ordered_signature_count} signature(s) with AND operators only match when the sub signatures are found in order. Use the ANY{{N}} combinator or the --any-order option to match them in any order.\r");
         }
         Ok(issues_found)
     }
 
-    pub fn verify_signature_info(config_file: Option<&String>) -> Result<bool, String> {
-        eprintln!("\r\nChecking info file...\r");
+    /// Reads the signatures without warnings about invalid values, as these are already reported by the verification.
+    fn read_config_lines_to_verify(lines: &[String], syntax: ConfigSyntax) -> Result<Vec<SignatureConfig>, String> {
+        log::buffered(|| Signature::read_config_lines(lines, None, ConfigSyntax { strict: false, ..syntax })).0
+    }
+
+    pub fn verify_signature_info(config_file: Option<&String>, info_file: Option<&String>, info_tags: Option<&Vec<String>>, syntax: ConfigSyntax) -> Result<bool, String> {
+        log_info!("\r\nChecking info file...\r");
 
         let config_path = PlayerId::get_config_path(config_file)?;
-        let lines = Self::read_text_file(&config_path)?;
-        let signature_ids = Signature::read_config_lines(&lines, None)?;
+        let lines = Self::read_config_file_lines(&config_path)?;
+        let signature_ids = Self::read_config_lines_to_verify(&lines, syntax)?;
 
-        let config_path_string = config_path.display().to_string().replace(".cfg", ".nfo");
-        let config_path = PlayerId::get_config_path_with_fallback(&config_path_string);
+        let config_path_string = info_file.cloned().unwrap_or_else(|| config_path.display().to_string().replace(".cfg", ".nfo"));
+        let config_path = PlayerId::get_info_file_path(config_file, info_file);
 
         if let Ok(config_path) = config_path {
-            eprintln!("Verify info file: {}\r\n\r", config_path.display());
+            log_info!("Verify info file: {}\r\n\r", config_path.display());
 
-            let lines = Self::read_text_file(&config_path)?;
-            let issues_found = validate::verify_info_file(&lines, &signature_ids)?;
+            let lines = Self::read_info_file_lines(&config_path)?;
+            let info_tags = info_tags.cloned().unwrap_or_else(|| DEFAULT_INFO_TAGS.map(str::to_string).to_vec());
+            let issues_found = validate::verify_info_file(&lines, &signature_ids, &info_tags, syntax)?;
 
             if !issues_found {
-                eprintln!("No issues found in info file.\r");
+                log_info!("No issues found in info file.\r");
             }
             Ok(issues_found)
         } else {
-            eprintln!("\r\nNo info file found: {config_path_string}\r");
+            log_error!("\r\nNo info file found: {config_path_string}\r");
             Ok(true)
         }
     }
 
-    fn get_data_offset(filename: &str, data: &[u8]) -> usize {
-        if sid_file::is_sid_file(data) {
-            sid_file::get_data_offset(data)
-        } else if filename.ends_with(".prg") { 2 } else { 0 }
+    /// Verifies the config file and the info file in parallel and writes the diagnostics of the config file before those of the info file.
+    pub fn verify_signatures_and_info(config_file: Option<&String>, info_file: Option<&String>, info_tags: Option<&Vec<String>>, syntax: ConfigSyntax) -> Result<bool, String> {
+        let (issues_found, messages) = Self::verify_signatures_and_info_buffered(config_file, info_file, info_tags, syntax);
+        log::flush(&messages);
+        issues_found
     }
 
-    fn get_config_path_with_fallback(filename: &str) -> Result<PathBuf, String> {
-        let file = Path::new(filename);
-        if file.exists() {
-            return Ok(file.to_path_buf())
+    fn verify_signatures_and_info_buffered(config_file: Option<&String>, info_file: Option<&String>, info_tags: Option<&Vec<String>>, syntax: ConfigSyntax) -> (Result<bool, String>, Vec<String>) {
+        let ((config_issues_found, mut messages), (info_issues_found, info_messages)) = thread::scope(|scope| {
+            let info_verification = scope.spawn(|| log::buffered(|| Self::verify_signature_info(config_file, info_file, info_tags, syntax)));
+            let config_verification = log::buffered(|| Self::verify_signatures(config_file, syntax));
+            (config_verification, info_verification.join().unwrap())
+        });
+
+        let issues_found = config_issues_found.and_then(|config_issues_found| {
+            messages.extend(info_messages);
+            info_issues_found.map(|info_issues_found| config_issues_found | info_issues_found)
+        });
+        (issues_found, messages)
+    }
+
+    /// Suggests a signature with the given name for the player of the positive files, made of the shortest byte sequence
+    /// that is in the data of all positive files and in none of the negative files. `None` is returned when there is no
+    /// such sequence.
+    pub fn suggest_signature(positive_files: &[PathBuf], negative_files: &[PathBuf], signature_name: &str) -> Result<Option<SignatureConfig>, String> {
+        let read_data = |filename: &PathBuf| {
+            let data = read(filename).map_err(|error| format!("Could not read file {}: {error}", filename.display()))?;
+            let data_offset = Self::get_data_offset(filename, &data).min(data.len());
+            Ok(data[data_offset..].to_vec())
+        };
+
+        let positives = positive_files.iter().map(read_data).collect::<Result<Vec<Vec<u8>>, String>>()?;
+        let negatives = negative_files.iter().map(read_data).collect::<Result<Vec<Vec<u8>>, String>>()?;
+
+        suggest::suggest_signature(&positives.iter().map(Vec::as_slice).collect::<Vec<&[u8]>>(), &negatives.iter().map(Vec::as_slice).collect::<Vec<&[u8]>>(), signature_name)
+    }
+
+    /// Returns whether the data of the file writes to several registers of the SID chip, as a hint that the file has a
+    /// player in it when no signature is found.
+    pub fn is_likely_sid_player(filename: &Path) -> bool {
+        read(filename).is_ok_and(|data| {
+            let data_offset = Self::get_data_offset(filename, &data).min(data.len());
+            heuristic::has_sid_register_writes(&data[data_offset..])
+        })
+    }
+
+    /// Whether the file starts with a PSID or RSID header. Only the header is read.
+    pub fn is_sid_file(filename: &Path) -> bool {
+        Self::get_file_type(filename) == FileType::Sid
+    }
+
+    /// Returns the format of the file, which is `FileType::Other` when the file can't be read.
+    pub fn get_file_type(filename: &Path) -> FileType {
+        let mut header = Vec::with_capacity(HEADER_READ_SIZE);
+        match File::open(filename).and_then(|file| file.take(HEADER_READ_SIZE as u64).read_to_end(&mut header)) {
+            Ok(_) => Self::get_buffer_file_type(&header, filename),
+            Err(_) => FileType::Other
         }
+    }
 
-        let default_config_file_path = env::current_exe().map_err(|_| "Could not determine executable location")?
-            .parent().ok_or("Could not determine executable directory")?
-            .join(filename);
+    pub fn get_buffer_file_type(buffer: &[u8], filename: &Path) -> FileType {
+        if sid_file::is_sid_file(buffer) {
+            FileType::Sid
+        } else if filename.extension().is_some_and(|extension| extension == "prg") {
+            FileType::Prg
+        } else {
+            FileType::Other
+        }
+    }
+
+    /// Returns the number of songs (subtunes) from the header of a SID file, or `None` for other files.
+    pub fn get_song_count(filename: &Path) -> Option<usize> {
+        let mut header = Vec::with_capacity(HEADER_READ_SIZE);
+        File::open(filename).ok()?.take(HEADER_READ_SIZE as u64).read_to_end(&mut header).ok()?;
+        sid_file::is_sid_file(&header).then(|| sid_file::get_song_count(&header))
+    }
+
+    /// Returns the offset in the file where the data starts together with the load address of the data, so that an
+    /// offset in the file can be translated to a memory address, or `None` when the file has no load address.
+    pub fn get_load_address(filename: &Path, scan_start: ScanStart) -> Option<(usize, usize)> {
+        let mut header = Vec::with_capacity(HEADER_READ_SIZE);
+        File::open(filename).ok()?.take(HEADER_READ_SIZE as u64).read_to_end(&mut header).ok()?;
 
-        if default_config_file_path.exists() {
-            Ok(default_config_file_path)
+        if sid_file::is_sid_file(&header) {
+            let data_offset = sid_file::get_data_offset(&header);
+            return (scan_start != ScanStart::Init && data_offset > 0).then(|| sid_file::get_load_address(&header)).flatten()
+                .map(|load_address| (data_offset, load_address));
+        }
+
+        let is_program_file = scan_start == ScanStart::Program ||
+            (scan_start == ScanStart::Data && filename.extension().is_some_and(|extension| extension == "prg"));
+
+        (is_program_file && !vsf_file::is_vsf_file(&header) && header.len() >= PRG_LOAD_ADDRESS_SIZE)
+            .then(|| (PRG_LOAD_ADDRESS_SIZE, u16::from_le_bytes([header[0], header[1]]) as usize))
+    }
+
+    pub(crate) fn get_data_offset(filename: &Path, data: &[u8]) -> usize {
+        if sid_file::is_sid_file(data) {
+            sid_file::get_data_offset(data)
+        } else if filename.extension().is_some_and(|extension| extension == "prg") { 2 } else { 0 }
+    }
+
+    pub(crate) fn get_program_data_offset(data: &[u8]) -> usize {
+        if sid_file::is_sid_file(data) {
+            sid_file::get_data_offset(data)
         } else {
-            Err(format!("File doesn't exist: {filename}"))
+            PRG_LOAD_ADDRESS_SIZE.min(data.len())
         }
     }
 
+    fn get_config_path_with_fallback(filename: &str) -> Result<PathBuf, String> {
+        Self::find_config_path(filename, &[Path::new(""), &Self::get_executable_directory()?])
+    }
+
+    /// Returns the path of the file in the first search directory that has it, where an empty search directory is the
+    /// current directory.
+    fn find_config_path(filename: &str, search_directories: &[&Path]) -> Result<PathBuf, String> {
+        search_directories.iter()
+            .map(|directory| directory.join(filename))
+            .find(|path| path.exists())
+            .ok_or_else(|| format!("File doesn't exist: {filename}"))
+    }
+
+    fn get_executable_directory() -> Result<PathBuf, String> {
+        let executable_path = env::current_exe().map_err(|_| "Could not determine executable location")?;
+        Ok(executable_path.parent().ok_or("Could not determine executable directory")?.to_path_buf())
+    }
+
     fn read_text_file(config_path: &PathBuf) -> Result<Vec<String>, String> {
         let lines = Self::read_lines(config_path);
         lines.map_err(|_| format!("Error reading file: {}", config_path.display()))
     }
 
+    /// Reads the lines of a config file, or the lines of the config section when the file is a bundle.
+    fn read_config_file_lines(config_path: &PathBuf) -> Result<Vec<String>, String> {
+        let lines = Self::read_text_file(config_path)?;
+        Ok(match bundle::split_bundle(&lines) {
+            Some((config_lines, _)) => config_lines.to_vec(),
+            None => lines
+        })
+    }
+
+    /// Reads the lines of a config file that is rewritten. When the file is a bundle, the lines of the config section
+    /// are returned without its marker, together with the bytes of the info section, which are written back unchanged.
+    fn read_config_file_to_rewrite(config_path: &PathBuf) -> Result<(Vec<String>, Option<Vec<u8>>), String> {
+        let data = read(config_path).map_err(|_| format!("Error reading file: {}", config_path.display()))?;
+        let Some(info_section) = bundle::get_info_section(&data) else {
+            return Ok((Self::read_text_file(config_path)?, None));
+        };
+
+        let config_lines = Self::read_config_file_lines(config_path)?.into_iter()
+            .skip_while(|line| line.trim().is_empty())
+            .skip(1)
+            .collect();
+        Ok((config_lines, Some(info_section.to_vec())))
+    }
+
+    /// Reads the lines of an info file, or the lines of the info section when the file is a bundle.
+    fn read_info_file_lines(info_path: &PathBuf) -> Result<Vec<String>, String> {
+        let lines = Self::read_text_file(info_path)?;
+        Ok(match bundle::split_bundle(&lines) {
+            Some((_, info_lines)) => info_lines.to_vec(),
+            None => lines
+        })
+    }
+
+    fn is_bundle_file(path: &PathBuf) -> bool {
+        Self::read_text_file(path).is_ok_and(|lines| bundle::split_bundle(&lines).is_some())
+    }
+
     fn read_lines(filename: &PathBuf) -> io::Result<Vec<String>> {
         let file = File::open(filename)?;
         let lines = BufReader::new(
@@ -246,3 +871,7 @@ impl PlayerId {
         chunk.lines().map_while(Result::ok).collect()
     }
 }
+
+#[cfg(test)]
+#[path = "./player_id_test.rs"]
+mod player_id_test;
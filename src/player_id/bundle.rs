@@ -0,0 +1,52 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+/// The comment line that starts a bundle and its config section. As it is a comment, the lines of the config section
+/// have the same line numbers as in the bundle.
+pub const CONFIG_SECTION_MARKER: &str = "; [CONFIG]";
+/// The comment line that ends the config section and starts the info section.
+pub const INFO_SECTION_MARKER: &str = "; [INFO]";
+
+/// Splits the lines of a bundle into the lines of the config section and those of the info section. The config section
+/// starts with its marker, so that the bundle can be read as a config file up to the info section. `None` is returned
+/// when the lines are not a bundle.
+pub fn split_bundle(lines: &[String]) -> Option<(&[String], &[String])> {
+    let first_line = lines.iter().position(|line| !line.trim().is_empty())?;
+    if lines[first_line].trim() != CONFIG_SECTION_MARKER {
+        return None;
+    }
+
+    let info_marker = lines.iter().position(|line| line.trim() == INFO_SECTION_MARKER)?;
+    Some((&lines[..info_marker], &lines[info_marker + 1..]))
+}
+
+/// Returns the bytes of a bundle from the line with the info section marker up to the end, so that the info section can
+/// be written back unchanged when the config section is rewritten. `None` is returned when the data is not a bundle.
+pub fn get_info_section(data: &[u8]) -> Option<&[u8]> {
+    let mut line_start = 0;
+    let mut config_marker_found = false;
+
+    for line in data.split_inclusive(|&byte| byte == b'\n') {
+        let text = line.trim_ascii();
+        if !config_marker_found && !text.is_empty() {
+            if text != CONFIG_SECTION_MARKER.as_bytes() {
+                return None;
+            }
+            config_marker_found = true;
+        } else if text == INFO_SECTION_MARKER.as_bytes() {
+            return Some(&data[line_start..]);
+        }
+        line_start += line.len();
+    }
+    None
+}
+
+/// Joins the config lines and the info lines into the lines of a bundle.
+pub fn create_bundle(config_lines: &[String], info_lines: &[String]) -> Vec<String> {
+    [CONFIG_SECTION_MARKER].into_iter()
+        .chain(config_lines.iter().map(String::as_str))
+        .chain([INFO_SECTION_MARKER])
+        .chain(info_lines.iter().map(String::as_str))
+        .map(str::to_string)
+        .collect()
+}
@@ -0,0 +1,30 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+use std::collections::HashSet;
+
+/// Opcodes of STY, STA and STX with an absolute address and of STA with an absolute address indexed by Y or X.
+const STORE_OPCODES: [u8; 5] = [0x8C, 0x8D, 0x8E, 0x99, 0x9D];
+const SID_REGISTER_PAGE: u8 = 0xD4;
+const LAST_SID_REGISTER: u8 = 0x1C;
+const MIN_SID_REGISTER_WRITES: usize = 3;
+
+/// Returns whether the data stores values into several different registers of the SID chip ($D400-$D41C), which is
+/// what a player does, even when it isn't known which player it is. Stores to a few registers are also done by code
+/// that only initializes the SID chip, so at least a minimum number of different registers is required.
+pub fn has_sid_register_writes(data: &[u8]) -> bool {
+    let mut registers = HashSet::new();
+    for instruction in data.windows(3) {
+        if STORE_OPCODES.contains(&instruction[0]) && instruction[1] <= LAST_SID_REGISTER && instruction[2] == SID_REGISTER_PAGE {
+            registers.insert(instruction[1]);
+            if registers.len() >= MIN_SID_REGISTER_WRITES {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+#[path = "./heuristic_test.rs"]
+mod heuristic_test;
@@ -0,0 +1,8 @@
+use super::*;
+
+#[test]
+fn has_sid_register_writes_requires_several_registers() {
+    assert!(has_sid_register_writes(&[0x8D, 0x18, 0xD4, 0xA9, 0x00, 0x9D, 0x04, 0xD4, 0x8E, 0x01, 0xD4]));
+    assert!(!has_sid_register_writes(&[0x8D, 0x18, 0xD4, 0x8D, 0x18, 0xD4, 0x8D, 0x18, 0xD4]));
+    assert!(!has_sid_register_writes(&[0x8D, 0x18, 0xD4, 0x8D, 0x20, 0xD4, 0x8D, 0x11, 0xD0, 0xAD, 0x04, 0xD4]));
+}
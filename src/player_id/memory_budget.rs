@@ -0,0 +1,47 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+use std::sync::{Arc, Condvar, Mutex};
+
+/// A number of bytes that is shared by the threads that read files. A thread reserves the size of the file before
+/// reading it and waits until enough of the budget is released by the other threads.
+pub struct MemoryBudget {
+    limit: usize,
+    used: Mutex<usize>,
+    released: Condvar
+}
+
+/// Bytes of a memory budget that are in use, which are released when the reservation is dropped.
+pub struct MemoryReservation {
+    budget: Arc<MemoryBudget>,
+    size: usize
+}
+
+impl MemoryBudget {
+    pub fn new(limit: usize) -> MemoryBudget {
+        MemoryBudget { limit, used: Mutex::new(0), released: Condvar::new() }
+    }
+
+    /// Waits until the size fits in the budget and reserves it. A size larger than the whole budget is reserved as the
+    /// whole budget, so that it is read when nothing else is in use instead of waiting forever.
+    pub fn reserve(budget: &Arc<MemoryBudget>, size: usize) -> MemoryReservation {
+        let size = size.min(budget.limit);
+
+        let used = budget.used.lock().unwrap();
+        let mut used = budget.released.wait_while(used, |used| *used + size > budget.limit).unwrap();
+        *used += size;
+
+        MemoryReservation { budget: Arc::clone(budget), size }
+    }
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        *self.budget.used.lock().unwrap() -= self.size;
+        self.budget.released.notify_all();
+    }
+}
+
+#[cfg(test)]
+#[path = "./memory_budget_test.rs"]
+mod memory_budget_test;
@@ -0,0 +1,43 @@
+use super::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Barrier;
+use std::thread;
+
+#[test]
+fn reserve_serializes_reads_that_exceed_the_budget() {
+    for (limit, size) in [(10, 8), (10, 64)] {
+        let budget = Arc::new(MemoryBudget::new(limit));
+        let concurrent_reads = AtomicUsize::new(0);
+        let max_concurrent_reads = AtomicUsize::new(0);
+
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    let _reservation = MemoryBudget::reserve(&budget, size);
+                    let reads = concurrent_reads.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_concurrent_reads.fetch_max(reads, Ordering::SeqCst);
+                    thread::yield_now();
+                    concurrent_reads.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+        assert_eq!(max_concurrent_reads.load(Ordering::SeqCst), 1);
+    }
+}
+
+#[test]
+fn reserve_allows_concurrent_reads_within_the_budget() {
+    let budget = Arc::new(MemoryBudget::new(32));
+    let all_reserved = Barrier::new(4);
+
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                let _reservation = MemoryBudget::reserve(&budget, 8);
+                // every thread only gets past the barrier when all four reservations are held at the same time
+                all_reserved.wait();
+            });
+        }
+    });
+    assert_eq!(*budget.used.lock().unwrap(), 0);
+}
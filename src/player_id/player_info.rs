@@ -0,0 +1,123 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+use super::signature::{Signature, SignatureInfo};
+
+const INFO_TAG_WIDTH: usize = 9;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct PlayerInfo {
+    pub signature_name: String,
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub released: Option<String>,
+    pub reference: Option<String>,
+    pub comment: Option<String>,
+    pub other_tags: Vec<(String, String)>
+}
+
+impl PlayerInfo {
+    /// Parses the raw info lines of a signature. Lines that continue the value of the previous tag,
+    /// like a multi-line COMMENT, are joined with a newline.
+    pub fn parse(signature_info: &SignatureInfo) -> PlayerInfo {
+        let (signature_name, info_lines) = signature_info;
+
+        let mut tags: Vec<(String, String)> = vec![];
+        for line in info_lines.iter().filter(|line| Signature::is_info_tag(line)) {
+            let value = line[INFO_TAG_WIDTH + 2..].trim();
+            let tag = line[..INFO_TAG_WIDTH].trim();
+
+            match tags.last_mut() {
+                Some((_, previous_value)) if tag.is_empty() => {
+                    previous_value.push('\n');
+                    previous_value.push_str(value);
+                },
+                _ => tags.push((tag.to_ascii_uppercase(), value.to_string()))
+            }
+        }
+
+        let mut player_info = PlayerInfo {
+            signature_name: signature_name.to_string(),
+            ..Default::default()
+        };
+
+        for (tag, value) in tags {
+            match tag.as_str() {
+                "NAME" => player_info.name = Some(value),
+                "AUTHOR" => player_info.author = Some(value),
+                "RELEASED" => player_info.released = Some(value),
+                "REFERENCE" => player_info.reference = Some(value),
+                "COMMENT" => player_info.comment = Some(value),
+                _ => player_info.other_tags.push((tag, value))
+            }
+        }
+        player_info
+    }
+
+    /// Formats the info in the layout of the info file, without the signature name.
+    pub fn to_info_lines(&self) -> Vec<String> {
+        let standard_tags = [
+            ("NAME", &self.name),
+            ("AUTHOR", &self.author),
+            ("RELEASED", &self.released),
+            ("REFERENCE", &self.reference)
+        ];
+
+        let tags = standard_tags.iter()
+            .filter_map(|(tag, value)| value.as_ref().map(|value| (*tag, value)))
+            .chain(self.other_tags.iter().map(|(tag, value)| (tag.as_str(), value)))
+            .chain(self.comment.as_ref().map(|value| ("COMMENT", value)));
+
+        let mut info_lines = vec![];
+        for (tag, value) in tags {
+            for (index, value_line) in value.lines().enumerate() {
+                if index == 0 {
+                    info_lines.push(format!("{tag:>INFO_TAG_WIDTH$}: {value_line}"));
+                } else {
+                    info_lines.push(format!("{:INFO_TAG_WIDTH$}  {value_line}", ""));
+                }
+            }
+        }
+        info_lines
+    }
+
+    /// Formats the info as a JSON object. Tags that are not defined are written as null.
+    pub fn to_json(&self) -> String {
+        let fields = [
+            ("signature", Some(&self.signature_name)),
+            ("name", self.name.as_ref()),
+            ("author", self.author.as_ref()),
+            ("released", self.released.as_ref()),
+            ("reference", self.reference.as_ref()),
+            ("comment", self.comment.as_ref())
+        ];
+
+        let fields = fields.iter()
+            .map(|(field, value)| format!("\"{field}\": {}", value.map_or("null".to_string(), |value| to_json_string(value))))
+            .collect::<Vec<String>>();
+
+        format!("{{{}}}", fields.join(", "))
+    }
+}
+
+fn to_json_string(value: &str) -> String {
+    let mut json_string = String::with_capacity(value.len() + 2);
+    json_string.push('"');
+    for character in value.chars() {
+        match character {
+            '"' => json_string.push_str("\\\""),
+            '\\' => json_string.push_str("\\\\"),
+            '\n' => json_string.push_str("\\n"),
+            '\r' => json_string.push_str("\\r"),
+            '\t' => json_string.push_str("\\t"),
+            _ if character.is_control() => json_string.push_str(&format!("\\u{:04x}", character as u32)),
+            _ => json_string.push(character)
+        }
+    }
+    json_string.push('"');
+    json_string
+}
+
+#[cfg(test)]
+#[path = "./player_info_test.rs"]
+mod player_info_test;
@@ -0,0 +1,83 @@
+use super::*;
+
+fn get_signature_info(info_lines: &[&str]) -> SignatureInfo {
+    ("OxyMod/THCM".to_string(), info_lines.iter().map(|line| line.to_string()).collect())
+}
+
+#[test]
+fn parse_multi_line_comment() {
+    let signature_info = get_signature_info(&[
+        "   AUTHOR: Uwe Anfang (The Human Code Machine)",
+        "  COMMENT: The technique used in this player was discovered",
+        "           in 2006. More info about this technique:",
+        "           https://codebase64.org/"
+    ]);
+
+    let player_info = PlayerInfo::parse(&signature_info);
+
+    assert_eq!(player_info, PlayerInfo {
+        signature_name: "OxyMod/THCM".to_string(),
+        author: Some("Uwe Anfang (The Human Code Machine)".to_string()),
+        comment: Some("The technique used in this player was discovered\nin 2006. More info about this technique:\nhttps://codebase64.org/".to_string()),
+        ..Default::default()
+    });
+}
+
+#[test]
+fn parse_all_tags() {
+    let signature_info = get_signature_info(&[
+        "     NAME: 5 Dimension Composer",
+        "   AUTHOR: Pleite Geier",
+        " RELEASED: 1990 Pleite Geier",
+        "REFERENCE: https://csdb.dk/release/?id=122330",
+        "   SOURCE: https://github.com/",
+        "  COMMENT: Single line"
+    ]);
+
+    let player_info = PlayerInfo::parse(&signature_info);
+
+    assert_eq!(player_info.name.as_deref(), Some("5 Dimension Composer"));
+    assert_eq!(player_info.author.as_deref(), Some("Pleite Geier"));
+    assert_eq!(player_info.released.as_deref(), Some("1990 Pleite Geier"));
+    assert_eq!(player_info.reference.as_deref(), Some("https://csdb.dk/release/?id=122330"));
+    assert_eq!(player_info.comment.as_deref(), Some("Single line"));
+    assert_eq!(player_info.other_tags, vec![("SOURCE".to_string(), "https://github.com/".to_string())]);
+}
+
+#[test]
+fn to_info_lines_keeps_info_file_layout() {
+    let info_lines = [
+        "     NAME: 5 Dimension Composer",
+        "REFERENCE: https://csdb.dk/release/?id=122330",
+        "  COMMENT: First line",
+        "           second line"
+    ];
+
+    let player_info = PlayerInfo::parse(&get_signature_info(&info_lines));
+
+    assert_eq!(player_info.to_info_lines(), info_lines.to_vec());
+}
+
+#[test]
+fn to_json_all_tags() {
+    let signature_info = get_signature_info(&[
+        "     NAME: 5 Dimension Composer",
+        "   AUTHOR: Pleite Geier",
+        " RELEASED: 1990 Pleite Geier",
+        "REFERENCE: https://csdb.dk/release/?id=122330",
+        "  COMMENT: A \"quoted\" comment",
+        "           on two lines"
+    ]);
+
+    assert_eq!(PlayerInfo::parse(&signature_info).to_json(), concat!(
+        r#"{"signature": "OxyMod/THCM", "name": "5 Dimension Composer", "author": "Pleite Geier", "released": "1990 Pleite Geier", "#,
+        r#""reference": "https://csdb.dk/release/?id=122330", "comment": "A \"quoted\" comment\non two lines"}"#
+    ));
+}
+
+#[test]
+fn to_json_missing_tags_are_null() {
+    let player_info = PlayerInfo::parse(&get_signature_info(&["   AUTHOR: Uwe Anfang"]));
+
+    assert_eq!(player_info.to_json(), r#"{"signature": "OxyMod/THCM", "name": null, "author": "Uwe Anfang", "released": null, "reference": null, "comment": null}"#);
+}
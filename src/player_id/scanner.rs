@@ -0,0 +1,207 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+use std::fs::{self, read, File};
+use std::io::{self, Cursor, Read};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::memory_budget::{MemoryBudget, MemoryReservation};
+use super::signature::Signature;
+use super::{PlayerId, ScanStart, SignatureConfig, SignatureMatch, CHUNKED_READ_THRESHOLD, HEADER_READ_SIZE, READ_CHUNK_SIZE};
+use super::super::{lha_file, sid_file, vsf_file};
+
+type DataOffsetHandler = Box<dyn Fn(&[u8], &str) -> Option<usize> + Send + Sync>;
+type MatchesAndDigest = (Option<Vec<SignatureMatch>>, Option<[u8; 16]>);
+
+/// Searches files for players with the given options and the data offset handlers that are registered on it, so
+/// that library users can scan files of their own formats. The functions of `PlayerId` that search files use a scanner
+/// without handlers.
+pub struct Scanner {
+    /// The distance in bytes between the bytes of a signature that are searched for besides the bytes next to each other.
+    pub stride: usize,
+    pub scan_start: ScanStart,
+    /// The time after which the search is given up, which is then reported as `None`.
+    pub timeout: Option<Duration>,
+    /// Offsets in the file, or in the memory snapshot of VSF files, in which no byte of a found signature may lie.
+    pub skip_regions: Vec<Range<usize>>,
+    data_offset_handlers: Vec<DataOffsetHandler>,
+    memory_budget: Option<Arc<MemoryBudget>>
+}
+
+impl Default for Scanner {
+    fn default() -> Self {
+        Scanner {
+            stride: 1,
+            scan_start: ScanStart::Data,
+            timeout: None,
+            skip_regions: vec![],
+            data_offset_handlers: vec![],
+            memory_budget: None
+        }
+    }
+}
+
+impl Scanner {
+    pub fn new() -> Scanner {
+        Scanner::default()
+    }
+
+    /// Registers a handler that determines where the data starts in files of a custom format, given the data and the
+    /// filename. Handlers are consulted in the order in which they are registered, before the built-in detection of
+    /// SID, VSF and PRG files, and the offset of the first handler that returns one is used. For files that are read in
+    /// chunks, the handler only gets the first 256 bytes of the file.
+    pub fn register_data_offset_handler(&mut self, handler: impl Fn(&[u8], &str) -> Option<usize> + Send + Sync + 'static) {
+        self.data_offset_handlers.push(Box::new(handler));
+    }
+
+    /// Limits the total size of the files that are read at the same time by the threads that search for players with
+    /// this scanner, or removes the limit when `None` is given. A thread waits with reading a file until the size of the
+    /// file fits in what is left of the limit. Files that are large enough to be read in chunks count as the size of a
+    /// chunk, and a file that is larger than the limit is read when no other file is read.
+    pub fn set_max_memory(&mut self, max_memory: Option<usize>) {
+        self.memory_budget = max_memory.map(|max_memory| Arc::new(MemoryBudget::new(max_memory)));
+    }
+
+    /// Finds the players in the file, or returns `None` when the search timed out. An error is returned when a skip
+    /// region ends after the end of the file, or when skip regions are given for an LHA archive or for a file that is
+    /// large enough to be read in chunks.
+    pub fn try_find_players_in_file(&self, filename: &Path, signature_ids: &Vec<SignatureConfig>, scan_for_multiple: bool) -> Result<Option<Vec<SignatureMatch>>, String> {
+        self.find_players_in_file(filename, signature_ids, scan_for_multiple, false).map(|(matches, _)| matches)
+    }
+
+    /// Same as `try_find_players_in_file`, but the MD5 digest of the content of the file is returned as well. The digest
+    /// is computed from the data that is searched, so that the file doesn't have to be read again to tell it apart from
+    /// other files by its content. Only files that are large enough to be read in chunks are read again for the digest.
+    pub fn try_find_players_and_digest_in_file(&self, filename: &Path, signature_ids: &Vec<SignatureConfig>, scan_for_multiple: bool) -> Result<(Option<Vec<SignatureMatch>>, [u8; 16]), String> {
+        self.find_players_in_file(filename, signature_ids, scan_for_multiple, true).map(|(matches, digest)| (matches, digest.unwrap_or_default()))
+    }
+
+    fn find_players_in_file(&self, filename: &Path, signature_ids: &Vec<SignatureConfig>, scan_for_multiple: bool, digest: bool) -> Result<MatchesAndDigest, String> {
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+
+        let file_size = fs::metadata(filename).map_err(|error| format!("Could not read file: {error}"))?.len();
+        let read_size = if file_size > CHUNKED_READ_THRESHOLD { READ_CHUNK_SIZE } else { file_size as usize };
+        let mut _reservation = self.reserve_memory(read_size);
+        if file_size > CHUNKED_READ_THRESHOLD {
+            if !self.skip_regions.is_empty() {
+                return Err(format!("Skip regions are not supported for files larger than {} MB", CHUNKED_READ_THRESHOLD / (1024 * 1024)));
+            }
+            let matches = self.find_players_in_large_file(filename, signature_ids, scan_for_multiple, READ_CHUNK_SIZE, deadline)
+                .map_err(|error| format!("Could not read file: {error}"))?;
+            let digest = if digest { Some(Self::get_file_digest(filename).map_err(|error| format!("Could not read file: {error}"))?) } else { None };
+            return Ok((matches, digest));
+        }
+
+        let data = read(filename).map_err(|error| format!("Could not read file: {error}"))?;
+        let digest = digest.then(|| md5::compute(&data).0);
+        if !sid_file::is_sid_file(&data) && !vsf_file::is_vsf_file(&data) && lha_file::is_lha_file(&data) {
+            // the reservation is replaced instead of extended, since waiting for more while holding it could deadlock
+            drop(_reservation);
+            _reservation = self.reserve_memory(data.len() + lha_file::get_unpacked_size(&data));
+            return Ok((self.find_players_in_lha(filename, &data, signature_ids, scan_for_multiple, deadline)?, digest));
+        }
+        Ok((self.find_players_in_data(filename, &data, signature_ids, scan_for_multiple, deadline)?, digest))
+    }
+
+    /// Finds the players in each file of an LHA archive or a self-extracting LHA archive, which are searched like other
+    /// files. The path of the file in the archive is set in the archive path of the matches, after the name of the
+    /// archive. Archives with a compression method that isn't supported give an error.
+    fn find_players_in_lha(&self, filename: &Path, data: &[u8], signature_ids: &Vec<SignatureConfig>, scan_for_multiple: bool, deadline: Option<Instant>) -> Result<Option<Vec<SignatureMatch>>, String> {
+        if !self.skip_regions.is_empty() {
+            return Err("Skip regions are not supported for LHA archives".to_string());
+        }
+
+        let Some(entries) = lha_file::get_entries(data, deadline)? else {
+            return Ok(None);
+        };
+        let archive_name = filename.file_name().map(|archive_name| archive_name.to_string_lossy()).unwrap_or_default();
+
+        let mut matches = vec![];
+        for entry in entries {
+            let entry_path = PathBuf::from(entry.path.to_ascii_lowercase());
+            let Some(entry_matches) = self.find_players_in_data(&entry_path, &entry.data, signature_ids, scan_for_multiple, deadline)? else {
+                return Ok(None);
+            };
+
+            let archive_path: Arc<str> = Arc::from(format!("{archive_name}/{}", entry.path));
+            for mut signature_match in entry_matches {
+                signature_match.archive_path = Some(Arc::clone(&archive_path));
+                matches.push(signature_match);
+            }
+            if !scan_for_multiple && !matches.is_empty() {
+                break;
+            }
+        }
+        Ok(Some(matches))
+    }
+
+    fn find_players_in_data(&self, filename: &Path, data: &[u8], signature_ids: &Vec<SignatureConfig>, scan_for_multiple: bool, deadline: Option<Instant>) -> Result<Option<Vec<SignatureMatch>>, String> {
+        let (stride, scan_start, skip_regions) = (self.stride, self.scan_start, &self.skip_regions[..]);
+
+        let (data, data_offset) = if let Some(data_offset) = self.get_custom_data_offset(filename, data) {
+            (data, data_offset)
+        } else if vsf_file::is_vsf_file(data) {
+            (vsf_file::get_ram(data)?, 0)
+        } else {
+            let data_offset = match scan_start {
+                ScanStart::Data => PlayerId::get_data_offset(filename, data),
+                ScanStart::Init => sid_file::is_sid_file(data).then(|| sid_file::get_init_offset(data)).flatten()
+                    .unwrap_or_else(|| PlayerId::get_data_offset(filename, data)),
+                ScanStart::Program => PlayerId::get_program_data_offset(data)
+            };
+            (data, data_offset)
+        };
+
+        if let Some(skip_region) = skip_regions.iter().find(|skip_region| skip_region.end > data.len()) {
+            return Err(format!("Skip region ${:04X}:${:04X} ends after the end of the file", skip_region.start, skip_region.end));
+        }
+
+        let Some(matches) = Signature::find_signatures_until(data, data_offset, signature_ids, scan_for_multiple, deadline, skip_regions) else {
+            return Ok(None);
+        };
+        let Some(mut matches) = Signature::find_signatures_at_stride(data, data_offset, signature_ids, scan_for_multiple, stride, matches, deadline) else {
+            return Ok(None);
+        };
+
+        // matches at a stride are only checked on their start offsets, since their bytes aren't next to each other
+        matches.retain(|signature_match| !signature_match.indexes.iter().any(|index| skip_regions.iter().any(|skip_region| skip_region.contains(index))));
+
+        if scan_start == ScanStart::Init {
+            for signature_match in &mut matches {
+                signature_match.indexes.iter_mut().for_each(|index| *index -= data_offset);
+            }
+        }
+        Ok(Some(matches))
+    }
+
+    fn find_players_in_large_file(&self, filename: &Path, signature_ids: &[SignatureConfig], scan_for_multiple: bool, chunk_size: usize, deadline: Option<Instant>) -> io::Result<Option<Vec<SignatureMatch>>> {
+        let mut file = File::open(filename)?;
+
+        let mut header = Vec::with_capacity(HEADER_READ_SIZE);
+        file.by_ref().take(HEADER_READ_SIZE as u64).read_to_end(&mut header)?;
+        let data_offset = self.get_custom_data_offset(filename, &header).unwrap_or_else(|| PlayerId::get_data_offset(filename, &header));
+
+        let mut reader = Cursor::new(header).chain(file);
+        Signature::find_signatures_in_reader(&mut reader, data_offset, signature_ids, scan_for_multiple, chunk_size, deadline)
+    }
+
+    fn get_file_digest(filename: &Path) -> io::Result<[u8; 16]> {
+        let mut context = md5::Context::new();
+        io::copy(&mut File::open(filename)?, &mut context)?;
+        Ok(context.compute().0)
+    }
+
+    fn reserve_memory(&self, size: usize) -> Option<MemoryReservation> {
+        self.memory_budget.as_ref().map(|budget| MemoryBudget::reserve(budget, size))
+    }
+
+    fn get_custom_data_offset(&self, filename: &Path, data: &[u8]) -> Option<usize> {
+        let filename = filename.to_string_lossy();
+        self.data_offset_handlers.iter()
+            .find_map(|handler| handler(data, &filename))
+            .map(|data_offset| data_offset.min(data.len()))
+    }
+}
@@ -1,45 +1,370 @@
 // Copyright (C) 2019 - 2024 Wilfred Bos
 // Licensed under the MIT license. See the LICENSE file for the terms and conditions.
 
+use std::io::{self, Read};
+use std::ops::{Range, RangeInclusive};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+
 use bndm::{BndmConfig, find_pattern};
 
+use crate::log_error;
+
 const CMD_WILDCARD: u16 = 0x100;
+const MIN_SIZE_CLAUSE: &str = "MINSIZE=";
+const ANY_COMBINATOR: &str = "ANY{";
+const OVERLAP_CLAUSE: &str = "OVERLAP";
+const ENTRY_CLAUSE: &str = "ENTRY";
+const CONTINUATION_MARKER: &str = "\\";
+const COMMENT_MARKER: char = ';';
+const EXPECT_ANNOTATION: &str = "EXPECT";
+const WEAK_SIGNATURE_MIN_BYTES: usize = 6;
+const WEAK_SIGNATURE_WILDCARD_DENSITY: f64 = 0.5;
+const DEFAULT_MIN_LINE_LENGTH: usize = 2;
+const DEFAULT_MIN_NAME_LENGTH: usize = 3;
+const INFO_TAG_WIDTH: usize = 9;
+const HEX_VALUE_LENGTH: usize = 2;
+const ABSOLUTE_INSTRUCTION_LENGTH: usize = 3;
+// zero page, stack and system area, and the I/O area, which stay at the same address when a player is relocated
+const FIXED_ADDRESS_PAGES: [RangeInclusive<u16>; 2] = [0x00..=0x03, 0xD0..=0xDF];
 
 pub struct SignatureConfig {
     pub bndm_configs: Vec<BndmConfig>,
-    pub signature_name: String
+    pub signature_name: Arc<str>,
+    pub min_size: usize,
+    /// When set, the signature matches when at least this number of the sub signatures are found, in any order.
+    pub any_threshold: Option<usize>,
+    /// When set, a sub signature is searched from the byte after the start of the previous sub signature instead of
+    /// after its end, so that sub signatures that overlap are found as well.
+    pub allow_overlap: bool,
+    /// When set, the signature matches when all sub signatures are found, in any order.
+    pub any_order: bool,
+    /// The path of the config file the signature was read from, when read from a file.
+    pub source: Option<Arc<str>>,
+    /// The opcodes of an entry point fingerprint, or empty for other signatures. A fingerprint only matches at the
+    /// offset where the search starts, which is the init routine when scanning from the init routine, and the operands
+    /// of the instructions can have any value.
+    pub entry_opcodes: Vec<u8>
+}
+
+/// How reliable a match is. A match is weak when the signature has only a few bytes that aren't wildcards, consists
+/// mostly of wildcards or matches when only some of its sub signatures are found.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Confidence {
+    Strong,
+    Weak
 }
 
+/// A signature found in a source. The name is shared with the signature config, so that no string is allocated per match.
 pub struct SignatureMatch {
-    pub signature_name: String,
+    pub signature_name: Arc<str>,
     pub indexes: Vec<usize>,
+    /// The fraction of wildcard bytes in the signature that matched, from 0.0 to 1.0.
+    pub wildcard_density: f64,
+    /// Whether any of the wildcard bytes of the signature were used to match, i.e. whether the match isn't exact.
+    pub wildcard_used: bool,
+    pub confidence: Confidence,
+    /// The number of bytes in the signature that aren't wildcards, used to rank matches by specificity.
+    pub fixed_byte_count: usize,
+    /// The path of the config file of the signature, when read from a file.
+    pub source: Option<Arc<str>>,
+    /// The name of the archive followed by the path of the file in the archive, when found in a file in an archive.
+    pub archive_path: Option<Arc<str>>
+}
+
+impl SignatureMatch {
+    fn new(signature: &SignatureConfig, indexes: Vec<usize>) -> SignatureMatch {
+        SignatureMatch {
+            signature_name: Arc::clone(&signature.signature_name),
+            indexes,
+            wildcard_density: Signature::get_wildcard_density(signature),
+            wildcard_used: signature.bndm_configs.iter().any(Signature::uses_wildcard),
+            confidence: Signature::get_confidence(signature),
+            fixed_byte_count: Signature::get_fixed_byte_count(signature),
+            source: signature.source.clone(),
+            archive_path: None
+        }
+    }
 }
 
 pub type SignatureInfo = (String, Vec<String>);
 
+/// The lengths that determine how the lines of a config file are read. Lines that are shorter than the minimum line
+/// length end a signature. A line is a signature name when it is at least as long as the minimum name length and there
+/// is no space from the third character up to that length, so that a line that starts with a hexadecimal value
+/// followed by a space is read as a signature value. A minimum name length below 3 is therefore read as 3.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConfigSyntax {
+    pub min_line_length: usize,
+    pub min_name_length: usize,
+    /// When set, a value in a signature that isn't a hexadecimal byte is an error instead of a warning.
+    pub strict: bool
+}
+
+impl Default for ConfigSyntax {
+    fn default() -> Self {
+        ConfigSyntax {
+            min_line_length: DEFAULT_MIN_LINE_LENGTH,
+            min_name_length: DEFAULT_MIN_NAME_LENGTH,
+            strict: false
+        }
+    }
+}
+
+/// An annotation in a comment line of the config file, written as `; EXPECT name count directory`, that expects the
+/// signature to match exactly that number of files in the directory and its subdirectories.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignatureExpectation {
+    pub signature_name: String,
+    pub file_count: usize,
+    pub directory: PathBuf,
+    pub line_number: usize
+}
+
 pub struct Signature {}
 
 impl Signature {
     pub fn find_signatures(source: &[u8], start_offset: usize, signatures: &Vec<SignatureConfig>, scan_for_multiple: bool) -> Vec<SignatureMatch> {
+        Self::find_signatures_until(source, start_offset, signatures, scan_for_multiple, None, &[]).unwrap_or_default()
+    }
+
+    /// Same as `find_signatures`, but gives up when the deadline has passed. The deadline is checked before each
+    /// signature is searched, so `None` is returned at the first check after the deadline. Signatures are not found
+    /// when any of their bytes lie within one of the skip regions.
+    pub fn find_signatures_until(source: &[u8], start_offset: usize, signatures: &Vec<SignatureConfig>, scan_for_multiple: bool, deadline: Option<Instant>, skip_regions: &[Range<usize>]) -> Option<Vec<SignatureMatch>> {
         let mut matches = vec![];
 
         for signature in signatures {
-            let mut indexes = vec![];
-            let mut index_found = true;
-            let mut last_index = start_offset;
-
-            for config in &signature.bndm_configs {
-                if let Some(index) = find_pattern(&source[last_index..], config) {
-                    indexes.push(last_index + index);
-                    last_index += index + config.pattern.len();
-                } else {
-                    index_found = false;
+            if Self::is_deadline_passed(deadline) {
+                return None;
+            }
+
+            if source.len() < signature.min_size {
+                continue;
+            }
+
+            if let Some(indexes) = Self::find_signature(source, start_offset, signature, skip_regions) {
+                matches.push(SignatureMatch::new(signature, indexes));
+
+                if !scan_for_multiple {
                     break;
                 }
             }
+        }
+        matches.dedup_by(|a, b| a.signature_name.eq(&b.signature_name));
+        Some(matches)
+    }
+
+    fn is_deadline_passed(deadline: Option<Instant>) -> bool {
+        deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Retries the signatures that were not found by searching the source as if it was stored with a stride, i.e. as
+    /// if only every n-th byte belongs to the player. For every possible phase of the stride a copy of the source
+    /// with only those bytes is searched, so each signature that isn't found is searched once more through all bytes.
+    /// `None` is returned when the deadline has passed before all phases are searched.
+    pub fn find_signatures_at_stride(source: &[u8], start_offset: usize, signatures: &[SignatureConfig], scan_for_multiple: bool, stride: usize, mut matches: Vec<SignatureMatch>, deadline: Option<Instant>) -> Option<Vec<SignatureMatch>> {
+        if stride <= 1 || start_offset >= source.len() || (!scan_for_multiple && !matches.is_empty()) {
+            return Some(matches);
+        }
+
+        let mut remaining_signatures = signatures.iter()
+            .filter(|signature| source.len() >= signature.min_size && signature.entry_opcodes.is_empty())
+            .filter(|signature| !matches.iter().any(|signature_match| signature_match.signature_name == signature.signature_name))
+            .collect::<Vec<&SignatureConfig>>();
+
+        for phase in 0..stride.min(source.len() - start_offset) {
+            if Self::is_deadline_passed(deadline) {
+                return None;
+            }
+
+            let strided_source = source[start_offset + phase..].iter().step_by(stride).copied().collect::<Vec<u8>>();
+
+            remaining_signatures.retain(|signature| {
+                let Some(indexes) = Self::find_signature(&strided_source, 0, signature, &[]) else {
+                    return true;
+                };
+
+                let indexes = indexes.iter().map(|index| start_offset + phase + index * stride).collect();
+                matches.push(SignatureMatch::new(signature, indexes));
+                false
+            });
+
+            if remaining_signatures.is_empty() || (!scan_for_multiple && !matches.is_empty()) {
+                break;
+            }
+        }
+        Some(matches)
+    }
+
+    /// Whether the pattern contains its wildcard. Every pattern has a wildcard value assigned when one is available, also
+    /// when the pattern doesn't contain any wildcards, so the wildcard being set isn't enough.
+    pub fn uses_wildcard(config: &BndmConfig) -> bool {
+        config.wildcard.is_some_and(|wildcard| config.pattern.contains(&wildcard))
+    }
+
+    pub fn get_wildcard_density(signature: &SignatureConfig) -> f64 {
+        let (wildcard_count, byte_count) = signature.bndm_configs.iter()
+            .fold((0, 0), |(wildcard_count, byte_count), config| {
+                let wildcards = config.wildcard.map_or(0, |wildcard| config.pattern.iter().filter(|&&byte| byte == wildcard).count());
+                (wildcard_count + wildcards, byte_count + config.pattern.len())
+            });
 
-            if index_found {
-                matches.push(SignatureMatch { signature_name: signature.signature_name.to_string(), indexes });
+        if byte_count == 0 { 0.0 } else { wildcard_count as f64 / byte_count as f64 }
+    }
+
+    pub fn get_fixed_byte_count(signature: &SignatureConfig) -> usize {
+        signature.bndm_configs.iter()
+            .map(|config| config.pattern.iter().filter(|&&byte| Some(byte) != config.wildcard).count())
+            .sum()
+    }
+
+    pub fn get_confidence(signature: &SignatureConfig) -> Confidence {
+        if signature.any_threshold.is_some() || Self::get_fixed_byte_count(signature) < WEAK_SIGNATURE_MIN_BYTES ||
+            Self::get_wildcard_density(signature) > WEAK_SIGNATURE_WILDCARD_DENSITY {
+            Confidence::Weak
+        } else {
+            Confidence::Strong
+        }
+    }
+
+    fn find_signature(source: &[u8], start_offset: usize, signature: &SignatureConfig, skip_regions: &[Range<usize>]) -> Option<Vec<usize>> {
+        if !signature.entry_opcodes.is_empty() {
+            let config = signature.bndm_configs.first()?;
+            let entry_source = &source[..source.len().min(start_offset + config.pattern.len())];
+            return Self::find_pattern_outside(entry_source, start_offset, config, skip_regions).map(|index| vec![index]);
+        }
+
+        if Self::is_unordered(signature) {
+            let indexes = signature.bndm_configs.iter()
+                .filter_map(|config| Self::find_pattern_outside(source, start_offset, config, skip_regions))
+                .collect::<Vec<usize>>();
+            return Self::is_signature_found(signature, &indexes).then_some(indexes);
+        }
+
+        let mut indexes = vec![];
+        let mut last_index = start_offset;
+
+        for config in &signature.bndm_configs {
+            let index = Self::find_pattern_outside(source, last_index, config, skip_regions)?;
+            indexes.push(index);
+            last_index = Self::get_next_search_index(signature, index, config);
+        }
+        Some(indexes)
+    }
+
+    /// Same as `find_pattern`, but a pattern is never found in a source that is shorter than the pattern. `find_pattern`
+    /// finds a single byte pattern that consists of its wildcard at position 0 of any source, also of an empty one.
+    fn find_pattern_in_source(source: &[u8], config: &BndmConfig) -> Option<usize> {
+        if source.len() < config.pattern.len() {
+            return None;
+        }
+        find_pattern(source, config)
+    }
+
+    /// Finds the first occurrence of the pattern from the start index that has no bytes within one of the skip regions.
+    fn find_pattern_outside(source: &[u8], start_index: usize, config: &BndmConfig, skip_regions: &[Range<usize>]) -> Option<usize> {
+        let mut search_start = start_index;
+        loop {
+            let index = search_start + Self::find_pattern_in_source(source.get(search_start..)?, config)?;
+            let end_index = index + config.pattern.len();
+
+            match skip_regions.iter().find(|skip_region| skip_region.start < end_index && index < skip_region.end) {
+                Some(skip_region) => search_start = skip_region.end,
+                None => return Some(index)
+            }
+        }
+    }
+
+    /// Whether the sub signatures are searched independently of each other instead of one after the other.
+    fn is_unordered(signature: &SignatureConfig) -> bool {
+        signature.any_threshold.is_some() || signature.any_order
+    }
+
+    fn get_next_search_index(signature: &SignatureConfig, index: usize, config: &BndmConfig) -> usize {
+        if signature.allow_overlap {
+            index + 1
+        } else {
+            index + config.pattern.len()
+        }
+    }
+
+    /// Finds signatures by reading the source in chunks, so that memory usage is bounded by the chunk size instead of
+    /// the size of the source. Each chunk is searched together with the tail of the previous chunk, which is as large
+    /// as the longest signature minus one byte, so that signatures across a chunk boundary are found as well.
+    /// When not scanning for multiple signatures, reading stops as soon as the first signature in config order has been
+    /// found, since no other signature can take precedence over it. This mostly helps when scanning for a single player.
+    /// The deadline is checked before each chunk is read, and `None` is returned when it has passed.
+    pub fn find_signatures_in_reader<R: Read>(reader: &mut R, start_offset: usize, signatures: &[SignatureConfig], scan_for_multiple: bool, chunk_size: usize, deadline: Option<Instant>) -> io::Result<Option<Vec<SignatureMatch>>> {
+        let overlap_size = signatures.iter()
+            .flat_map(|signature| &signature.bndm_configs)
+            .map(|config| config.pattern.len())
+            .max()
+            .unwrap_or(1) - 1;
+
+        let mut signature_indexes = signatures.iter()
+            .map(|signature| (vec![None; signature.bndm_configs.len()], start_offset))
+            .collect::<Vec<(Vec<Option<usize>>, usize)>>();
+        let mut window = Vec::with_capacity(chunk_size + overlap_size);
+        let mut window_offset = 0;
+        let mut source_size = 0;
+
+        loop {
+            if Self::is_deadline_passed(deadline) {
+                return Ok(None);
+            }
+
+            let bytes_read = reader.by_ref().take(chunk_size as u64).read_to_end(&mut window)?;
+            source_size += bytes_read;
+
+            for (signature, (indexes, next_index)) in signatures.iter().zip(signature_indexes.iter_mut()) {
+                if Self::is_unordered(signature) {
+                    let search_start = start_offset.max(window_offset) - window_offset;
+                    for (config, index) in signature.bndm_configs.iter().zip(indexes.iter_mut()).filter(|(_, index)| index.is_none()) {
+                        *index = window.get(search_start..)
+                            .and_then(|window| Self::find_pattern_in_source(window, config))
+                            .map(|index| window_offset + search_start + index);
+                    }
+                    continue;
+                }
+
+                while let Some(position) = indexes.iter().position(Option::is_none) {
+                    let config = &signature.bndm_configs[position];
+                    let search_start = (*next_index).max(window_offset) - window_offset;
+                    if search_start > window.len() {
+                        break;
+                    }
+
+                    if let Some(index) = Self::find_pattern_in_source(&window[search_start..], config) {
+                        let index = window_offset + search_start + index;
+                        indexes[position] = Some(index);
+                        *next_index = Self::get_next_search_index(signature, index, config);
+                    } else {
+                        break;
+                    }
+                }
+            }
+
+            if bytes_read < chunk_size {
+                break;
+            }
+
+            if !scan_for_multiple && Self::is_first_signature_found(signatures, &signature_indexes, source_size) {
+                break;
+            }
+
+            let overlap_start = window.len().saturating_sub(overlap_size);
+            window.drain(..overlap_start);
+            window_offset = source_size - window.len();
+        }
+
+        let mut matches = vec![];
+        for (signature, (indexes, _)) in signatures.iter().zip(signature_indexes) {
+            let indexes = indexes.into_iter().flatten().collect::<Vec<usize>>();
+            let is_at_entry_point = signature.entry_opcodes.is_empty() || indexes.first() == Some(&start_offset);
+            if source_size >= signature.min_size && Self::is_signature_found(signature, &indexes) && is_at_entry_point {
+                matches.push(SignatureMatch::new(signature, indexes));
 
                 if !scan_for_multiple {
                     break;
@@ -47,52 +372,167 @@ impl Signature {
             }
         }
         matches.dedup_by(|a, b| a.signature_name.eq(&b.signature_name));
-        matches
+        Ok(Some(matches))
+    }
+
+    fn is_first_signature_found(signatures: &[SignatureConfig], signature_indexes: &[(Vec<Option<usize>>, usize)], source_size: usize) -> bool {
+        signatures.first().zip(signature_indexes.first())
+            .is_some_and(|(signature, (indexes, _))| source_size >= signature.min_size && indexes.iter().all(Option::is_some))
+    }
+
+    fn is_signature_found(signature: &SignatureConfig, indexes: &[usize]) -> bool {
+        indexes.len() >= signature.any_threshold.unwrap_or(signature.bndm_configs.len())
     }
 
     pub fn find_signature_info<'a>(signature_infos: &'a [SignatureInfo], signature_name: &str) -> Option<&'a SignatureInfo> {
         signature_infos.iter().find(|(signature_info_name, _)| signature_info_name.eq_ignore_ascii_case(signature_name))
     }
 
-    pub fn read_config_lines(config_lines: &Vec<String>, signature_name_to_filter: Option<&String>) -> Result<Vec<SignatureConfig>, String> {
-        if !Self::is_config_file(config_lines) {
-            return Err("Not an config file.".to_string());
+    /// Reads the signatures from the lines of a config file. An error is returned when the lines are not in the config
+    /// file format. Lines without any content, or without a signature with the name to filter on, are valid and result
+    /// in no signatures, so that the caller can decide how to handle that.
+    pub fn read_config_lines(config_lines: &[String], signature_name_to_filter: Option<&String>, syntax: ConfigSyntax) -> Result<Vec<SignatureConfig>, String> {
+        let config_lines = &Self::replace_tabs(config_lines);
+
+        if config_lines.iter().all(|line| line.trim().is_empty()) {
+            return Ok(vec![]);
+        }
+
+        if !Self::is_config_file(config_lines, syntax) {
+            return Err("Not a config file.".to_string());
         }
 
         let mut signatures = vec![];
         let mut signature_name = "".to_string();
         let mut signature_lines = vec![];
 
-        for line in config_lines {
+        for (_, line) in Self::join_continuation_lines(config_lines) {
             let signature_text = line.trim();
 
-            if Self::is_signature_min_length(signature_text) {
-                if Self::is_signature_name(signature_text) {
-                    Self::process_multi_signatures(signature_name_to_filter, &mut signatures, &signature_name, &mut signature_lines);
+            if Self::is_signature_min_length(signature_text, syntax) {
+                if Self::is_signature_name(signature_text, syntax) {
+                    Self::process_multi_signatures(signature_name_to_filter, &mut signatures, &signature_name, &mut signature_lines, syntax.strict)?;
                     signature_name = signature_text.to_string();
                 } else {
                     signature_lines.push(signature_text.to_string());
                     if Self::has_end_marker(signature_text) {
-                        Self::process_single_signature(signature_name_to_filter, &mut signatures, &signature_name, &mut signature_lines);
+                        Self::process_single_signature(signature_name_to_filter, &mut signatures, &signature_name, &mut signature_lines, syntax.strict)?;
                     }
                 }
             } else {
-                Self::process_multi_signatures(signature_name_to_filter, &mut signatures, &signature_name, &mut signature_lines);
+                Self::process_multi_signatures(signature_name_to_filter, &mut signatures, &signature_name, &mut signature_lines, syntax.strict)?;
                 signature_name = "".to_string();
             }
         }
 
-        Self::process_multi_signatures(signature_name_to_filter, &mut signatures, &signature_name, &mut signature_lines);
+        Self::process_multi_signatures(signature_name_to_filter, &mut signatures, &signature_name, &mut signature_lines, syntax.strict)?;
         Ok(signatures)
     }
 
+    /// Joins lines that end with a continuation marker with the line that follows, returning each joined line with the index of its first line.
+    pub fn join_continuation_lines(config_lines: &[String]) -> Vec<(usize, String)> {
+        let mut lines: Vec<(usize, String)> = vec![];
+        let mut continued = false;
+
+        for (index, line) in config_lines.iter().enumerate() {
+            if Self::is_comment(line) {
+                continue;
+            }
+
+            match lines.last_mut() {
+                Some((_, previous_line)) if continued => {
+                    previous_line.push(' ');
+                    previous_line.push_str(line.trim());
+                },
+                _ => lines.push((index, line.to_string()))
+            }
+
+            let (_, last_line) = lines.last_mut().unwrap();
+            continued = Self::has_continuation_marker(last_line);
+            if continued {
+                last_line.truncate(last_line.trim_end().len() - CONTINUATION_MARKER.len());
+                last_line.truncate(last_line.trim_end().len());
+            }
+        }
+        lines
+    }
+
+    /// Replaces tabs with spaces, so that values separated by tabs are read in the same way as values separated by spaces.
+    pub fn replace_tabs(lines: &[String]) -> Vec<String> {
+        lines.iter().map(|line| line.replace('\t', " ")).collect()
+    }
+
+    /// Replaces tabs in info lines with spaces, where indented lines with tabs are realigned so that the tag ends at
+    /// the column of the colon and a continued value starts at the column of the values.
+    pub fn replace_info_tabs(lines: &[String]) -> Vec<String> {
+        lines.iter().map(|line| {
+            let text = line.replace('\t', " ");
+            if !line.contains('\t') || !text.starts_with(' ') || text.trim().is_empty() {
+                return text;
+            }
+
+            let text = text.trim();
+            match text.split_once(':') {
+                Some((tag, value)) if !tag.is_empty() && tag.len() <= INFO_TAG_WIDTH && tag.bytes().all(|char| char.is_ascii_uppercase()) =>
+                    format!("{tag:>INFO_TAG_WIDTH$}: {}", value.trim_start()),
+                _ => format!("{:INFO_TAG_WIDTH$}  {text}", "")
+            }
+        }).collect()
+    }
+
+    pub fn has_continuation_marker(text: &str) -> bool {
+        text.trim_end().ends_with(CONTINUATION_MARKER)
+    }
+
+    /// Whether the line is a comment, which starts with a semicolon and is skipped when reading signatures.
+    pub fn is_comment(line: &str) -> bool {
+        line.trim_start().starts_with(COMMENT_MARKER)
+    }
+
+    /// Reads the EXPECT annotations from the comment lines. An error is returned for an annotation that doesn't have a
+    /// signature name, file count and directory.
+    pub fn read_expectations(config_lines: &[String]) -> Result<Vec<SignatureExpectation>, String> {
+        let mut expectations = vec![];
+
+        for (line_number, line) in (1..).zip(config_lines) {
+            if !Self::is_expectation(line) {
+                continue;
+            }
+
+            let expectation = Self::parse_expectation(line, line_number)
+                .ok_or_else(|| format!("Invalid {EXPECT_ANNOTATION} annotation at line: {line_number}, use: ; {EXPECT_ANNOTATION} name count directory"))?;
+            expectations.push(expectation);
+        }
+        Ok(expectations)
+    }
+
+    pub fn is_expectation(line: &str) -> bool {
+        Self::get_comment_text(line).is_some_and(|text| text.split_ascii_whitespace().next() == Some(EXPECT_ANNOTATION))
+    }
+
+    pub fn parse_expectation(line: &str, line_number: usize) -> Option<SignatureExpectation> {
+        let text = Self::get_comment_text(line)?.trim().strip_prefix(EXPECT_ANNOTATION)?.trim_start();
+        let (signature_name, text) = text.split_once(' ')?;
+        let (file_count, directory) = text.trim_start().split_once(' ')?;
+        let file_count = file_count.parse::<usize>().ok()?;
+        let directory = directory.trim();
+
+        Some(SignatureExpectation { signature_name: signature_name.to_string(), file_count, directory: PathBuf::from(directory), line_number })
+    }
+
+    fn get_comment_text(line: &str) -> Option<&str> {
+        line.trim_start().strip_prefix(COMMENT_MARKER)
+    }
+
     pub fn has_end_marker(text: &str) -> bool {
         let text_len = text.len();
         text_len >= 3 && text.as_bytes()[text_len - 3..].eq_ignore_ascii_case(b"END")
     }
 
-    pub fn read_info_lines(lines: &Vec<String>) -> Result<Vec<SignatureInfo>, String> {
-        if !Self::is_info_file(lines) {
+    pub fn read_info_lines(lines: &[String], syntax: ConfigSyntax) -> Result<Vec<SignatureInfo>, String> {
+        let lines = &Self::replace_info_tabs(lines);
+
+        if !Self::is_info_file(lines, syntax) {
             return Err("Not an info file.".to_string());
         }
 
@@ -101,10 +541,10 @@ impl Signature {
 
         let mut info_lines = vec![];
         for line in lines {
-            if Self::is_signature_min_length(line) {
+            if Self::is_signature_min_length(line, syntax) {
                 if Self::is_info_tag(line) {
                     info_lines.push(line.to_string());
-                } else if Self::is_signature_name(line) {
+                } else if Self::is_signature_name(line, syntax) {
                     if !signature_name.is_empty() {
                         signature_infos.push((signature_name, info_lines.to_owned()));
                     }
@@ -128,16 +568,16 @@ impl Signature {
         Ok(signature_infos)
     }
 
-    pub fn is_config_file(config_lines: &[String]) -> bool {
-        let mut lines_iter = config_lines.iter();
+    pub fn is_config_file(config_lines: &[String], syntax: ConfigSyntax) -> bool {
+        let mut lines_iter = config_lines.iter().filter(|line| !Self::is_comment(line));
 
         while let Some(line) = lines_iter.next() {
             if line.trim().is_empty() {
                 continue;
             }
-            if Self::is_signature_min_length(line) && Self::is_signature_name(line) {
+            if Self::is_signature_min_length(line, syntax) && Self::is_signature_name(line, syntax) {
                 if let Some(line) = lines_iter.next() {
-                    return Self::is_signature_min_length(line) && !Self::is_signature_name(line);
+                    return Self::is_signature_min_length(line, syntax) && !Self::is_signature_name(line, syntax);
                 }
             }
             break;
@@ -145,16 +585,16 @@ impl Signature {
         false
     }
 
-    pub fn is_info_file(info_lines: &[String]) -> bool {
+    pub fn is_info_file(info_lines: &[String], syntax: ConfigSyntax) -> bool {
         let mut lines_iter = info_lines.iter();
 
         while let Some(line) = lines_iter.next() {
             if line.trim().is_empty() {
                 continue;
             }
-            if Self::is_signature_min_length(line) && Self::is_signature_name(line) && !Self::is_info_tag(line) {
+            if Self::is_signature_min_length(line, syntax) && Self::is_signature_name(line, syntax) && !Self::is_info_tag(line) {
                 if let Some(line) = lines_iter.next() {
-                    return Self::is_signature_min_length(line) && Self::is_info_tag(line);
+                    return Self::is_signature_min_length(line, syntax) && Self::is_info_tag(line);
                 }
             }
             break;
@@ -162,8 +602,8 @@ impl Signature {
         false
     }
 
-    pub fn is_signature_min_length(signature_text_line: &str) -> bool {
-        signature_text_line.len() >= 2
+    pub fn is_signature_min_length(signature_text_line: &str, syntax: ConfigSyntax) -> bool {
+        signature_text_line.len() >= syntax.min_line_length
     }
 
     pub fn is_info_tag(signature_text_line: &str) -> bool {
@@ -174,57 +614,203 @@ impl Signature {
         }
     }
 
-    pub fn is_signature_name(signature_text_line: &str) -> bool {
-        if let Some(chars) = signature_text_line.as_bytes().get(..3) {
-            chars[2] != b' ' && (signature_text_line.len() > 3 || !matches!(&chars.to_ascii_uppercase()[..], b"END" | b"AND"))
-        } else {
-            false
+    pub fn is_and_operator(word: &str) -> bool {
+        word == "AND" || word == "&&"
+    }
+
+    pub fn is_signature_name(signature_text_line: &str, syntax: ConfigSyntax) -> bool {
+        match signature_text_line.as_bytes().get(..syntax.min_name_length.max(DEFAULT_MIN_NAME_LENGTH)) {
+            Some(chars) => !chars[HEX_VALUE_LENGTH..].contains(&b' ') &&
+                !signature_text_line.eq_ignore_ascii_case("END") && !signature_text_line.eq_ignore_ascii_case("AND"),
+            None => false
         }
     }
 
-    fn process_multi_signatures(signature_name_to_filter: Option<&String>, signatures: &mut Vec<SignatureConfig>, signature_name: &str, signature_lines: &mut Vec<String>) {
+    fn process_multi_signatures(signature_name_to_filter: Option<&String>, signatures: &mut Vec<SignatureConfig>, signature_name: &str, signature_lines: &mut Vec<String>, strict: bool) -> Result<(), String> {
         for signature_line in signature_lines.drain(..) {
-            Self::process_signature_line(signature_name_to_filter, signatures, signature_name, &signature_line);
+            Self::process_signature_line(signature_name_to_filter, signatures, signature_name, &signature_line, strict)?;
         }
+        Ok(())
     }
 
-    fn process_single_signature(signature_name_to_filter: Option<&String>, signatures: &mut Vec<SignatureConfig>, signature_name: &str, signature_lines: &mut Vec<String>) {
-        Self::process_signature_line(signature_name_to_filter, signatures, signature_name, &signature_lines.join(" "));
+    fn process_single_signature(signature_name_to_filter: Option<&String>, signatures: &mut Vec<SignatureConfig>, signature_name: &str, signature_lines: &mut Vec<String>, strict: bool) -> Result<(), String> {
+        Self::process_signature_line(signature_name_to_filter, signatures, signature_name, &signature_lines.join(" "), strict)?;
         signature_lines.clear();
+        Ok(())
     }
 
-    fn process_signature_line(signature_name_to_filter: Option<&String>, signatures: &mut Vec<SignatureConfig>, signature_name: &str, signature_text: &str) {
-        let signature = Self::process_signature_value(signature_name, signature_text);
+    fn process_signature_line(signature_name_to_filter: Option<&String>, signatures: &mut Vec<SignatureConfig>, signature_name: &str, signature_text: &str, strict: bool) -> Result<(), String> {
         if signature_name_to_filter.is_none() || signature_name_to_filter.unwrap().eq_ignore_ascii_case(signature_name) {
+            let (signature, invalid_values) = Self::process_signature_value_with_invalid_values(signature_name, signature_text);
+
+            for (invalid_value, byte) in invalid_values {
+                let message = format!("Invalid value '{invalid_value}' read as {byte:02X} in signature: {signature_name}");
+                if strict {
+                    return Err(message);
+                }
+                log_error!("Warning: {message}\r");
+            }
             signatures.push(signature);
         }
+        Ok(())
+    }
+
+    pub fn process_signature_value(signature_name: &str, signature_text: &str) -> SignatureConfig {
+        Self::process_signature_value_with_invalid_values(signature_name, signature_text).0
     }
 
-    fn process_signature_value(signature_name: &str, signature_text: &str) -> SignatureConfig {
+    /// Same as `process_signature_value`, but also returns the values that aren't hexadecimal bytes, together with the
+    /// byte they are read as. Only the first two characters of a value are read, and when these aren't hexadecimal
+    /// digits the value is read as 00, so that the signature can still be used.
+    pub fn process_signature_value_with_invalid_values(signature_name: &str, signature_text: &str) -> (SignatureConfig, Vec<(String, u8)>) {
+        let mut invalid_values = vec![];
         let mut signature = vec![];
         let mut bndm_configs = vec![];
+        let mut min_size = 0;
+        let mut any_threshold = None;
+        let mut allow_overlap = false;
+        let mut entry_opcodes = vec![];
+
+        let signature_text = signature_text.to_ascii_uppercase();
+        let entry_point = signature_text.split_ascii_whitespace().any(|word| word == ENTRY_CLAUSE);
 
-        for word in signature_text.to_ascii_uppercase().split_ascii_whitespace() {
+        for word in signature_text.split_ascii_whitespace() {
             if word.len() >= 2 {
                 match word {
                     "??" => signature.push(CMD_WILDCARD),
-                    "AND" | "&&" | "END" => {
-                        Self::add_signature(&signature, &mut bndm_configs);
+                    _ if Self::is_and_operator(word) || word == "END" => {
+                        Self::add_segment(&signature, entry_point, &mut bndm_configs, &mut entry_opcodes);
                         signature.clear();
                     },
-                    _ => signature.push(Self::convert_hex_to_bin(&word[..2]))
+                    _ if word.starts_with(MIN_SIZE_CLAUSE) => min_size = Self::parse_min_size(word).unwrap_or(0),
+                    _ if word.starts_with(ANY_COMBINATOR) => any_threshold = Self::parse_any_threshold(word),
+                    OVERLAP_CLAUSE => allow_overlap = true,
+                    ENTRY_CLAUSE => {},
+                    _ => {
+                        let value = Self::convert_hex_to_bin(word.get(..2).unwrap_or_default());
+                        if word.len() > 2 || value.is_none() {
+                            invalid_values.push((word.to_string(), value.unwrap_or(0) as u8));
+                        }
+                        signature.push(value.unwrap_or(0));
+                    }
                 }
             }
         }
 
         if !signature.is_empty() {
-            Self::add_signature(&signature, &mut bndm_configs);
+            Self::add_segment(&signature, entry_point, &mut bndm_configs, &mut entry_opcodes);
+        }
+
+        (SignatureConfig { signature_name: Arc::from(signature_name), bndm_configs, min_size, any_threshold, allow_overlap, any_order: false, source: None, entry_opcodes }, invalid_values)
+    }
+
+    /// Adds a sub signature, where the values of an entry point fingerprint are opcodes that are each followed by
+    /// wildcards for their operands.
+    fn add_segment(signature: &[u16], entry_point: bool, bndm_configs: &mut Vec<BndmConfig>, entry_opcodes: &mut Vec<u8>) {
+        if entry_point {
+            entry_opcodes.extend(signature.iter().map(|&opcode| opcode as u8));
+            Self::add_signature(&Self::expand_opcodes(signature), bndm_configs);
+        } else {
+            Self::add_signature(signature, bndm_configs);
+        }
+    }
+
+    /// Adds wildcards for the operands after each opcode. The operands of the last instruction are left out, so that
+    /// the pattern doesn't end with a wildcard.
+    fn expand_opcodes(opcodes: &[u16]) -> Vec<u16> {
+        let mut pattern = vec![];
+        for &opcode in opcodes {
+            pattern.push(opcode);
+            if opcode != CMD_WILDCARD {
+                pattern.resize(pattern.len() + Self::get_instruction_length(opcode as u8) - 1, CMD_WILDCARD);
+            }
+        }
+
+        while pattern.last() == Some(&CMD_WILDCARD) {
+            pattern.pop();
+        }
+        pattern
+    }
+
+    /// Replaces the operands of instructions with an absolute address by wildcards, so that the signature also matches
+    /// copies of the player that are relocated to another address. Each sub signature is read as 6502 instructions from
+    /// its first byte. Addresses that don't change when a player is relocated, like those of the SID registers, are kept.
+    pub fn mask_absolute_addresses(signature: &mut SignatureConfig) {
+        if !signature.entry_opcodes.is_empty() {
+            return;
+        }
+
+        for config in std::mem::take(&mut signature.bndm_configs) {
+            let mut pattern = config.pattern.iter()
+                .map(|&value| if Some(value) == config.wildcard { CMD_WILDCARD } else { value as u16 })
+                .collect::<Vec<u16>>();
+            Self::mask_address_operands(&mut pattern);
+            while pattern.last() == Some(&CMD_WILDCARD) {
+                pattern.pop();
+            }
+
+            let config_count = signature.bndm_configs.len();
+            Self::add_signature(&pattern, &mut signature.bndm_configs);
+            if signature.bndm_configs.len() == config_count {
+                signature.bndm_configs.push(config);
+            }
+        }
+    }
+
+    fn mask_address_operands(pattern: &mut [u16]) {
+        let mut index = 0;
+        while index < pattern.len() {
+            if pattern[index] == CMD_WILDCARD {
+                index += 1;
+                continue;
+            }
+
+            let length = Self::get_instruction_length(pattern[index] as u8);
+            if length == ABSOLUTE_INSTRUCTION_LENGTH && index + 2 < pattern.len() && Self::is_relocatable_address(pattern[index + 2]) {
+                pattern[index + 1] = CMD_WILDCARD;
+                pattern[index + 2] = CMD_WILDCARD;
+            }
+            index += length;
+        }
+    }
+
+    fn is_relocatable_address(high_byte: u16) -> bool {
+        high_byte == CMD_WILDCARD || !FIXED_ADDRESS_PAGES.iter().any(|pages| pages.contains(&high_byte))
+    }
+
+    /// Returns the length in bytes of a 6502 instruction, including the undocumented opcodes.
+    pub fn get_instruction_length(opcode: u8) -> usize {
+        match opcode {
+            0x20 => 3,
+            0x00 | 0x40 | 0x60 => 1,
+            _ => match opcode & 0x0F {
+                0x02 if opcode >= 0x80 && opcode & 0x10 == 0 => 2,
+                0x02 | 0x08 | 0x0A => 1,
+                0x09 | 0x0B if opcode & 0x10 != 0 => 3,
+                0x0C..=0x0F => 3,
+                _ => 2
+            }
         }
+    }
 
-        SignatureConfig { signature_name: signature_name.to_string(), bndm_configs }
+    pub fn parse_any_threshold(word: &str) -> Option<usize> {
+        word.strip_prefix(ANY_COMBINATOR)?.strip_suffix('}')?.parse::<usize>().ok()
+    }
+
+    pub fn parse_min_size(word: &str) -> Option<usize> {
+        let value = word.get(MIN_SIZE_CLAUSE.len()..)?;
+        if let Some(hex_value) = value.strip_prefix('$') {
+            usize::from_str_radix(hex_value, 16).ok()
+        } else {
+            value.parse::<usize>().ok()
+        }
     }
 
     fn add_signature(signature: &[u16], bndm_configs: &mut Vec<BndmConfig>) {
+        if signature.is_empty() {
+            return;
+        }
+
         let (wildcard_used, calculated_wildcard) = Self::calculate_wildcard(signature);
 
         if !wildcard_used || calculated_wildcard.is_some() {
@@ -262,7 +848,15 @@ impl Signature {
         }
     }
 
-    fn convert_hex_to_bin(digit_string: &str) -> u16 {
-        u16::from_str_radix(digit_string, 16).unwrap_or(0)
+    fn convert_hex_to_bin(digit_string: &str) -> Option<u16> {
+        if !digit_string.is_empty() && digit_string.bytes().all(|digit| digit.is_ascii_hexdigit()) {
+            u16::from_str_radix(digit_string, 16).ok()
+        } else {
+            None
+        }
     }
 }
+
+#[cfg(test)]
+#[path = "./signature_test.rs"]
+mod signature_test;
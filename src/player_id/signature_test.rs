@@ -0,0 +1,449 @@
+use super::*;
+
+fn load_signatures(config: &str) -> Vec<SignatureConfig> {
+    let lines = config.lines().map(|line| line.to_string()).collect::<Vec<String>>();
+    Signature::read_config_lines(&lines, None, ConfigSyntax::default()).unwrap()
+}
+
+fn find_signature_names(source: &[u8], signatures: &Vec<SignatureConfig>) -> Vec<String> {
+    Signature::find_signatures(source, 0, signatures, true).into_iter().map(|signature_match| signature_match.signature_name.to_string()).collect()
+}
+
+#[test]
+fn parse_min_size() {
+    assert_eq!(Signature::parse_min_size("MINSIZE=4096"), Some(4096));
+    assert_eq!(Signature::parse_min_size("MINSIZE=$1000"), Some(4096));
+    assert_eq!(Signature::parse_min_size("MINSIZE=XYZ"), None);
+}
+
+#[test]
+fn find_signatures_min_size() {
+    let signatures = load_signatures("Test_Player\nA9 00 8D ?? D4 MINSIZE=16");
+
+    assert_eq!(signatures[0].min_size, 16);
+
+    let mut source = vec![0xA9, 0x00, 0x8D, 0x18, 0xD4];
+    assert!(find_signature_names(&source, &signatures).is_empty());
+
+    source.resize(16, 0);
+    assert_eq!(find_signature_names(&source, &signatures), vec!["Test_Player".to_string()]);
+}
+
+fn find_signatures_in_chunks(source: &[u8], signatures: &[SignatureConfig], chunk_size: usize) -> Vec<(String, Vec<usize>)> {
+    Signature::find_signatures_in_reader(&mut io::Cursor::new(source), 0, signatures, true, chunk_size, None).unwrap().unwrap()
+        .into_iter()
+        .map(|signature_match| (signature_match.signature_name.to_string(), signature_match.indexes))
+        .collect()
+}
+
+#[test]
+fn find_signatures_in_reader_across_chunk_boundary() {
+    let signatures = load_signatures("Test_Player\nA9 00 8D ?? D4");
+    let mut source = vec![0; 32];
+    source[6..11].copy_from_slice(&[0xA9, 0x00, 0x8D, 0x18, 0xD4]);
+
+    for chunk_size in [4, 5, 8, 9, 16, 32, 64] {
+        assert_eq!(find_signatures_in_chunks(&source, &signatures, chunk_size), vec![("Test_Player".to_string(), vec![6])]);
+    }
+}
+
+#[test]
+fn find_signatures_in_reader_and_chain_across_chunks() {
+    let signatures = load_signatures("Test_Player\nA9 00 8D && 8D 18 D4");
+    let mut source = vec![0; 40];
+    source[2..5].copy_from_slice(&[0xA9, 0x00, 0x8D]);
+    source[14..17].copy_from_slice(&[0x8D, 0x18, 0xD4]);
+
+    for chunk_size in [3, 4, 8, 15, 16, 64] {
+        assert_eq!(find_signatures_in_chunks(&source, &signatures, chunk_size), vec![("Test_Player".to_string(), vec![2, 14])]);
+    }
+}
+
+#[test]
+fn find_signatures_in_reader_same_as_in_memory() {
+    let signatures = load_signatures("First\nA9 00 8D\nSecond\n8D 18 D4 && A9 ?? 8D\nThird\nFF FF FF");
+    let source = [0x00, 0x8D, 0x18, 0xD4, 0x00, 0xA9, 0x00, 0x8D, 0x00, 0xA9, 0x01, 0x8D];
+
+    let expected = Signature::find_signatures(&source, 0, &signatures, true).into_iter()
+        .map(|signature_match| (signature_match.signature_name.to_string(), signature_match.indexes))
+        .collect::<Vec<(String, Vec<usize>)>>();
+
+    assert_eq!(expected.len(), 2);
+    for chunk_size in [2, 3, 5, 7, 12] {
+        assert_eq!(find_signatures_in_chunks(&source, &signatures, chunk_size), expected);
+    }
+}
+
+fn find_signatures_at_stride(source: &[u8], signatures: &Vec<SignatureConfig>, scan_for_multiple: bool, stride: usize) -> Vec<(String, Vec<usize>)> {
+    let matches = Signature::find_signatures(source, 0, signatures, scan_for_multiple);
+    Signature::find_signatures_at_stride(source, 0, signatures, scan_for_multiple, stride, matches, None)
+        .unwrap()
+        .into_iter()
+        .map(|signature_match| (signature_match.signature_name.to_string(), signature_match.indexes))
+        .collect()
+}
+
+#[test]
+fn find_signatures_at_stride_only_matches_with_stride() {
+    let signatures = load_signatures("Test_Player\nA9 00 8D && 18 D4");
+    let source = [0x00, 0xA9, 0xEA, 0x00, 0xEA, 0x8D, 0xEA, 0x18, 0xEA, 0xD4, 0xEA];
+
+    assert!(find_signatures_at_stride(&source, &signatures, false, 1).is_empty());
+    assert!(find_signatures_at_stride(&source, &signatures, false, 3).is_empty());
+    assert_eq!(find_signatures_at_stride(&source, &signatures, false, 2), vec![("Test_Player".to_string(), vec![1, 7])]);
+}
+
+#[test]
+fn find_signatures_at_stride_keeps_natural_matches() {
+    let signatures = load_signatures("First_Player\nA9 00 8D\n\nSecond_Player\nA2 00 BD");
+    let source = [0xA9, 0x00, 0x8D, 0xA2, 0xEA, 0x00, 0xEA, 0xBD];
+
+    assert_eq!(find_signatures_at_stride(&source, &signatures, false, 2), vec![("First_Player".to_string(), vec![0])]);
+    assert_eq!(find_signatures_at_stride(&source, &signatures, true, 2), vec![
+        ("First_Player".to_string(), vec![0]),
+        ("Second_Player".to_string(), vec![3])
+    ]);
+}
+
+struct CountingReader<'a> {
+    source: &'a [u8],
+    bytes_read: usize
+}
+
+impl Read for CountingReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let bytes_read = (&self.source[self.bytes_read..]).read(buf)?;
+        self.bytes_read += bytes_read;
+        Ok(bytes_read)
+    }
+}
+
+#[test]
+fn find_signatures_in_reader_stops_after_first_match() {
+    let signatures = load_signatures("Test_Player\nA9 00 8D\n\nOther_Player\nA2 00 BD");
+    let mut source = vec![0x00; 1024];
+    source[..3].copy_from_slice(&[0xA9, 0x00, 0x8D]);
+
+    let mut reader = CountingReader { source: &source, bytes_read: 0 };
+    let matches = Signature::find_signatures_in_reader(&mut reader, 0, &signatures, false, 16, None).unwrap().unwrap();
+    assert_eq!(matches[0].signature_name.as_ref(), "Test_Player");
+    assert_eq!(reader.bytes_read, 16);
+
+    let mut reader = CountingReader { source: &source, bytes_read: 0 };
+    Signature::find_signatures_in_reader(&mut reader, 0, &signatures, true, 16, None).unwrap().unwrap();
+    assert_eq!(reader.bytes_read, source.len());
+}
+
+#[test]
+fn find_signatures_in_reader_stops_at_the_deadline() {
+    let signatures = load_signatures("Test_Player\nA9 00 8D");
+    let source = vec![0x00; 1024];
+
+    let mut reader = CountingReader { source: &source, bytes_read: 0 };
+    let matches = Signature::find_signatures_in_reader(&mut reader, 0, &signatures, true, 16, Some(Instant::now())).unwrap();
+    assert!(matches.is_none());
+    assert_eq!(reader.bytes_read, 0);
+}
+
+#[test]
+fn find_signatures_in_reader_keeps_config_order() {
+    let signatures = load_signatures("Test_Player\nA9 00 8D\n\nOther_Player\nA2 00 BD");
+    let mut source = vec![0x00; 1024];
+    source[..3].copy_from_slice(&[0xA2, 0x00, 0xBD]);
+    source[1000..1003].copy_from_slice(&[0xA9, 0x00, 0x8D]);
+
+    let mut reader = CountingReader { source: &source, bytes_read: 0 };
+    let matches = Signature::find_signatures_in_reader(&mut reader, 0, &signatures, false, 16, None).unwrap().unwrap();
+    assert_eq!(matches[0].signature_name.as_ref(), "Test_Player");
+    assert_eq!(reader.bytes_read, 1008);
+}
+
+#[test]
+fn find_signatures_any_threshold() {
+    let signatures = load_signatures("Two_Of_Three\nA9 00 8D && 8D 18 D4 && A2 ?? BD ANY{2}\n\nThree_Of_Three\nA9 00 8D && 8D 18 D4 && A2 ?? BD ANY{3}");
+    let source = [0x00, 0xA2, 0x01, 0xBD, 0x00, 0xA9, 0x00, 0x8D, 0x00];
+
+    assert_eq!(signatures[0].any_threshold, Some(2));
+    assert_eq!(find_signature_names(&source, &signatures), vec!["Two_Of_Three".to_string()]);
+    assert_eq!(Signature::find_signatures(&source, 0, &signatures, true)[0].indexes, vec![5, 1]);
+
+    let source = [0xA2, 0x01, 0xBD, 0x8D, 0x18, 0xD4, 0xA9, 0x00, 0x8D];
+    assert_eq!(find_signature_names(&source, &signatures), vec!["Two_Of_Three".to_string(), "Three_Of_Three".to_string()]);
+
+    assert!(find_signature_names(&[0xA9, 0x00, 0x8D], &signatures).is_empty());
+    assert_eq!(find_signatures_in_chunks(&source, &signatures, 4), vec![
+        ("Two_Of_Three".to_string(), vec![6, 3, 0]),
+        ("Three_Of_Three".to_string(), vec![6, 3, 0])
+    ]);
+}
+
+#[test]
+fn get_wildcard_density() {
+    let signatures = load_signatures("No_Wildcards\nA9 00 8D 18\n\nHalf_Wildcards\nA9 ?? 8D ?? && BD ?? ?? 9D\n\nMostly_Wildcards\nA9 ?? ?? ?? ?? ?? ?? ?? ?? D4");
+
+    assert_eq!(Signature::get_wildcard_density(&signatures[0]), 0.0);
+    assert_eq!(Signature::get_wildcard_density(&signatures[1]), 0.5);
+    assert_eq!(Signature::get_wildcard_density(&signatures[2]), 0.8);
+}
+
+#[test]
+fn find_signatures_wildcard_density() {
+    let signatures = load_signatures("Test_Player\nA9 ?? 8D ?? D4");
+    let matches = Signature::find_signatures(&[0xA9, 0x00, 0x8D, 0x18, 0xD4], 0, &signatures, false);
+
+    assert_eq!(matches[0].wildcard_density, 0.4);
+}
+
+#[test]
+fn read_config_lines_mixed_and_operators() {
+    let mixed = load_signatures("Test_Player\nA9 00 8D && 8D 18 D4 AND A2 ?? BD");
+    let and_only = load_signatures("Test_Player\nA9 00 8D AND 8D 18 D4 AND A2 ?? BD");
+
+    assert_eq!(mixed[0].bndm_configs.len(), 3);
+    assert_eq!(mixed[0].bndm_configs.len(), and_only[0].bndm_configs.len());
+
+    let source = [0xA9, 0x00, 0x8D, 0x8D, 0x18, 0xD4, 0xA2, 0x01, 0xBD];
+    assert_eq!(Signature::find_signatures(&source, 0, &mixed, false)[0].indexes, vec![0, 3, 6]);
+}
+
+#[test]
+fn read_config_lines_continuation_marker() {
+    let wrapped = load_signatures("Test_Player\nA9 00 8D \\\n  8D 18 D4 && \\\nA2 ?? BD\n\nOther_Player\nA2 00 BD");
+    let single_line = load_signatures("Test_Player\nA9 00 8D 8D 18 D4 && A2 ?? BD\n\nOther_Player\nA2 00 BD");
+
+    assert_eq!(wrapped.len(), single_line.len());
+    for (wrapped, single_line) in wrapped.iter().zip(&single_line) {
+        assert_eq!(wrapped.signature_name, single_line.signature_name);
+        assert_eq!(wrapped.bndm_configs.iter().map(|config| &config.pattern).collect::<Vec<_>>(),
+                   single_line.bndm_configs.iter().map(|config| &config.pattern).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn join_continuation_lines() {
+    let lines = ["Test_Player", "A9 00 \\", "8D 18\\", "D4", "", "Other_Player"].map(str::to_string);
+
+    assert_eq!(Signature::join_continuation_lines(&lines), vec![
+        (0, "Test_Player".to_string()),
+        (1, "A9 00 8D 18 D4".to_string()),
+        (4, "".to_string()),
+        (5, "Other_Player".to_string())
+    ]);
+}
+
+#[test]
+fn get_confidence() {
+    let signatures = load_signatures("Strong_Player\nA9 00 8D 18 D4 60\n\nShort_Player\nA9 00 8D 18 D4\n\nWildcard_Player\nA9 00 8D ?? ?? ?? ?? 18 D4 60 ?? ?? ??\n\nAny_Player\nA9 00 8D 18 D4 60 && A2 00 BD ANY{1}");
+
+    assert_eq!(Signature::get_confidence(&signatures[0]), Confidence::Strong);
+    assert_eq!(Signature::get_confidence(&signatures[1]), Confidence::Weak);
+    assert_eq!(Signature::get_confidence(&signatures[2]), Confidence::Weak);
+    assert_eq!(Signature::get_confidence(&signatures[3]), Confidence::Weak);
+
+    let matches = Signature::find_signatures(&[0xA9, 0x00, 0x8D, 0x18, 0xD4, 0x60], 0, &signatures, true);
+    assert_eq!(matches[0].confidence, Confidence::Strong);
+    assert_eq!(matches[1].confidence, Confidence::Weak);
+}
+
+#[test]
+fn read_config_lines_not_a_config_file() {
+    let lines = ["     NAME: Test Player", "   AUTHOR: Test"].map(str::to_string);
+
+    assert_eq!(Signature::read_config_lines(&lines, None, ConfigSyntax::default()).err(), Some("Not a config file.".to_string()));
+}
+
+#[test]
+fn read_config_lines_without_signatures() {
+    assert!(Signature::read_config_lines(&[], None, ConfigSyntax::default()).unwrap().is_empty());
+    assert!(Signature::read_config_lines(&["".to_string(), "  ".to_string()], None, ConfigSyntax::default()).unwrap().is_empty());
+
+    let lines = ["Test_Player", "A9 00 8D"].map(str::to_string);
+    assert!(Signature::read_config_lines(&lines, Some(&"Other_Player".to_string()), ConfigSyntax::default()).unwrap().is_empty());
+}
+
+#[test]
+fn read_config_lines_with_tabs() {
+    let with_tabs = load_signatures("Test_Player\nA9\t00 8D\t&&\t8D 18 D4\n\t\nOther_Player\n\tA2 ?? BD");
+    let with_spaces = load_signatures("Test_Player\nA9 00 8D && 8D 18 D4\n\nOther_Player\nA2 ?? BD");
+
+    assert_eq!(with_tabs.len(), with_spaces.len());
+    for (with_tabs, with_spaces) in with_tabs.iter().zip(&with_spaces) {
+        assert_eq!(with_tabs.signature_name, with_spaces.signature_name);
+        assert_eq!(with_tabs.bndm_configs.iter().map(|config| &config.pattern).collect::<Vec<_>>(),
+                   with_spaces.bndm_configs.iter().map(|config| &config.pattern).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn process_signature_value_without_byte_sequence() {
+    assert!(Signature::process_signature_value("Test_Player", "MINSIZE=1000 END").bndm_configs.is_empty());
+    assert!(Signature::process_signature_value("Test_Player", "AND && END").bndm_configs.is_empty());
+    assert_eq!(Signature::process_signature_value("Test_Player", "A9 00 AND && 8D 18 END").bndm_configs.len(), 2);
+}
+
+#[test]
+fn find_signatures_overlapping_sub_signatures() {
+    let source = [0xA9, 0x00, 0x8D, 0x18, 0xD4];
+
+    let signatures = load_signatures("Test_Player\nA9 00 8D && 8D 18 D4");
+    assert!(!signatures[0].allow_overlap);
+    assert!(find_signature_names(&source, &signatures).is_empty());
+
+    let signatures = load_signatures("Test_Player\nA9 00 8D && 8D 18 D4 OVERLAP");
+    assert!(signatures[0].allow_overlap);
+    assert_eq!(Signature::find_signatures(&source, 0, &signatures, false)[0].indexes, vec![0, 2]);
+    assert_eq!(find_signatures_in_chunks(&source, &signatures, 2), vec![("Test_Player".to_string(), vec![0, 2])]);
+}
+
+#[test]
+fn find_signatures_in_any_order() {
+    let source = [0x8D, 0x18, 0xD4, 0x00, 0xA9, 0x00, 0x8D];
+
+    let mut signatures = load_signatures("Test_Player\nA9 00 8D && 8D 18 D4");
+    assert!(find_signature_names(&source, &signatures).is_empty());
+
+    signatures[0].any_order = true;
+    assert_eq!(Signature::find_signatures(&source, 0, &signatures, false)[0].indexes, vec![4, 0]);
+    assert_eq!(find_signatures_in_chunks(&source, &signatures, 3), vec![("Test_Player".to_string(), vec![4, 0])]);
+    assert_eq!(Signature::get_confidence(&signatures[0]), Confidence::Strong);
+
+    assert!(find_signature_names(&source[..4], &signatures).is_empty());
+}
+
+#[test]
+fn uses_wildcard_only_when_pattern_contains_it() {
+    let signatures = load_signatures("Exact_Player\nA9 00 8D 18 D4\n\nWildcard_Player\nA9 ?? 8D 18 D4");
+
+    assert!(signatures[0].bndm_configs[0].wildcard.is_some());
+    assert!(!Signature::uses_wildcard(&signatures[0].bndm_configs[0]));
+    assert!(Signature::uses_wildcard(&signatures[1].bndm_configs[0]));
+
+    let matches = Signature::find_signatures(&[0xA9, 0x00, 0x8D, 0x18, 0xD4], 0, &signatures, true);
+    assert!(!matches[0].wildcard_used);
+    assert!(matches[1].wildcard_used);
+}
+
+#[test]
+fn find_pattern_in_source_single_byte_patterns() {
+    let wildcard_only = BndmConfig::new(&[0x00], Some(0x00));
+    assert_eq!(Signature::find_pattern_in_source(&[], &wildcard_only), None);
+    assert_eq!(Signature::find_pattern_in_source(&[0xEA], &wildcard_only), Some(0));
+
+    let single_byte = BndmConfig::new(&[0xA9], Some(0x00));
+    assert_eq!(Signature::find_pattern_in_source(&[], &single_byte), None);
+    assert_eq!(Signature::find_pattern_in_source(&[0xEA, 0xA9], &single_byte), Some(1));
+    assert_eq!(Signature::find_pattern_in_source(&[0xEA, 0xEA], &single_byte), None);
+}
+
+#[test]
+fn find_signatures_wildcard_only_sub_signature_past_end_of_source() {
+    let signatures = load_signatures("Test_Player\nA9 00 8D && ??");
+
+    assert!(find_signature_names(&[0xA9, 0x00, 0x8D], &signatures).is_empty());
+    assert_eq!(Signature::find_signatures(&[0xA9, 0x00, 0x8D, 0xEA], 0, &signatures, false)[0].indexes, vec![0, 3]);
+    assert!(find_signatures_in_chunks(&[0xA9, 0x00, 0x8D], &signatures, 2).is_empty());
+}
+
+#[test]
+fn read_config_lines_with_raised_min_lengths() {
+    let lines = ["Test".to_string(), "A9 00 8D".to_string(), "AB".to_string(), "A2 00 BD".to_string()];
+    let syntax = ConfigSyntax { min_line_length: 3, min_name_length: 4, ..ConfigSyntax::default() };
+
+    assert_eq!(Signature::read_config_lines(&lines, None, ConfigSyntax::default()).unwrap().len(), 3);
+
+    let signatures = Signature::read_config_lines(&lines, None, syntax).unwrap();
+    assert_eq!(signatures.len(), 2);
+    assert_eq!(signatures[0].signature_name.as_ref(), "Test");
+
+    assert!(Signature::is_signature_name("Test", syntax));
+    assert!(!Signature::is_signature_name("Tst", syntax));
+    assert!(!Signature::is_signature_name("A9 00", syntax));
+    assert!(!Signature::is_signature_min_length("A9", syntax));
+}
+
+#[test]
+fn read_config_lines_reports_invalid_values() {
+    let lines = ["Test_Player".to_string(), "A9 0G 8D 18X D4".to_string()];
+
+    let (signatures, messages) = crate::log::buffered(|| Signature::read_config_lines(&lines, None, ConfigSyntax::default()).unwrap());
+    assert_eq!(signatures[0].bndm_configs[0].pattern, vec![0xA9, 0x00, 0x8D, 0x18, 0xD4]);
+    assert_eq!(messages, vec![
+        "Warning: Invalid value '0G' read as 00 in signature: Test_Player\r",
+        "Warning: Invalid value '18X' read as 18 in signature: Test_Player\r"
+    ]);
+
+    let strict_syntax = ConfigSyntax { strict: true, ..ConfigSyntax::default() };
+    assert_eq!(Signature::read_config_lines(&lines, None, strict_syntax).err(),
+        Some("Invalid value '0G' read as 00 in signature: Test_Player".to_string()));
+
+    let (_, messages) = crate::log::buffered(|| Signature::read_config_lines(&["Test_Player".to_string(), "A9 00 8D ?? D4".to_string()], None, ConfigSyntax::default()).unwrap());
+    assert!(messages.is_empty());
+}
+
+#[test]
+fn get_instruction_length_of_opcodes() {
+    let lengths = [(0x00, 1), (0x20, 3), (0x4C, 3), (0x60, 1), (0xA9, 2), (0xA2, 2), (0x02, 1), (0x8D, 3), (0xBD, 3),
+        (0xB9, 3), (0x99, 3), (0xA8, 1), (0xEA, 1), (0xD0, 2), (0xB1, 2), (0x0B, 2), (0x1B, 3), (0xFF, 3)];
+
+    for (opcode, length) in lengths {
+        assert_eq!(Signature::get_instruction_length(opcode), length, "opcode ${opcode:02X}");
+    }
+}
+
+#[test]
+fn find_signatures_with_entry_point_fingerprint() {
+    let signatures = Signature::read_config_lines(&["Test_Player".to_string(), "A9 8D A2 BD 60 ENTRY".to_string()], None, ConfigSyntax::default()).unwrap();
+    assert_eq!(signatures[0].entry_opcodes, vec![0xA9, 0x8D, 0xA2, 0xBD, 0x60]);
+    assert_eq!(signatures[0].bndm_configs[0].pattern.len(), 11);
+
+    // LDA #$0F, STA $D418, LDX #$00, LDA $1000,X, RTS
+    let routine = [0xA9, 0x0F, 0x8D, 0x18, 0xD4, 0xA2, 0x00, 0xBD, 0x00, 0x10, 0x60];
+    let relocated_routine = [0xA9, 0x00, 0x8D, 0x00, 0x20, 0xA2, 0x07, 0xBD, 0x34, 0x12, 0x60];
+
+    let source = [&[0xEA, 0xEA][..], &routine].concat();
+    assert_eq!(Signature::find_signatures(&source, 2, &signatures, false)[0].indexes, vec![2]);
+    assert!(Signature::find_signatures(&source, 0, &signatures, false).is_empty());
+    assert_eq!(Signature::find_signatures(&relocated_routine, 0, &signatures, false)[0].indexes, vec![0]);
+
+    let mut reader = io::Cursor::new(&source);
+    assert_eq!(Signature::find_signatures_in_reader(&mut reader, 2, &signatures, false, 4, None).unwrap().unwrap()[0].indexes, vec![2]);
+}
+
+#[test]
+fn find_signatures_with_masked_absolute_addresses() {
+    // LDX #$00, LDA $1020,X, STA $D400,X, INX, CPX #$19, BNE, JSR $1040, RTS
+    let routine = [0xA2, 0x00, 0xBD, 0x20, 0x10, 0x9D, 0x00, 0xD4, 0xE8, 0xE0, 0x19, 0xD0, 0xF5, 0x20, 0x40, 0x10, 0x60];
+    let relocated_routine = [0xEA, 0xA2, 0x00, 0xBD, 0x23, 0xC4, 0x9D, 0x00, 0xD4, 0xE8, 0xE0, 0x19, 0xD0, 0xF5, 0x20, 0x43, 0xC4, 0x60];
+    let other_register_routine = [0xA2, 0x00, 0xBD, 0x20, 0x10, 0x9D, 0x00, 0xD5, 0xE8, 0xE0, 0x19, 0xD0, 0xF5, 0x20, 0x40, 0x10, 0x60];
+
+    let mut signatures = load_signatures("Test_Player\nA2 00 BD 20 10 9D 00 D4 E8 E0 19 D0 F5 20 40 10 60");
+    assert_eq!(find_signature_names(&routine, &signatures), vec!["Test_Player"]);
+    assert!(find_signature_names(&relocated_routine, &signatures).is_empty());
+
+    Signature::mask_absolute_addresses(&mut signatures[0]);
+    assert_eq!(Signature::find_signatures(&routine, 0, &signatures, false)[0].indexes, vec![0]);
+    assert_eq!(Signature::find_signatures(&relocated_routine, 0, &signatures, false)[0].indexes, vec![1]);
+    assert!(find_signature_names(&other_register_routine, &signatures).is_empty());
+}
+
+#[test]
+fn read_config_lines_with_expectations() {
+    let lines = ["; EXPECT Test_Player 2 tunes/Test Player", "Test_Player", "; a comment", "A9 00 8D 18 D4", ";EXPECT Test_Player many tunes"]
+        .map(|line| line.to_string());
+
+    assert_eq!(Signature::read_config_lines(&lines, None, ConfigSyntax::default()).unwrap().len(), 1);
+    assert!(Signature::is_config_file(&lines, ConfigSyntax::default()));
+    assert_eq!(Signature::read_expectations(&lines[..4]).unwrap(), vec![SignatureExpectation {
+        signature_name: "Test_Player".to_string(), file_count: 2, directory: PathBuf::from("tunes/Test Player"), line_number: 1
+    }]);
+    assert!(Signature::read_expectations(&lines).unwrap_err().contains("line: 5"));
+}
+
+#[test]
+fn read_info_lines_with_tabs() {
+    let to_lines = |text: &str| text.lines().map(str::to_string).collect::<Vec<String>>();
+    let with_tabs = Signature::read_info_lines(&to_lines("Test_Player\n\tNAME:\tTest Player\n   AUTHOR:\tSomeone\n\t\tand someone else\n\nOther_Player\n\tNAME: Other"), ConfigSyntax::default()).unwrap();
+    let with_spaces = Signature::read_info_lines(&to_lines("Test_Player\n     NAME: Test Player\n   AUTHOR: Someone\n           and someone else\n\nOther_Player\n     NAME: Other"), ConfigSyntax::default()).unwrap();
+
+    assert_eq!(with_tabs, with_spaces);
+}
@@ -0,0 +1,75 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+use std::collections::HashSet;
+
+use super::signature::{ConfigSyntax, Signature, SignatureConfig};
+
+const MIN_SEQUENCE_LENGTH: usize = 4;
+const MAX_SEQUENCE_LENGTH: usize = 32;
+
+/// Finds the shortest byte sequence that is in all positives and in none of the negatives, and turns it into a signature
+/// with the given name. Of the sequences with the same length, the first one in the first positive is used. Sequences of
+/// a single repeated byte are skipped, since they are found in too many files to identify a player. Every candidate is
+/// checked with the matcher before it is suggested.
+pub fn suggest_signature(positives: &[&[u8]], negatives: &[&[u8]], signature_name: &str) -> Result<Option<SignatureConfig>, String> {
+    let Some((first_positive, other_positives)) = positives.split_first() else {
+        return Ok(None);
+    };
+
+    for length in MIN_SEQUENCE_LENGTH..=MAX_SEQUENCE_LENGTH.min(first_positive.len()) {
+        let Some(candidates) = get_candidates(first_positive, other_positives, negatives, length) else {
+            break;
+        };
+
+        for sequence in first_positive.windows(length) {
+            if !candidates.contains(sequence) {
+                continue;
+            }
+
+            let mut signatures = Signature::read_config_lines(&[signature_name.to_string(), format_sequence(sequence)], None, ConfigSyntax::default())?;
+            if signatures.len() != 1 {
+                return Err(format!("Invalid signature name: {signature_name}"));
+            }
+            if is_distinguishing(&signatures, positives, negatives) {
+                return Ok(signatures.pop());
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Returns the sequences of the given length of the first positive that are also in the other positives and not in any
+/// negative, or `None` when no sequence is in all positives, so that longer sequences aren't either. Only the sequences
+/// of the first positive are kept in memory, while the windows of the other files are streamed through.
+fn get_candidates<'a>(first_positive: &'a [u8], other_positives: &[&[u8]], negatives: &[&[u8]], length: usize) -> Option<HashSet<&'a [u8]>> {
+    let mut candidates = first_positive.windows(length).collect::<HashSet<&[u8]>>();
+    for data in other_positives {
+        let found = data.windows(length).filter_map(|window| candidates.get(window).copied()).collect::<HashSet<&[u8]>>();
+        if found.is_empty() {
+            return None;
+        }
+        candidates = found;
+    }
+
+    candidates.retain(|sequence| !is_single_value(sequence));
+    for data in negatives {
+        data.windows(length).for_each(|window| {
+            candidates.remove(window);
+        });
+    }
+    Some(candidates)
+}
+
+fn is_single_value(sequence: &[u8]) -> bool {
+    sequence.iter().all(|value| *value == sequence[0])
+}
+
+fn format_sequence(sequence: &[u8]) -> String {
+    sequence.iter().map(|value| format!("{value:02X}")).collect::<Vec<String>>().join(" ")
+}
+
+fn is_distinguishing(signatures: &Vec<SignatureConfig>, positives: &[&[u8]], negatives: &[&[u8]]) -> bool {
+    positives.iter().all(|data| !Signature::find_signatures(data, 0, signatures, false).is_empty()) &&
+        negatives.iter().all(|data| Signature::find_signatures(data, 0, signatures, false).is_empty())
+}
@@ -2,25 +2,50 @@
 // Licensed under the MIT license. See the LICENSE file for the terms and conditions.
 
 use std::collections::HashMap;
+use rayon::prelude::*;
+use url::Url;
+use crate::log_error;
 use crate::player_id::SignatureConfig;
-use crate::player_id::signature::Signature;
+use crate::player_id::signature::{ConfigSyntax, Signature};
 
-pub fn verify_config_file(config_lines: &Vec<String>) -> Result<bool, String> {
+// the number of bytes at the start of a pattern that the masks of the matcher are built from
+const MATCHER_WINDOW_SIZE: usize = usize::BITS as usize;
+
+struct SignatureValue {
+    line_number: i32,
+    signature_name: String,
+    segments: Vec<Vec<String>>
+}
+
+pub fn verify_config_file(config_lines: &[String], syntax: ConfigSyntax) -> Result<bool, String> {
     let mut error = false;
     let mut signature_names_added = HashMap::new();
 
-    let mut line_number = 1;
     let mut last_empty_line_number = -1;
     let mut signature_name = "".to_string();
     let mut signature_lines = vec![];
+    let mut signature_start_line = 0;
+    let mut signature_values = vec![];
+
+    error |= validate_tabs(config_lines);
+    let config_lines = &Signature::replace_tabs(config_lines);
 
-    for line in config_lines {
+    error |= validate_continuation_lines(config_lines);
+    error |= validate_expectations(config_lines);
+
+    // in the old format every signature ends with an END marker, so lines without it are joined with the next line
+    let old_format = config_lines.iter().any(|line| !Signature::is_comment(line) && Signature::has_end_marker(line.trim()));
+
+    for (line_number, line) in Signature::join_continuation_lines(config_lines) {
+        let line_number = line_number as i32 + 1;
         let signature_text = line.trim();
 
-        if Signature::is_signature_min_length(signature_text) {
-            if Signature::is_signature_name(signature_text) {
+        if Signature::is_signature_min_length(signature_text, syntax) {
+            if Signature::is_signature_name(signature_text, syntax) {
                 error |= validate_signature_without_value(&signature_names_added, &signature_name);
                 error |= validate_signature_value_lines(&signature_name, &signature_lines);
+                error |= validate_end_marker(old_format, &signature_name, signature_start_line, &signature_lines);
+                add_signature_value_lines(&mut signature_values, &signature_name, signature_start_line, &signature_lines);
                 signature_lines.clear();
 
                 signature_name = signature_text.to_string();
@@ -34,15 +59,21 @@ pub fn verify_config_file(config_lines: &Vec<String>) -> Result<bool, String> {
 
                     if signature_text.eq_ignore_ascii_case("END") ||
                         signature_text.eq_ignore_ascii_case("AND") {
-                        eprintln!("Signature name cannot be a reserved word at line: {line_number}\r");
+                        log_error!("[E002] Signature name cannot be a reserved word at line: {line_number}\r");
                     } else {
-                        eprintln!("Signature found without a name: {signature_text}\r");
+                        log_error!("[E001] Signature found without a name: {signature_text}\r");
                     }
                 }
 
+                if signature_lines.is_empty() {
+                    signature_start_line = line_number;
+                }
+                error |= validate_end_marker_position(&signature_name, signature_text, line_number);
                 signature_lines.push(signature_text.to_string());
                 if Signature::has_end_marker(signature_text) {
-                    error |= validate_signature_value(&signature_name, &signature_lines.join(" "));
+                    let signature_value = signature_lines.join(" ");
+                    error |= validate_signature_value(&signature_name, &signature_value);
+                    add_signature_value(&mut signature_values, &signature_name, signature_start_line, &signature_value);
                     signature_lines.clear();
                 }
                 signature_names_added.insert(signature_name.to_ascii_uppercase(), true);
@@ -51,22 +82,24 @@ pub fn verify_config_file(config_lines: &Vec<String>) -> Result<bool, String> {
         } else {
             if signature_text.is_empty() && !line.is_empty() {
                 error = true;
-                eprintln!("Line found with only spaces\r");
+                log_error!("[W002] Line found with only spaces\r");
             }
 
             error |= validate_signature_without_value(&signature_names_added, &signature_name);
             error |= validate_signature_value_lines(&signature_name, &signature_lines);
+            error |= validate_end_marker(old_format, &signature_name, signature_start_line, &signature_lines);
+            add_signature_value_lines(&mut signature_values, &signature_name, signature_start_line, &signature_lines);
             signature_lines.clear();
 
             if !signature_text.is_empty() {
                 error = true;
-                eprintln!("Invalid signature found. Signature name should be at least 3 characters long and signature value line should have at least 2 valid characters: {signature_text}\r");
+                log_error!("[E003] Invalid signature found. Signature name should be at least 3 characters long and signature value line should have at least 2 valid characters: {signature_text}\r");
                 signature_names_added.insert(signature_name.to_ascii_uppercase(), true);
             }
 
             if line.is_empty() && last_empty_line_number == line_number - 1 {
                 error = true;
-                eprintln!("Two consecutive empty lines found at line: {line_number}\r");
+                log_error!("[W003] Two consecutive empty lines found at line: {line_number}\r");
             }
 
             if error {
@@ -77,16 +110,18 @@ pub fn verify_config_file(config_lines: &Vec<String>) -> Result<bool, String> {
 
             last_empty_line_number = line_number;
         }
-
-        line_number += 1;
     }
 
     error |= validate_signature_without_value(&signature_names_added, &signature_name);
     error |= validate_signature_value_lines(&signature_name, &signature_lines);
+    error |= validate_end_marker(old_format, &signature_name, signature_start_line, &signature_lines);
+    add_signature_value_lines(&mut signature_values, &signature_name, signature_start_line, &signature_lines);
+
+    report_duplicate_signatures(&signature_values);
     Ok(error)
 }
 
-pub fn verify_info_file(info_lines: &Vec<String>, signatures: &[SignatureConfig]) -> Result<bool, String> {
+pub fn verify_info_file(info_lines: &[String], signatures: &[SignatureConfig], info_tags: &[String], syntax: ConfigSyntax) -> Result<bool, String> {
     let mut error = false;
     let mut signature_names_added = HashMap::new();
 
@@ -97,12 +132,15 @@ pub fn verify_info_file(info_lines: &Vec<String>, signatures: &[SignatureConfig]
     let mut info_line_found = false;
     let mut signature_name_found = false;
 
+    error |= validate_tabs(info_lines);
+    let info_lines = &Signature::replace_info_tabs(info_lines);
+
     for line in info_lines {
         line_number += 1;
         let signature_text = line.trim_end();
         if signature_text.len() != line.len() {
             error = true;
-            eprintln!("Space(s) found at the end of the line on line: {line_number}\r");
+            log_error!("[W101] Space(s) found at the end of the line on line: {line_number}\r");
         }
 
         let signature_text = signature_text.trim();
@@ -110,34 +148,34 @@ pub fn verify_info_file(info_lines: &Vec<String>, signatures: &[SignatureConfig]
         if Signature::is_info_tag(line) {
             if !signature_name_found {
                 error = true;
-                eprintln!("Info found without a signature name at line: {line_number}\r");
+                log_error!("[E101] Info found without a signature name at line: {line_number}\r");
                 previous_tag = "".to_string();
             }
 
             let tag = line.chars().take(10).collect::<String>();
             let tag = tag.trim();
-            error |= validate_info_tag(&signature_name, tag, &previous_tag);
+            error |= validate_info_tag(&signature_name, tag, &previous_tag, info_tags);
 
             let value = &line.chars().skip(11).collect::<String>();
-            error |= validate_info_tag_value(&signature_name, tag, value);
+            error |= validate_info_tag_value(&signature_name, tag, value, line_number);
 
             if !tag.is_empty() {
                 previous_tag = tag.to_string();
             }
 
             info_line_found = true;
-        } else if Signature::is_signature_name(signature_text) {
-            error |= validate_signature_exists_in_config(signatures, signature_text);
-
-            if signature_name_found && !info_line_found {
+        } else if Signature::is_signature_name(signature_text, syntax) {
+            if let Some(position) = find_misindented_tag(signature_text) {
                 error = true;
-                eprintln!("Signature name found without any info: {signature_name}\r");
+                log_error!("[E103] Wrong indentation '{}' or invalid tag in: {}\r", &signature_text[..=position], signature_name);
+                continue;
             }
 
-            if let Some(position) = signature_text.find(':') {
+            error |= validate_signature_exists_in_config(signatures, signature_text);
+
+            if signature_name_found && !info_line_found {
                 error = true;
-                eprintln!("Wrong indentation '{}' or invalid tag in: {}\r", &signature_text[..=position], signature_name);
-                continue;
+                log_error!("[E102] Signature name found without any info: {signature_name}\r");
             }
 
             error |= validate_signature_name(signature_text, &signature_names_added);
@@ -151,12 +189,12 @@ pub fn verify_info_file(info_lines: &Vec<String>, signatures: &[SignatureConfig]
         } else {
             if signature_name_found && !info_line_found {
                 error = true;
-                eprintln!("Signature name found without any info: {signature_name}\r");
+                log_error!("[E102] Signature name found without any info: {signature_name}\r");
             }
 
             if line.is_empty() && last_empty_line_number == line_number - 1 {
                 error = true;
-                eprintln!("Two consecutive empty lines found at line: {line_number}\r");
+                log_error!("[W102] Two consecutive empty lines found at line: {line_number}\r");
             }
             last_empty_line_number = line_number;
 
@@ -168,12 +206,168 @@ pub fn verify_info_file(info_lines: &Vec<String>, signatures: &[SignatureConfig]
     Ok(error)
 }
 
+/// Counts the signatures with sub signatures that are only found when they occur in order, i.e. signatures with AND
+/// operators that don't use the ANY{N} combinator.
+pub fn count_ordered_signatures(signatures: &[SignatureConfig]) -> usize {
+    signatures.iter()
+        .filter(|signature| signature.bndm_configs.len() > 1 && signature.any_threshold.is_none() && !signature.any_order)
+        .count()
+}
+
+fn validate_tabs(lines: &[String]) -> bool {
+    let mut error = false;
+    for (line_number, line) in (1..).zip(lines) {
+        if line.contains('\t') {
+            error = true;
+            log_error!("[W001] Tab character found at line: {line_number}, use spaces instead\r");
+        }
+    }
+    error
+}
+
+fn validate_continuation_lines(config_lines: &[String]) -> bool {
+    let mut error = false;
+    for (line_number, line) in (1..).zip(config_lines) {
+        if !Signature::is_comment(line) && Signature::has_continuation_marker(line) && config_lines.get(line_number).is_none_or(|next_line| next_line.trim().is_empty()) {
+            error = true;
+            log_error!("[E004] Line continuation found without a following signature value line at line: {line_number}\r");
+        }
+    }
+    error
+}
+
+/// Reports the lines of a signature that were read without an END marker in a file in the old format, where they are
+/// read as separate signatures instead of as one.
+fn validate_end_marker(old_format: bool, signature_name: &str, start_line: i32, signature_lines: &[String]) -> bool {
+    if old_format && !signature_name.is_empty() && !signature_lines.is_empty() {
+        log_error!("[E026] END marker missing in signature starting at line: {start_line} in: {signature_name}\r");
+        return true;
+    }
+    false
+}
+
+/// Returns the position of the colon when the line starts with a tag that is not at the position of a tag, like a tag
+/// word followed by a colon and a space. A colon elsewhere, like in Player:V2, is part of a signature name.
+fn find_misindented_tag(signature_text: &str) -> Option<usize> {
+    let position = signature_text.find(':')?;
+    let is_tag = !signature_text[..position].is_empty() && signature_text[..position].bytes().all(|character| character.is_ascii_alphabetic());
+    let ends_tag = signature_text[position + 1..].is_empty() || signature_text[position + 1..].starts_with(' ');
+    (is_tag && ends_tag).then_some(position)
+}
+
+fn validate_end_marker_position(signature_name: &str, signature_text: &str, line_number: i32) -> bool {
+    let words = signature_text.split_ascii_whitespace().collect::<Vec<&str>>();
+    if words.iter().rev().skip(1).any(|word| word.eq_ignore_ascii_case("END")) {
+        log_error!("[E017] Signature END operator can only be present at the end of the line at line: {line_number} in: {signature_name}\r");
+        return true;
+    }
+    false
+}
+
+fn validate_expectations(config_lines: &[String]) -> bool {
+    let mut error = false;
+    for (line_number, line) in (1..).zip(config_lines) {
+        if Signature::is_expectation(line) && Signature::parse_expectation(line, line_number).is_none() {
+            error = true;
+            log_error!("[E025] Invalid EXPECT annotation at line: {line_number}, use: ; EXPECT name count directory\r");
+        }
+    }
+    error
+}
+
+fn add_signature_value_lines(signature_values: &mut Vec<SignatureValue>, signature_name: &str, start_line: i32, signature_lines: &[String]) {
+    for (index, signature_line) in signature_lines.iter().enumerate() {
+        add_signature_value(signature_values, signature_name, start_line + index as i32, signature_line);
+    }
+}
+
+fn add_signature_value(signature_values: &mut Vec<SignatureValue>, signature_name: &str, line_number: i32, signature_text: &str) {
+    if signature_name.is_empty() {
+        return;
+    }
+
+    let mut segments = vec![vec![]];
+    for word in signature_text.to_ascii_uppercase().split_ascii_whitespace() {
+        match word {
+            _ if Signature::is_and_operator(word) => segments.push(vec![]),
+            "END" => {},
+            _ => segments.last_mut().unwrap().push(word.to_string())
+        }
+    }
+
+    signature_values.push(SignatureValue { line_number, signature_name: signature_name.to_string(), segments });
+}
+
+/// Reports signature values that are identical to or always match together with another one. These are warnings that
+/// don't fail the verification, as such a signature still identifies the player. The signatures are compared in
+/// parallel and the warnings are written in the order of their line numbers.
+fn report_duplicate_signatures(signature_values: &[SignatureValue]) {
+    let mut issues: Vec<(i32, i32, String)> = signature_values.par_iter()
+        .enumerate()
+        .flat_map_iter(|(index, signature)| {
+            signature_values[index + 1..].iter().filter_map(move |other_signature| compare_signature_values(signature, other_signature))
+        })
+        .collect();
+
+    issues.sort();
+
+    for (_, _, message) in &issues {
+        log_error!("{message}\r");
+    }
+}
+
+fn compare_signature_values(signature: &SignatureValue, other_signature: &SignatureValue) -> Option<(i32, i32, String)> {
+    let first_line = signature.line_number.min(other_signature.line_number);
+    let last_line = signature.line_number.max(other_signature.line_number);
+
+    if signature.segments == other_signature.segments {
+        let message = if signature.signature_name.eq(&other_signature.signature_name) {
+            format!("[W008] Signature value defined more than once at line {first_line} and {last_line}: {}", signature.signature_name)
+        } else {
+            format!("[W009] Signature value at line {first_line} is identical to the one at line {last_line}: {} and {}", signature.signature_name, other_signature.signature_name)
+        };
+        return Some((first_line, last_line, message));
+    }
+
+    if is_sub_signature(&signature.signature_name) || is_sub_signature(&other_signature.signature_name) {
+        return None;
+    }
+
+    let (covering, covered) = if is_signature_covered_by(signature, other_signature) {
+        (other_signature, signature)
+    } else if is_signature_covered_by(other_signature, signature) {
+        (signature, other_signature)
+    } else {
+        return None;
+    };
+
+    Some((first_line, last_line, format!("[W010] Signature value at line {} always matches when the one at line {} matches: {} and {}",
+        covering.line_number, covered.line_number, covering.signature_name, covered.signature_name)))
+}
+
+fn is_sub_signature(signature_name: &str) -> bool {
+    signature_name.starts_with('(')
+}
+
+fn is_signature_covered_by(signature: &SignatureValue, covering_signature: &SignatureValue) -> bool {
+    if signature.segments.len() != 1 || covering_signature.segments.len() != 1 {
+        return false;
+    }
+
+    let words = &signature.segments[0];
+    let covering_words = &covering_signature.segments[0];
+
+    !covering_words.is_empty() && covering_words.len() < words.len() && words.windows(covering_words.len()).any(|window| {
+        window.iter().zip(covering_words).all(|(word, covering_word)| covering_word == "??" || word == covering_word)
+    })
+}
+
 fn validate_signature_exists_in_config(signatures: &[SignatureConfig], signature_name: &str) -> bool {
     let mut error = false;
 
-    if !signatures.iter().any(|signature| signature.signature_name.eq(signature_name)) {
+    if !signatures.iter().any(|signature| signature.signature_name.as_ref().eq(signature_name)) {
         error = true;
-        eprintln!("Signature ID not found in config file: {signature_name}\r");
+        log_error!("[E104] Signature ID not found in config file: {signature_name}\r");
     }
     error
 }
@@ -183,12 +377,12 @@ fn validate_signature_name(signature_name: &str, signature_names_added: &HashMap
 
     if signature_name.contains(' ') {
         error = true;
-        eprintln!("Signature name contains spaces or invalid signature value: {signature_name}\r");
+        log_error!("[E005] Signature name contains spaces or invalid signature value: {signature_name}\r");
     }
 
     if signature_names_added.contains_key(&signature_name.to_ascii_uppercase()) {
         error = true;
-        eprintln!("Signature defined more than once or with different casing: {signature_name}\r");
+        log_error!("[E006] Signature defined more than once or with different casing: {signature_name}\r");
     }
     error
 }
@@ -207,7 +401,7 @@ fn validate_signature_without_value(signature_names_added: &HashMap<String, bool
         let has_signature_value = signature_names_added.get(&signature_name.to_ascii_uppercase());
         if !has_signature_value.unwrap() {
             error = true;
-            eprintln!("Signature name found without a value: {signature_name}\r");
+            log_error!("[E007] Signature name found without a value: {signature_name}\r");
         }
     }
     error
@@ -217,10 +411,10 @@ fn validate_spaces(signature_name: &str, signature_value: &str, line_length: usi
     let mut error = false;
     if line_length != signature_size {
         error = true;
-        eprintln!("Signature contains spaces at beginning or at the end of the line: {signature_name}\r");
+        log_error!("[W004] Signature contains spaces at beginning or at the end of the line: {signature_name}\r");
     } else if signature_value.contains("  ") {
         error = true;
-        eprintln!("Signature contains double spaces: {signature_name}\r");
+        log_error!("[W005] Signature contains double spaces: {signature_name}\r");
     }
     error
 }
@@ -232,23 +426,94 @@ fn validate_signature_value(signature_name: &str, signature_text: &str) -> bool
 
     if signature_text.ne(&signature_text_upper) {
         error = true;
-        eprintln!("Signature contains lowercase characters: {signature_name}\r");
+        log_error!("[W006] Signature contains lowercase characters: {signature_name}\r");
     }
 
     let signature_text_without_end = signature_text.replace(" END", "");
     if signature_text_without_end.len() <= 4 {
         error = true;
-        eprintln!("Invalid signature found. Signature value should have at least 2 values separated with a space: {signature_name}\r");
+        log_error!("[E008] Invalid signature found. Signature value should have at least 2 values separated with a space: {signature_name}\r");
+    }
+
+    let words = signature_text_upper.split_ascii_whitespace().collect::<Vec<&str>>();
+    let words_without_end = words.strip_suffix(&["END"]).unwrap_or(&words);
+
+    if words.first().is_some_and(|word| Signature::is_and_operator(word)) {
+        error = true;
+        log_error!("[E009] Signature should not begin with an AND or && operator: {signature_name}\r");
+    }
+
+    if words_without_end.len() > 1 && words_without_end.last().is_some_and(|word| Signature::is_and_operator(word)) {
+        error = true;
+        log_error!("[E010] Signature should not end with an AND or && operator: {signature_name}\r");
+    }
+
+    if words.windows(2).any(|pair| pair.iter().all(|word| Signature::is_and_operator(word))) {
+        error = true;
+        log_error!("[E011] Signature should not contain consecutive AND or && operators: {signature_name}\r");
+    }
+
+    let sub_signatures = words.split(|word| Signature::is_and_operator(word)).collect::<Vec<&[&str]>>();
+    for sub_signature in &sub_signatures {
+        error |= validate_signature_range(signature_name, &sub_signature.join(" "));
+    }
+
+    error |= validate_any_threshold(signature_name, &words, sub_signatures.len());
+    error |= validate_entry_clause(signature_name, &words, sub_signatures.len());
+    error |= validate_signature_compiles(signature_name, signature_text);
+    error
+}
+
+fn validate_entry_clause(signature_name: &str, words: &[&str], sub_signature_count: usize) -> bool {
+    if words.contains(&"ENTRY") && (sub_signature_count > 1 || words.iter().any(|word| *word == "??" || word.starts_with("ANY{"))) {
+        log_error!("[E024] ENTRY clause can't be combined with wildcards, AND operators or the ANY combinator: {signature_name}\r");
+        return true;
+    }
+    false
+}
+
+fn validate_signature_compiles(signature_name: &str, signature_text: &str) -> bool {
+    let signature = Signature::process_signature_value(signature_name, signature_text);
+    if signature.bndm_configs.is_empty() {
+        log_error!("[E012] Signature does not contain any byte sequence to search for: {signature_name}\r");
+        return true;
+    }
+
+    let sub_signature_count = signature.bndm_configs.len();
+    let wildcard_sub_signatures = (1..=sub_signature_count)
+        .filter(|&position| is_wildcard_only(&signature.bndm_configs[position - 1].pattern, signature.bndm_configs[position - 1].wildcard, usize::MAX))
+        .collect::<Vec<usize>>();
+    for position in &wildcard_sub_signatures {
+        log_error!("[E027] Sub signature {position} of {sub_signature_count} consists only of wildcards, which matches anything of its length: {signature_name}\r");
+    }
+    if !wildcard_sub_signatures.is_empty() {
+        return true;
+    }
+
+    if signature.bndm_configs.iter().any(|config| is_wildcard_only(&config.pattern, config.wildcard, MATCHER_WINDOW_SIZE)) {
+        // the leading wildcards are already reported as E016, so the warning doesn't fail the verification by itself
+        log_error!("[W007] Signature has only wildcards in the first {MATCHER_WINDOW_SIZE} bytes of a sub signature, which matches almost anywhere: {signature_name}\r");
     }
+    false
+}
+
+fn is_wildcard_only(pattern: &[u8], wildcard: Option<u8>, length: usize) -> bool {
+    wildcard.is_some_and(|wildcard| pattern.iter().take(length).all(|&value| value == wildcard))
+}
 
-    if signature_text_without_end.ends_with(" AND") || signature_text_without_end.ends_with(" &&") {
+fn validate_any_threshold(signature_name: &str, words: &[&str], sub_signature_count: usize) -> bool {
+    let mut error = false;
+
+    let any_combinators = words.iter().filter(|word| word.starts_with("ANY{")).collect::<Vec<&&str>>();
+    if any_combinators.len() > 1 {
         error = true;
-        eprintln!("Signature should not end with an AND or && operator: {signature_name}\r");
+        log_error!("[E013] ANY combinator can only be used once in signature: {signature_name}\r");
     }
 
-    for signature in signature_text_upper.split(" AND ") {
-        for signature in signature.split(" && ") {
-            error |= validate_signature_range(signature_name, signature);
+    if let Some(any_threshold) = any_combinators.first().and_then(|word| Signature::parse_any_threshold(word)) {
+        if any_threshold > sub_signature_count {
+            error = true;
+            log_error!("[E014] ANY combinator requires {any_threshold} sub signatures but only {sub_signature_count} are defined in signature: {signature_name}\r");
         }
     }
     error
@@ -260,32 +525,34 @@ fn validate_signature_range(signature_name: &str, signature_text: &str) -> bool
     while let Some((index, word)) = it.next() {
         if index == 255 {
             error = true;
-            eprintln!("Signature cannot be larger than 254 bytes: {signature_name}\r");
+            log_error!("[E015] Signature cannot be larger than 254 bytes: {signature_name}\r");
         }
         match word {
             "??" => {
                 if index == 0 || it.peek().is_none() || it.peek().unwrap().1.eq_ignore_ascii_case("END") {
                     error = true;
-                    eprintln!("Signature ID or SUB ID (with AND operator) should not begin or end with a wildcard: {signature_name}\r");
+                    log_error!("[E016] Signature ID or SUB ID (with AND operator) should not begin or end with a wildcard: {signature_name}\r");
                 }
             },
-            "END" => {
-                if it.peek().is_some() {
+            "END" => {},
+            _ if word.starts_with("ANY{") => {
+                if Signature::parse_any_threshold(word).unwrap_or(0) == 0 {
                     error = true;
-                    eprintln!("Signature END operator can only be present at the end of the line: {signature_name}\r");
+                    log_error!("[E018] Invalid ANY combinator '{word}' in signature: {signature_name}\r");
                 }
             },
-            "AND" | "&&" => {
-                if index == 0 {
+            "OVERLAP" | "ENTRY" => {},
+            _ if word.starts_with("MINSIZE=") => {
+                if Signature::parse_min_size(word).unwrap_or(0) == 0 {
                     error = true;
-                    eprintln!("Signature should not begin with an AND or && operator: {signature_name}\r");
+                    log_error!("[E019] Invalid minimum size '{word}' in signature: {signature_name}\r");
                 }
             },
             _ => {
                 let valid_chars = word.bytes().all(|b| b.is_ascii_hexdigit());
                 if !valid_chars || (!word.is_empty() && word.len() != 2) {
                     error = true;
-                    eprintln!("Unsupported value '{word}' in signature: {signature_name}\r");
+                    log_error!("[E020] Unsupported value '{word}' in signature: {signature_name}\r");
                 }
             }
         }
@@ -293,49 +560,68 @@ fn validate_signature_range(signature_name: &str, signature_text: &str) -> bool
     error
 }
 
-fn validate_info_tag_value(signature_name: &str, tag: &str, value: &str) -> bool {
+fn validate_info_tag_value(signature_name: &str, tag: &str, value: &str, line_number: i32) -> bool {
     let mut error = false;
 
     if let Some(first_char) = value.chars().next() {
         if first_char.is_ascii_whitespace() {
             error = true;
-            eprintln!("Value in '{}' is not correctly aligned in: {}\r", tag.trim(), signature_name);
+            log_error!("[E108] Value in '{}' is not correctly aligned in: {}\r", tag.trim(), signature_name);
         }
     }
 
-    if tag.eq_ignore_ascii_case("REFERENCE:") && !value.trim().to_ascii_uppercase().starts_with("HTTP") {
-        error = true;
-        eprintln!("Reference has an invalid URL in signature: {signature_name}\r");
+    if tag.eq_ignore_ascii_case("REFERENCE:") {
+        if let Err(reason) = validate_url(value.trim()) {
+            error = true;
+            log_error!("[E109] Reference has an invalid URL ({reason}) at line: {line_number} in signature: {signature_name}\r");
+        }
     }
     error
 }
 
-fn validate_info_tag(signature_name: &str, tag: &str, previous_tag: &str) -> bool {
-    match tag {
-        "" | "AUTHOR:" | "RELEASED:" | "NAME:" | "REFERENCE:" | "COMMENT:" => {
-            validate_order(signature_name, tag, previous_tag)
-        },
-        _ => {
-            eprintln!("Invalid tag found '{tag}' in signature: {signature_name}\r");
-            true
-        }
+/// Checks that the reference is a complete http or https URL, without looking it up.
+fn validate_url(reference: &str) -> Result<(), String> {
+    if reference.contains(char::is_whitespace) {
+        return Err("contains spaces".to_string());
+    }
+
+    let url = Url::parse(reference).map_err(|error| format!("{error}"))?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(format!("unsupported scheme '{}'", url.scheme()));
     }
+    // the parser accepts a missing or extra slash after the scheme of http URLs, which is a copy-paste error in a reference
+    let authority = reference.get(url.scheme().len()..).and_then(|rest| rest.strip_prefix("://"));
+    if authority.is_none_or(|authority| authority.is_empty() || authority.starts_with('/')) || url.host_str().is_none_or(str::is_empty) {
+        return Err("missing host".to_string());
+    }
+    Ok(())
 }
 
-fn validate_order(signature_name: &str, tag: &str, previous_tag: &str) -> bool {
+fn validate_info_tag(signature_name: &str, tag: &str, previous_tag: &str, info_tags: &[String]) -> bool {
+    if tag.is_empty() || get_order(tag, info_tags) > 0 {
+        validate_order(signature_name, tag, previous_tag, info_tags)
+    } else {
+        log_error!("[E105] Invalid tag found '{tag}' in signature: {signature_name}\r");
+        true
+    }
+}
+
+/// Checks that the tag comes after the previous tag in the order of the info tags. A line without a tag continues the
+/// previous tag, which is only allowed for the COMMENT tag, also when a custom list of info tags is used.
+fn validate_order(signature_name: &str, tag: &str, previous_tag: &str, info_tags: &[String]) -> bool {
     if !previous_tag.is_empty() {
-        let tag_order = get_order(tag);
-        let previous_tag_order = get_order(previous_tag);
+        let tag_order = get_order(tag, info_tags);
+        let previous_tag_order = get_order(previous_tag, info_tags);
 
         let mut error = tag_order <= previous_tag_order;
         if error {
-            eprintln!("Order of tags '{tag}' '{previous_tag}' is not valid: {signature_name}\r");
+            log_error!("[E106] Order of tags '{tag}' '{previous_tag}' is not valid: {signature_name}\r");
         }
 
-        let multi_line_detected_on_non_comment = tag_order == 6 && previous_tag_order < 5;
+        let multi_line_detected_on_non_comment = tag.is_empty() && previous_tag.ne("COMMENT:");
         if multi_line_detected_on_non_comment {
             error = true;
-            eprintln!("Multi-line not allowed for tag '{previous_tag}' in: {signature_name}\r");
+            log_error!("[E107] Multi-line not allowed for tag '{previous_tag}' in: {signature_name}\r");
         }
         error
     } else {
@@ -343,14 +629,17 @@ fn validate_order(signature_name: &str, tag: &str, previous_tag: &str) -> bool {
     }
 }
 
-fn get_order(tag: &str) -> i32 {
-    match tag.trim() {
-        "NAME:" => 1,
-        "AUTHOR:" => 2,
-        "RELEASED:" => 3,
-        "REFERENCE:" => 4,
-        "COMMENT:" => 5,
-        "" => 6,
-        _ => 0
+fn get_order(tag: &str, info_tags: &[String]) -> usize {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return info_tags.len() + 1;
     }
+
+    tag.strip_suffix(':')
+        .and_then(|tag_name| info_tags.iter().position(|info_tag| info_tag.eq(tag_name)))
+        .map_or(0, |position| position + 1)
 }
+
+#[cfg(test)]
+#[path = "./validate_test.rs"]
+mod validate_test;
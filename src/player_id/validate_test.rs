@@ -0,0 +1,278 @@
+use super::*;
+
+fn to_lines(text: &str) -> Vec<String> {
+    text.lines().map(|line| line.to_string()).collect()
+}
+
+fn to_tags(tags: &[&str]) -> Vec<String> {
+    tags.iter().map(|tag| tag.to_string()).collect()
+}
+
+fn load_signatures(config: &str) -> Vec<SignatureConfig> {
+    Signature::read_config_lines(&to_lines(config), None, ConfigSyntax::default()).unwrap()
+}
+
+#[test]
+fn verify_info_file_default_tags() {
+    let signatures = load_signatures("Test_Player\nA9 00 8D 00 D4");
+    let info_lines = to_lines("Test_Player\n     NAME: Test Player\n  COMMENT: First line\n           Second line");
+
+    let issues_found = verify_info_file(&info_lines, &signatures, &to_tags(&["NAME", "AUTHOR", "RELEASED", "REFERENCE", "COMMENT"]), ConfigSyntax::default()).unwrap();
+
+    assert!(!issues_found);
+}
+
+#[test]
+fn verify_info_file_custom_tags() {
+    let signatures = load_signatures("Test_Player\nA9 00 8D 00 D4");
+    let info_lines = to_lines("Test_Player\n     NAME: Test Player\n  VERSION: 1.2\n   SOURCE: Disk image\n  COMMENT: Some comment");
+
+    let issues_found = verify_info_file(&info_lines, &signatures, &to_tags(&["NAME", "VERSION", "SOURCE", "COMMENT"]), ConfigSyntax::default()).unwrap();
+
+    assert!(!issues_found);
+}
+
+#[test]
+fn verify_info_file_custom_tag_not_allowed_by_default() {
+    let signatures = load_signatures("Test_Player\nA9 00 8D 00 D4");
+    let info_lines = to_lines("Test_Player\n     NAME: Test Player\n  VERSION: 1.2");
+
+    let issues_found = verify_info_file(&info_lines, &signatures, &to_tags(&["NAME", "AUTHOR", "RELEASED", "REFERENCE", "COMMENT"]), ConfigSyntax::default()).unwrap();
+
+    assert!(issues_found);
+}
+
+#[test]
+fn verify_info_file_custom_tags_wrong_order() {
+    let signatures = load_signatures("Test_Player\nA9 00 8D 00 D4");
+    let info_lines = to_lines("Test_Player\n   SOURCE: Disk image\n  VERSION: 1.2");
+
+    let issues_found = verify_info_file(&info_lines, &signatures, &to_tags(&["NAME", "VERSION", "SOURCE", "COMMENT"]), ConfigSyntax::default()).unwrap();
+
+    assert!(issues_found);
+}
+
+#[test]
+fn verify_info_file_custom_tags_multi_line_only_for_comment() {
+    let signatures = load_signatures("Test_Player\nA9 00 8D 00 D4");
+    let info_tags = to_tags(&["NAME", "SOURCE", "COMMENT"]);
+    let verify = |info: &str| crate::log::buffered(|| verify_info_file(&to_lines(info), &signatures, &info_tags, ConfigSyntax::default()).unwrap());
+
+    assert!(!verify("Test_Player\n     NAME: Test Player\n  COMMENT: Line one\n           line two").0);
+
+    let (issues_found, messages) = verify("Test_Player\n     NAME: Test Player\n   SOURCE: Disk one\n           disk two");
+    assert!(issues_found);
+    assert!(messages.iter().any(|message| message.starts_with("[E107] Multi-line not allowed for tag 'SOURCE:'")));
+}
+
+#[test]
+fn verify_config_file_any_threshold() {
+    assert!(!verify_config_file(&to_lines("Test_Player\nA9 00 8D && 8D 18 D4 && A2 00 BD ANY{2}"), ConfigSyntax::default()).unwrap());
+    assert!(verify_config_file(&to_lines("Test_Player\nA9 00 8D && 8D 18 D4 && A2 00 BD ANY{4}"), ConfigSyntax::default()).unwrap());
+    assert!(verify_config_file(&to_lines("Test_Player\nA9 00 8D && 8D 18 D4 && A2 00 BD ANY{0}"), ConfigSyntax::default()).unwrap());
+    assert!(verify_config_file(&to_lines("Test_Player\nA9 00 8D && ANY{2} 8D 18 D4 ANY{1}"), ConfigSyntax::default()).unwrap());
+}
+
+#[test]
+fn verify_config_file_mixed_and_operators() {
+    assert!(!verify_config_file(&to_lines("Test_Player\nA9 00 8D && 8D 18 D4 AND A2 00 BD"), ConfigSyntax::default()).unwrap());
+    assert!(!verify_config_file(&to_lines("Test_Player\nA9 00 8D AND 8D 18 D4 && A2 00 BD END"), ConfigSyntax::default()).unwrap());
+    assert!(verify_config_file(&to_lines("Test_Player\nA9 00 8D && AND 8D 18 D4"), ConfigSyntax::default()).unwrap());
+    assert!(verify_config_file(&to_lines("Test_Player\nA9 00 8D AND && 8D 18 D4"), ConfigSyntax::default()).unwrap());
+    assert!(verify_config_file(&to_lines("Test_Player\nA9 00 8D AND 8D 18 D4 && END"), ConfigSyntax::default()).unwrap());
+    assert!(verify_config_file(&to_lines("Test_Player\nA9 00 8D AND 8D 18 D4 &&\nEND"), ConfigSyntax::default()).unwrap());
+}
+
+#[test]
+fn verify_config_file_continuation_marker() {
+    assert!(!verify_config_file(&to_lines("Test_Player\nA9 00 8D \\\n8D 18 D4 && \\\nA2 00 BD\n\nOther_Player\nA2 00 9D"), ConfigSyntax::default()).unwrap());
+    assert!(verify_config_file(&to_lines("Test_Player\nA9 00 8D \\\n\nOther_Player\nA2 00 9D"), ConfigSyntax::default()).unwrap());
+    assert!(verify_config_file(&to_lines("Test_Player\nA9 00 8D \\"), ConfigSyntax::default()).unwrap());
+}
+
+#[test]
+fn verify_config_file_with_tabs() {
+    assert!(!verify_config_file(&to_lines("Test_Player\nA9 00 8D 18 D4"), ConfigSyntax::default()).unwrap());
+    assert!(verify_config_file(&to_lines("Test_Player\nA9\t00 8D 18 D4"), ConfigSyntax::default()).unwrap());
+    assert!(verify_config_file(&to_lines("Test_Player\t\nA9 00 8D 18 D4"), ConfigSyntax::default()).unwrap());
+}
+
+#[test]
+fn verify_info_file_with_tabs() {
+    let signatures = load_signatures("Test_Player\nA9 00 8D 18 D4");
+    let info_tags = to_tags(&["NAME", "COMMENT"]);
+
+    assert!(!verify_info_file(&to_lines("Test_Player\n     NAME: Test Player"), &signatures, &info_tags, ConfigSyntax::default()).unwrap());
+    assert!(verify_info_file(&to_lines("Test_Player\n     NAME:\tTest Player"), &signatures, &info_tags, ConfigSyntax::default()).unwrap());
+
+    let (_, messages) = crate::log::buffered(|| verify_info_file(&to_lines("Test_Player\n\tNAME:\tTest Player\n\tCOMMENT:\tLine one\n\t\tline two"), &signatures, &info_tags, ConfigSyntax::default()));
+    assert_eq!(messages.iter().map(|message| &message[..6]).collect::<Vec<&str>>(), vec!["[W001]", "[W001]", "[W001]"]);
+}
+
+#[test]
+fn verify_config_file_signature_without_byte_sequence() {
+    assert!(!verify_config_file(&to_lines("Test_Player\nA9 00 8D 18 D4 MINSIZE=1000"), ConfigSyntax::default()).unwrap());
+    assert!(verify_config_file(&to_lines("Test_Player\nMINSIZE=1000"), ConfigSyntax::default()).unwrap());
+    assert!(verify_config_file(&to_lines("Test_Player\nMINSIZE=1000 ANY{1} END"), ConfigSyntax::default()).unwrap());
+}
+
+#[test]
+fn verify_config_file_overlap_clause() {
+    assert!(!verify_config_file(&to_lines("Test_Player\nA9 00 8D && 8D 18 D4 OVERLAP"), ConfigSyntax::default()).unwrap());
+    assert!(verify_config_file(&to_lines("Test_Player\nA9 00 8D && 8D 18 D4 OVERLAPS"), ConfigSyntax::default()).unwrap());
+}
+
+#[test]
+fn count_ordered_signatures_with_and_operators() {
+    let mut signatures = load_signatures("First\nA9 00 8D && 8D 18 D4\nSecond\nA9 00 8D 18 D4\nThird\nA9 00 8D && 8D 18 D4 ANY{1}");
+    assert_eq!(count_ordered_signatures(&signatures), 1);
+
+    signatures[0].any_order = true;
+    assert_eq!(count_ordered_signatures(&signatures), 0);
+}
+
+#[test]
+fn verify_config_file_with_raised_min_name_length() {
+    let config_lines = to_lines("Test\nA9 00 8D 18 D4");
+    let syntax = ConfigSyntax { min_name_length: 5, ..ConfigSyntax::default() };
+
+    assert!(!verify_config_file(&config_lines, ConfigSyntax::default()).unwrap());
+    assert!(verify_config_file(&config_lines, syntax).unwrap());
+}
+
+#[test]
+fn verify_config_file_diagnostic_codes() {
+    let get_codes = |config: &str| crate::log::buffered(|| verify_config_file(&to_lines(config), ConfigSyntax::default()).unwrap()).1.iter()
+        .map(|message| message[1..5].to_string())
+        .collect::<Vec<String>>();
+
+    assert_eq!(get_codes("A9 00 8D 18 D4"), vec!["E001"]);
+    assert_eq!(get_codes("Test_Player\nA9 00  8D 18 D4"), vec!["W005"]);
+    assert_eq!(get_codes("Test_Player\na9 00 8D 18 D4"), vec!["W006"]);
+    assert_eq!(get_codes("Test_Player\n?? 00 8D 18 D4"), vec!["E016"]);
+    assert_eq!(get_codes("Test_Player\nA9 00 8D 18 D4\nSecond_Player\nA9 00 8D 18 D4"), vec!["W009"]);
+}
+
+#[test]
+fn verify_info_file_diagnostic_codes() {
+    let signatures = load_signatures("Test_Player\nA9 00 8D 00 D4");
+    let info_tags = to_tags(&["NAME", "AUTHOR", "RELEASED", "REFERENCE", "COMMENT"]);
+
+    let (_, messages) = crate::log::buffered(|| verify_info_file(&to_lines("Other_Player\n     NAME: Other Player"), &signatures, &info_tags, ConfigSyntax::default()).unwrap());
+
+    assert!(messages[0].starts_with("[E104] "));
+}
+
+#[test]
+fn verify_config_file_entry_clause() {
+    assert!(!verify_config_file(&to_lines("Test_Player\nA9 8D A2 BD 60 ENTRY"), ConfigSyntax::default()).unwrap());
+    assert!(!verify_config_file(&to_lines("Test_Player\nA9 8D A2 BD 60 ENTRY END"), ConfigSyntax::default()).unwrap());
+    assert!(verify_config_file(&to_lines("Test_Player\nA9 8D ?? BD 60 ENTRY"), ConfigSyntax::default()).unwrap());
+    assert!(verify_config_file(&to_lines("Test_Player\nA9 8D && A2 BD 60 ENTRY"), ConfigSyntax::default()).unwrap());
+}
+
+#[test]
+fn verify_info_file_reference_urls() {
+    let signatures = load_signatures("Test_Player\nA9 00 8D 00 D4");
+    let info_tags = to_tags(&["NAME", "AUTHOR", "RELEASED", "REFERENCE", "COMMENT"]);
+    let verify_reference = |reference: &str| {
+        let info_lines = to_lines(&format!("Test_Player\n     NAME: Test Player\nREFERENCE: {reference}"));
+        crate::log::buffered(|| verify_info_file(&info_lines, &signatures, &info_tags, ConfigSyntax::default()).unwrap())
+    };
+
+    assert!(!verify_reference("https://csdb.dk/release/?id=10741").0);
+    assert!(!verify_reference("http://www.example.com/players/test.html").0);
+
+    for reference in ["https://csdb.dk/release/?id= 10741", "https://", "http:/csdb.dk", "https:///release/?id=10741", "ftp://csdb.dk/release", "csdb.dk/release/?id=10741", "http//csdb.dk"] {
+        let (issues_found, messages) = verify_reference(reference);
+        assert!(issues_found, "{reference}");
+        assert!(messages[0].starts_with("[E109] ") && messages[0].contains("at line: 3"), "{reference}: {}", messages[0]);
+    }
+}
+
+#[test]
+fn verify_config_file_wildcard_window() {
+    let get_codes = |signature_value: String| crate::log::buffered(|| verify_config_file(&to_lines(&format!("Test_Player\n{signature_value}")), ConfigSyntax::default()).unwrap()).1.iter()
+        .map(|message| message[1..5].to_string())
+        .collect::<Vec<String>>();
+    let wildcards = |count: usize| vec!["??"; count].join(" ");
+
+    // a leading wildcard is already an error, the warning tells that the sub signature matches almost anywhere
+    assert_eq!(get_codes(format!("A9 00 8D && {} 8D 18 D4", wildcards(MATCHER_WINDOW_SIZE))), vec!["E016", "W007"]);
+    assert_eq!(get_codes(format!("{} A9 00 8D", wildcards(MATCHER_WINDOW_SIZE + 2))), vec!["E016", "W007"]);
+    assert_eq!(get_codes(format!("A9 00 8D && {} 8D 18 D4", wildcards(MATCHER_WINDOW_SIZE - 1))), vec!["E016"]);
+
+    let (error, messages) = crate::log::buffered(|| validate_signature_compiles("Test_Player", &format!("A9 00 8D && {} 8D 18 D4", wildcards(MATCHER_WINDOW_SIZE))));
+    assert!(!error);
+    assert!(messages[0].starts_with("[W007]"));
+}
+
+#[test]
+fn verify_config_file_wildcard_only_sub_signature() {
+    let get_messages = |config: &str| crate::log::buffered(|| verify_config_file(&to_lines(config), ConfigSyntax::default()).unwrap()).1;
+
+    let messages = get_messages("Test_Player\nA9 00 8D && ?? ?? ?? && 8D 18 D4");
+    assert!(messages.contains(&"[E027] Sub signature 2 of 3 consists only of wildcards, which matches anything of its length: Test_Player\r".to_string()));
+    assert!(!messages.iter().any(|message| message.starts_with("[W007]")));
+
+    assert!(!get_messages("Test_Player\nA9 00 8D && ?? 00 ?? && 8D 18 D4").iter().any(|message| message.starts_with("[E027]")));
+}
+
+#[test]
+fn verify_config_file_end_markers() {
+    let get_messages = |config: &str| crate::log::buffered(|| verify_config_file(&to_lines(config), ConfigSyntax::default()).unwrap()).1;
+
+    assert!(get_messages("First_Player\nA9 00 8D 18 D4 END\nSecond_Player\nA2 00 BD\n00 10 END").is_empty());
+
+    let missing_end = get_messages("First_Player\nA9 00 8D 18 D4 END\nSecond_Player\nA2 00 BD 00 10\n\nThird_Player\nA0 00 B9 00 10 END");
+    assert_eq!(missing_end, vec!["[E026] END marker missing in signature starting at line: 4 in: Second_Player\r"]);
+
+    let misplaced_end = get_messages("First_Player\nA9 00 8D END 18 D4\nA2 00 BD 00 10 END");
+    assert_eq!(misplaced_end, vec!["[E017] Signature END operator can only be present at the end of the line at line: 2 in: First_Player\r"]);
+}
+
+#[test]
+fn verify_info_file_signature_name_with_colon() {
+    let signatures = load_signatures("Test_Player:V2\nA9 00 8D 00 D4");
+    let info_tags = to_tags(&["NAME", "AUTHOR", "RELEASED", "REFERENCE", "COMMENT"]);
+    let verify = |info: &str| crate::log::buffered(|| verify_info_file(&to_lines(info), &signatures, &info_tags, ConfigSyntax::default()).unwrap());
+
+    assert_eq!(verify("Test_Player:V2\n     NAME: Test Player"), (false, vec![]));
+
+    let (issues_found, messages) = verify("Test_Player:V2\n     NAME: Test Player\n  AUTHOR: Someone");
+    assert!(issues_found);
+    assert_eq!(messages, vec!["[E103] Wrong indentation 'AUTHOR:' or invalid tag in: Test_Player:V2\r"]);
+}
+
+#[test]
+fn verify_config_file_duplicate_signatures() {
+    let verify = |config: &str| crate::log::buffered(|| verify_config_file(&to_lines(config), ConfigSyntax::default()).unwrap());
+
+    let (issues_found, messages) = verify("First_Player\nA9 00 8D 18 D4\nA9 00 8D 18 D4");
+    assert!(!issues_found);
+    assert_eq!(messages, vec!["[W008] Signature value defined more than once at line 2 and 3: First_Player\r"]);
+
+    let (issues_found, messages) = verify("First_Player\nA9 ?? 8D 18 D4\n\nSecond_Player\nA2 00 BD 00 10\n\nThird_Player\nEA A9 00 8D 18 D4 60");
+    assert!(!issues_found);
+    assert_eq!(messages, vec!["[W010] Signature value at line 2 always matches when the one at line 8 matches: First_Player and Third_Player\r"]);
+
+    // sub signatures are part of another signature, so they aren't reported when another signature covers them
+    assert_eq!(verify("First_Player\nA9 00 8D 18 D4\n(Sub)\nEA A9 00 8D 18 D4 60"), (false, vec![]));
+}
+
+#[test]
+fn verify_config_file_duplicate_signatures_in_line_order() {
+    let config = (0..50)
+        .map(|index| format!("Player_{index:02}\nA9 {index:02X} 8D 18 D4\n\nCopy_{index:02}\nA9 {index:02X} 8D 18 D4\n"))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let (issues_found, messages) = crate::log::buffered(|| verify_config_file(&to_lines(&config), ConfigSyntax::default()).unwrap());
+
+    assert!(!issues_found);
+    assert_eq!(messages.len(), 50);
+    for (index, message) in messages.iter().enumerate() {
+        let line_number = index * 6 + 2;
+        assert_eq!(message, &format!("[W009] Signature value at line {line_number} is identical to the one at line {}: Player_{index:02} and Copy_{index:02}\r", line_number + 3));
+    }
+}
@@ -0,0 +1,356 @@
+use super::*;
+use crate::fixture::Fixture;
+
+#[test]
+fn load_builtin_config() {
+    let signature_ids = PlayerId::load_builtin_config(None).unwrap();
+
+    assert!(!signature_ids.is_empty());
+    assert_eq!(signature_ids[0].signature_name.as_ref(), "128bytes_tiny");
+}
+
+#[test]
+fn scan_with_builtin_config() {
+    let signature_ids = PlayerId::load_builtin_config(Some(&"128bytes_tiny".to_string())).unwrap();
+    let buffer = [0x00, 0xA9, 0x1F, 0x8D, 0x18, 0xD4, 0xA5, 0x02, 0x25, 0x03, 0xD0, 0x3F, 0x00];
+
+    let matches = PlayerId::find_players_in_buffer(&buffer, &signature_ids, false);
+
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].signature_name.as_ref(), "128bytes_tiny");
+    assert_eq!(matches[0].indexes, vec![1]);
+}
+
+#[test]
+fn find_config_path_resolution_order() {
+    let current_directory = Fixture::new();
+    let executable_directory = Fixture::new();
+    let search_directories = [current_directory.path.as_path(), executable_directory.path.as_path()];
+
+    assert_eq!(PlayerId::find_config_path("which.cfg", &search_directories), Err("File doesn't exist: which.cfg".to_string()));
+
+    let executable_directory_config_path = executable_directory.write_file("which.cfg", b"");
+    assert_eq!(PlayerId::find_config_path("which.cfg", &search_directories), Ok(executable_directory_config_path));
+
+    let current_directory_config_path = current_directory.write_file("which.cfg", b"");
+    assert_eq!(PlayerId::find_config_path("which.cfg", &search_directories), Ok(current_directory_config_path));
+    assert!(PlayerId::get_config_path(Some(&"".to_string())).is_err());
+}
+
+#[test]
+fn convert_ids_to_string_line_endings() {
+    let lines = "Player_A\nA9 00 8D\n\nPlayer_B\nA2 ?? BD"
+        .lines().map(|line| line.to_string()).collect::<Vec<String>>();
+
+    let crlf_output = PlayerId::convert_ids_to_string(PlayerId::load_config_lines(&lines, None, ConfigSyntax::default()).unwrap(), true, LineEnding::CrLf, &HashMap::new());
+    assert_eq!(crlf_output.as_bytes(), b"Player_A\r\nA9 00 8D\r\n\r\nPlayer_B\r\nA2 ?? BD\r\n");
+
+    let lf_output = PlayerId::convert_ids_to_string(PlayerId::load_config_lines(&lines, None, ConfigSyntax::default()).unwrap(), true, LineEnding::Lf, &HashMap::new());
+    assert_eq!(lf_output.as_bytes(), b"Player_A\nA9 00 8D\n\nPlayer_B\nA2 ?? BD\n");
+
+    let old_format_output = PlayerId::convert_ids_to_string(PlayerId::load_config_lines(&lines, None, ConfigSyntax::default()).unwrap(), false, LineEnding::Lf, &HashMap::new());
+    assert_eq!(old_format_output.as_bytes(), b"Player_A\nA9 00 8D END\n\nPlayer_B\nA2 ?? BD END\n");
+}
+
+#[test]
+fn get_changed_operator_styles_mixed_input() {
+    let lines = "Player_A\nA9 00 8D && 18 D4\n\nPlayer_B\nA2 00 AND BD 00\n\nPlayer_C\nA9 00\n8D 18 END\n\nPlayer_D\nA2 ?? BD"
+        .lines().map(|line| line.to_string()).collect::<Vec<String>>();
+
+    let convert = |new_format| PlayerId::convert_ids_to_string(PlayerId::load_config_lines(&lines, None, ConfigSyntax::default()).unwrap(), new_format, LineEnding::Lf, &HashMap::new())
+        .lines().map(|line| line.to_string()).collect::<Vec<String>>();
+
+    assert_eq!(PlayerId::get_changed_operator_styles(&lines, &convert(true), ConfigSyntax::default()), vec!["Player_B", "Player_C"]);
+    assert_eq!(PlayerId::get_changed_operator_styles(&lines, &convert(false), ConfigSyntax::default()), vec!["Player_A", "Player_B", "Player_D"]);
+}
+
+#[test]
+fn detect_line_ending() {
+    assert_eq!(PlayerId::detect_line_ending(b"Player_A\nA9 00 8D\n\nPlayer_B\r\nA2 ?? BD\n"), LineEnding::Lf);
+    assert_eq!(PlayerId::detect_line_ending(b"Player_A\r\nA9 00 8D\r\n\r\nPlayer_B\nA2 ?? BD\r\n"), LineEnding::CrLf);
+    assert_eq!(PlayerId::detect_line_ending(b"Player_A"), LineEnding::CrLf);
+}
+
+#[test]
+fn convert_file_format_auto_line_ending() {
+    let fixture = Fixture::new();
+    let config_path = fixture.path.join("line-ending.cfg");
+    let config_file = config_path.to_string_lossy().to_string();
+
+    fs::write(&config_path, b"Player_A\nA9 00 8D END\n\nPlayer_B\nA2 ?? BD END\n").unwrap();
+    PlayerId::convert_file_format(Some(&config_file), true, LineEnding::Auto, false, ConfigSyntax::default()).unwrap();
+    let lf_output = fs::read(&config_path).unwrap();
+
+    fs::write(&config_path, b"Player_A\r\nA9 00 8D END\r\n\r\nPlayer_B\r\nA2 ?? BD END\r\n").unwrap();
+    PlayerId::convert_file_format(Some(&config_file), true, LineEnding::Auto, false, ConfigSyntax::default()).unwrap();
+    let crlf_output = fs::read(&config_path).unwrap();
+
+    assert_eq!(lf_output, b"Player_A\nA9 00 8D\n\nPlayer_B\nA2 ?? BD\n");
+    assert_eq!(crlf_output, b"Player_A\r\nA9 00 8D\r\n\r\nPlayer_B\r\nA2 ?? BD\r\n");
+}
+
+#[test]
+fn convert_file_format_with_raised_min_name_length() {
+    let fixture = Fixture::new();
+    let config_path = fixture.write_file("syntax.cfg", b"Test\nA9 00 8D 18 D4 END\n");
+    let config_file = config_path.to_string_lossy().to_string();
+    let syntax = ConfigSyntax { min_name_length: 5, ..ConfigSyntax::default() };
+
+    let (result, _) = crate::log::buffered(|| PlayerId::convert_file_format(Some(&config_file), true, LineEnding::Lf, false, syntax));
+    assert_eq!(result.err(), Some("Issues found in config file.".to_string()));
+
+    crate::log::buffered(|| PlayerId::convert_file_format(Some(&config_file), true, LineEnding::Lf, false, ConfigSyntax::default())).0.unwrap();
+    assert_eq!(fs::read(&config_path).unwrap(), b"Test\nA9 00 8D 18 D4\n");
+}
+
+#[test]
+fn get_info_file_path_explicit_info_file() {
+    let fixture = Fixture::new();
+    let info_file = fixture.write_file("custom.nfo", b"").to_string_lossy().to_string();
+
+    let info_path = PlayerId::get_info_file_path(Some(&"sidid.cfg".to_string()), Some(&info_file));
+    drop(fixture);
+
+    assert_eq!(info_path, Ok(PathBuf::from(&info_file)));
+    assert!(PlayerId::get_info_file_path(None, Some(&info_file)).is_err());
+    assert!(PlayerId::get_info_file_path(None, Some(&"".to_string())).is_err());
+}
+
+#[test]
+fn verify_signatures_and_info_reports_both_in_order() {
+    let fixture = Fixture::new();
+    let config_file = fixture.write_file("verify.cfg", b"Test_Player\na9 00 8D\n");
+    fixture.write_file("verify.nfo", b"Unknown_Player\n     NAME: Test\n");
+
+    let (issues_found, messages) = PlayerId::verify_signatures_and_info_buffered(Some(&config_file.display().to_string()), None, None, ConfigSyntax::default());
+
+    assert_eq!(issues_found, Ok(true));
+
+    let config_issue = messages.iter().position(|message| message.starts_with("[W006] Signature contains lowercase characters: Test_Player"));
+    let info_issue = messages.iter().position(|message| message.starts_with("[E104] Signature ID not found in config file: Unknown_Player"));
+    assert!(config_issue.is_some() && info_issue.is_some());
+    assert!(config_issue < info_issue);
+}
+
+#[test]
+fn convert_and_sort_bundle_keep_info_section() {
+    let fixture = Fixture::new();
+    let info_section = b"; [INFO]\r\nSecond_Player\r\n     NAME: Second\r\n  COMMENT: Line one\r\n           line two\r\n";
+    let bundle_path = fixture.write_file("bundle.cfg", &[&b"; [CONFIG]\r\nSecond_Player\r\nA2 00 BD END\r\n\r\nFirst_Player\r\nA9 00 8D END\r\n"[..], info_section].concat());
+    let bundle_file = bundle_path.to_string_lossy().to_string();
+
+    crate::log::buffered(|| PlayerId::convert_file_format(Some(&bundle_file), true, LineEnding::Auto, false, ConfigSyntax::default())).0.unwrap();
+    let converted = fs::read(&bundle_path).unwrap();
+    assert_eq!(converted, [&b"; [CONFIG]\r\nSecond_Player\r\nA2 00 BD\r\n\r\nFirst_Player\r\nA9 00 8D\r\n"[..], info_section].concat());
+
+    crate::log::buffered(|| PlayerId::sort_config_file(Some(&bundle_file), LineEnding::Auto, ConfigSyntax::default())).0.unwrap();
+    let sorted = fs::read(&bundle_path).unwrap();
+    assert_eq!(sorted, [&b"; [CONFIG]\r\nFirst_Player\r\nA9 00 8D\r\n\r\nSecond_Player\r\nA2 00 BD\r\n"[..], info_section].concat());
+
+    assert_eq!(PlayerId::load_info_file(&bundle_path, ConfigSyntax::default()).unwrap().len(), 1);
+}
+
+#[test]
+fn write_bundle_round_trip() {
+    let fixture = Fixture::new();
+    let config_file = fixture.write_file("bundle.cfg", b"; Test signatures\nFirst_Player\nA9 00 8D 18 D4 END\nSecond_Player\nA2 00 BD AND 8D 18 D4 END\n");
+    let info_file = fixture.write_file("bundle.nfo", b"First_Player\n     NAME: First\n  COMMENT: Line one\n           line two\n\nSecond_Player\n   AUTHOR: Someone\n");
+    let bundle_file = config_file.with_extension("bundle");
+    let config_file_name = config_file.display().to_string();
+    PlayerId::write_bundle(Some(&config_file_name), None, &bundle_file, LineEnding::Lf, ConfigSyntax::default()).unwrap();
+
+    let bundle_file_name = bundle_file.display().to_string();
+    let signature_ids = PlayerId::load_config_file(&config_file, None, ConfigSyntax::default()).unwrap();
+    let bundle_signature_ids = PlayerId::load_config_file(&bundle_file, None, ConfigSyntax::default()).unwrap();
+    let signature_infos = PlayerId::load_info_file(&info_file, ConfigSyntax::default()).unwrap();
+    let bundle_info_path = PlayerId::get_info_file_path(Some(&bundle_file_name), None);
+    let bundle_signature_infos = PlayerId::load_info_file(&bundle_file, ConfigSyntax::default()).unwrap();
+    let (issues_found, _) = PlayerId::verify_signatures_and_info_buffered(Some(&bundle_file_name), None, None, ConfigSyntax::default());
+    let bundle_text = fs::read_to_string(&bundle_file).unwrap();
+
+    assert!(bundle_text.starts_with("; [CONFIG]\n; Test signatures\n"));
+    assert_eq!(bundle_info_path, Ok(bundle_file.clone()));
+    assert_eq!(issues_found, Ok(false));
+
+    let format_signatures = |signature_ids: &[SignatureConfig]| signature_ids.iter()
+        .map(|signature| (signature.signature_name.to_string(), PlayerId::format_signature_value(signature, true)))
+        .collect::<Vec<(String, String)>>();
+    assert_eq!(format_signatures(&bundle_signature_ids), format_signatures(&signature_ids));
+    assert_eq!(bundle_signature_infos.iter().map(PlayerInfo::parse).collect::<Vec<PlayerInfo>>(), signature_infos.iter().map(PlayerInfo::parse).collect::<Vec<PlayerInfo>>());
+}
+
+#[test]
+fn load_config_lines_without_signatures() {
+    let lines = ["Test_Player", "A9 00 8D"].map(str::to_string);
+
+    assert_eq!(PlayerId::load_config_lines(&[], None, ConfigSyntax::default()).err(), Some("No signature defined.".to_string()));
+    assert_eq!(PlayerId::load_config_lines(&lines, Some(&"Other_Player".to_string()), ConfigSyntax::default()).err(), Some("No signature found with name: Other_Player".to_string()));
+    assert_eq!(PlayerId::load_config_lines(&["     NAME: Test".to_string()], None, ConfigSyntax::default()).err(), Some("Not a config file.".to_string()));
+}
+
+#[test]
+fn sort_config_file_keeps_comments_with_their_signature() {
+    let fixture = Fixture::new();
+    let config = "; Signatures of test players\n\n; comment of zeta\nZeta_Player\nA9 00 8D\n\n; comment of alpha\n; second line\nAlpha_Player\nA2 00 BD\n\n; end of file\n";
+    let config_path = fixture.write_file("sort.cfg", config.as_bytes());
+
+    crate::log::buffered(|| PlayerId::sort_config_file(Some(&config_path.to_string_lossy().to_string()), LineEnding::Lf, ConfigSyntax::default())).0.unwrap();
+
+    assert_eq!(fs::read_to_string(&config_path).unwrap(), "; Signatures of test players\n\n; comment of alpha\n; second line\nAlpha_Player\nA2 00 BD\n\n; comment of zeta\nZeta_Player\nA9 00 8D\n\n; end of file\n");
+}
+
+#[test]
+fn sort_signature_ids_stable_and_idempotent() {
+    let config = "Zeta_Player\r\nA9 00 8D\r\n(Zeta_Sub)\r\nA2 00 BD\r\n\r\nalpha_Player\r\nA9 01 8D\r\nA9 02 8D && 8D 18 D4\r\n\r\nMid_Player\r\nA0 00 B9 MINSIZE=16\r\n";
+    let lines = config.lines().map(|line| line.to_string()).collect::<Vec<String>>();
+
+    let sorted = PlayerId::convert_ids_to_string(PlayerId::sort_signature_ids(Signature::read_config_lines(&lines, None, ConfigSyntax::default()).unwrap()), true, LineEnding::CrLf, &HashMap::new());
+    assert_eq!(sorted, "alpha_Player\r\nA9 01 8D\r\nA9 02 8D && 8D 18 D4\r\n\r\nMid_Player\r\nA0 00 B9 MINSIZE=16\r\n\r\nZeta_Player\r\nA9 00 8D\r\n(Zeta_Sub)\r\nA2 00 BD\r\n");
+
+    let lines = sorted.lines().map(|line| line.to_string()).collect::<Vec<String>>();
+    let sorted_again = PlayerId::convert_ids_to_string(PlayerId::sort_signature_ids(Signature::read_config_lines(&lines, None, ConfigSyntax::default()).unwrap()), true, LineEnding::CrLf, &HashMap::new());
+    assert_eq!(sorted_again, sorted);
+}
+
+#[test]
+fn try_find_players_in_file_skipping_regions() {
+    let fixture = Fixture::new();
+    let filename = fixture.write_file("skip-region.bin", &[0xA9, 0x00, 0x8D, 0x18, 0xD4, 0xEA, 0xA9, 0x00, 0x8D, 0x18, 0xD4, 0xEA]);
+    let signature_ids = PlayerId::load_config_lines(&["Test_Player".to_string(), "A9 00 8D 18 D4".to_string()], None, ConfigSyntax::default()).unwrap();
+
+    let find_indexes = |skip_regions: &[Range<usize>]| {
+        PlayerId::try_find_players_in_file_skipping(&filename, &signature_ids, false, 1, ScanStart::Data, None, skip_regions)
+            .map(|matches| matches.unwrap().iter().map(|signature_match| signature_match.indexes.clone()).collect::<Vec<Vec<usize>>>())
+    };
+
+    let without_skip_regions = find_indexes(&[]);
+    let outside_skip_region = find_indexes(&[2..3, 5..6]);
+    let all_skipped = find_indexes(&[2..3, 10..12]);
+    let past_end_of_file = find_indexes(&[5..6, 10..13]);
+
+    assert_eq!(without_skip_regions, Ok(vec![vec![0]]));
+    assert_eq!(outside_skip_region, Ok(vec![vec![6]]));
+    assert_eq!(all_skipped, Ok(vec![]));
+    assert!(past_end_of_file.is_err());
+}
+
+#[test]
+fn try_find_players_in_large_file_with_skip_regions_and_timeout() {
+    let fixture = Fixture::new();
+    let filename = fixture.write_file("large.bin", &[]);
+    File::options().write(true).open(&filename).unwrap().set_len(CHUNKED_READ_THRESHOLD + 1).unwrap();
+    let signature_ids = PlayerId::load_config_lines(&["Test_Player".to_string(), "A9 00 8D 18 D4".to_string()], None, ConfigSyntax::default()).unwrap();
+
+    let result = PlayerId::try_find_players_in_file_skipping(&filename, &signature_ids, false, 1, ScanStart::Data, None, &[0..1, 2..3]);
+
+    assert_eq!(result.err(), Some("Skip regions are not supported for files larger than 64 MB".to_string()));
+
+    let result = PlayerId::try_find_players_in_file_skipping(&filename, &signature_ids, false, 1, ScanStart::Data, Some(Duration::ZERO), &[]);
+    assert_eq!(result.map(|matches| matches.is_none()), Ok(true));
+}
+
+#[test]
+fn load_config_file_sets_source_of_matches() {
+    let fixture = Fixture::new();
+    let community_config = fixture.write_file("community.cfg", b"Community_Player\r\nA9 00 8D 18 D4\r\n");
+    let personal_config = fixture.write_file("personal.cfg", b"Personal_Player\r\nA2 00 BD 00 10\r\n");
+
+    let mut signature_ids = PlayerId::load_config_file(&community_config, None, ConfigSyntax::default()).unwrap();
+    signature_ids.extend(PlayerId::load_config_file(&personal_config, None, ConfigSyntax::default()).unwrap());
+
+    let matches = PlayerId::find_players_in_buffer(&[0xA9, 0x00, 0x8D, 0x18, 0xD4, 0xA2, 0x00, 0xBD, 0x00, 0x10], &signature_ids, true);
+
+    let sources = matches.iter().map(|player| player.source.as_deref().unwrap().to_string()).collect::<Vec<String>>();
+    assert_eq!(sources, vec![community_config.display().to_string(), personal_config.display().to_string()]);
+}
+
+#[test]
+fn try_find_players_in_empty_file() {
+    let signature_ids = PlayerId::load_config_lines(&["Test_Player".to_string(), "A9 00 8D 18 D4".to_string()], None, ConfigSyntax::default()).unwrap();
+
+    let fixture = Fixture::new();
+    for extension in ["sid", "prg", "vsf", "bin"] {
+        let filename = fixture.write_file(&format!("empty.{extension}"), &[]);
+
+        for scan_start in [ScanStart::Data, ScanStart::Init, ScanStart::Program] {
+            let matches = PlayerId::try_find_players_in_file_from(&filename, &signature_ids, true, 2, scan_start);
+            assert_eq!(matches.map(|matches| matches.len()), Ok(0));
+        }
+    }
+}
+
+#[test]
+fn scan_files_streaming_yields_all_files() {
+    let signature_ids = Arc::new(PlayerId::load_config_lines(&["Test_Player".to_string(), "A9 00 8D 18 D4".to_string()], None, ConfigSyntax::default()).unwrap());
+    let fixture = Fixture::new();
+    let files = (0..5)
+        .map(|index| {
+            let data: &[u8] = if index % 2 == 0 { &[0xEA, 0xA9, 0x00, 0x8D, 0x18, 0xD4] } else { &[0xEA, 0xEA] };
+            fixture.write_file(&format!("streaming-{index}.bin"), data)
+        })
+        .collect::<Vec<PathBuf>>();
+
+    let mut scanned = PlayerId::scan_files_streaming(files.clone(), Arc::clone(&signature_ids), false)
+        .map(|file_matches| (file_matches.filename, file_matches.matches.len()))
+        .collect::<Vec<(PathBuf, usize)>>();
+    let first_only = PlayerId::scan_files_streaming(files.clone(), signature_ids, false).take(1).count();
+
+    scanned.sort();
+    assert_eq!(scanned, files.into_iter().enumerate().map(|(index, filename)| (filename, (index + 1) % 2)).collect::<Vec<(PathBuf, usize)>>());
+    assert_eq!(first_only, 1);
+}
+
+#[test]
+fn suggest_signature_from_positive_and_negative_files() {
+    let fixture = Fixture::new();
+    let create_files = |kind: &str, files: &[&[u8]]| {
+        files.iter().enumerate()
+            .map(|(index, data)| fixture.write_file(&format!("suggest-{kind}-{index}.bin"), data))
+            .collect::<Vec<PathBuf>>()
+    };
+    let positive_files = create_files("positive", &[
+        &[0x00, 0xA9, 0x0F, 0x8D, 0x18, 0xD4, 0x60, 0xEA],
+        &[0xEA, 0xEA, 0xA2, 0x00, 0xA9, 0x0F, 0x8D, 0x18, 0xD4, 0x60],
+        &[0xA9, 0x0F, 0x8D, 0x18, 0xD4, 0x60, 0x00, 0x00, 0x00, 0x00]
+    ]);
+    let negative_files = create_files("negative", &[
+        &[0xA9, 0x0F, 0x8D, 0x18, 0xD4, 0x4C, 0x00, 0x10],
+        &[0x0F, 0x8D, 0x18, 0xD4, 0x00]
+    ]);
+
+    let signature = PlayerId::suggest_signature(&positive_files, &negative_files, "Suggested_Player");
+    let no_signature = PlayerId::suggest_signature(&positive_files, &positive_files[..1], "Suggested_Player");
+
+    let signature = signature.unwrap().unwrap();
+    assert_eq!(signature.signature_name.as_ref(), "Suggested_Player");
+    assert_eq!(PlayerId::format_signature_value(&signature, true), "8D 18 D4 60");
+    assert!(no_signature.unwrap().is_none());
+}
+
+/// Every truncation of the files is scanned in all ways, which fails the test when reading any of them panics.
+#[test]
+fn try_find_players_in_truncated_files() {
+    let fixture = Fixture::new();
+    let signature_ids = PlayerId::load_config_lines(&["Test_Player".to_string(), "A9 00 8D 18 D4".to_string()], None, ConfigSyntax::default()).unwrap();
+
+    let mut sid_file = vec![0; 0x7C];
+    sid_file[0..4].copy_from_slice(b"PSID");
+    sid_file[0x06..0x08].copy_from_slice(&0x7Cu16.to_be_bytes());
+    sid_file[0x0A..0x0C].copy_from_slice(&0x1002u16.to_be_bytes());
+    sid_file.extend_from_slice(&[0x00, 0x10, 0xEA, 0xEA, 0xA9, 0x00, 0x8D, 0x18, 0xD4]);
+    let prg_file = [0x00, 0x10, 0xA9, 0x00, 0x8D, 0x18, 0xD4].to_vec();
+    let vsf_file = [&b"VICE Snapshot File\x1a\x02\x00C64"[..], &[0; 13], b"C64MEM"].concat();
+
+    for (extension, data) in [("sid", sid_file), ("prg", prg_file), ("vsf", vsf_file)] {
+        for length in 0..=data.len() {
+            let filename = fixture.write_file(&format!("truncated.{extension}"), &data[..length]);
+
+            for scan_start in [ScanStart::Data, ScanStart::Init, ScanStart::Program] {
+                let _ = PlayerId::try_find_players_in_file_from(&filename, &signature_ids, true, 2, scan_start);
+                let _ = PlayerId::get_load_address(&filename, scan_start);
+            }
+            let _ = PlayerId::try_find_players_in_file_skipping(&filename, &signature_ids, true, 1, ScanStart::Data, None, &[0..1, 2..3]);
+            let _ = (PlayerId::get_song_count(&filename), PlayerId::is_likely_sid_player(&filename));
+        }
+    }
+}
@@ -0,0 +1,41 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+#![allow(dead_code)]
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static FIXTURE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// A temporary directory with test files, which is removed when the fixture is dropped.
+pub struct Fixture {
+    pub path: PathBuf
+}
+
+impl Fixture {
+    pub fn new() -> Fixture {
+        let fixture_id = FIXTURE_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let path = env::temp_dir().join(format!("player-id-test-{}-{}", std::process::id(), fixture_id));
+        fs::create_dir_all(&path).unwrap();
+        Fixture { path }
+    }
+
+    /// Writes the data to a file in the fixture. Missing directories in the filename are created.
+    pub fn write_file(&self, filename: &str, data: &[u8]) -> PathBuf {
+        let file_path = self.path.join(filename);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(&file_path, data).unwrap();
+        file_path
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
@@ -3,26 +3,26 @@
 
 use std::path::Path;
 
-pub fn get_hvsc_root(filename: &str) -> Option<String> {
-    let mut path = Path::new(filename);
+pub fn get_hvsc_root(filename: &Path) -> Option<String> {
+    let mut path = filename;
     if path.is_file() {
         path = path.parent().unwrap();
     }
 
     let stil_txt = path.join("STIL.txt");
     if stil_txt.exists() {
-        return Some(path.parent().unwrap().to_str().unwrap().to_string());
+        return Some(path.parent().unwrap().to_string_lossy().to_string());
     }
 
     let stil_txt = path.join("C64Music");
     if stil_txt.join("DOCUMENTS").join("STIL.txt").exists() {
-        return Some(stil_txt.to_str().unwrap().to_string());
+        return Some(stil_txt.to_string_lossy().to_string());
     }
 
     loop {
         let stil_txt = path.join("DOCUMENTS").join("STIL.txt");
         if stil_txt.exists() {
-            return Some(path.to_str().unwrap().to_string());
+            return Some(path.to_string_lossy().to_string());
         }
 
         if path.parent().is_none() {
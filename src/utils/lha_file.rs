@@ -0,0 +1,447 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+use std::time::Instant;
+
+const METHOD_OFFSET: usize = 2;
+const METHOD_SIZE: usize = 5;
+const LEVEL_0_1_NAME_OFFSET: usize = 22;
+const LEVEL_2_CRC_OFFSET: usize = 21;
+const LEVEL_2_EXTENDED_HEADER_OFFSET: usize = 24;
+const MIN_HEADER_SIZE: usize = 24;
+const EXTENDED_HEADER_FILENAME: u8 = 0x01;
+const EXTENDED_HEADER_DIRECTORY: u8 = 0x02;
+const EXTENDED_HEADER_COMMON: u8 = 0x00;
+const DIRECTORY_SEPARATOR: u8 = 0xFF;
+const BASIC_START_ADDRESS: usize = 0x0801;
+const MAX_SFX_CODE_SIZE: usize = 0x2000;
+const MAX_COMPRESSION_RATIO: usize = 2048;
+
+const MAX_MATCH_LENGTH: usize = 256;
+const MIN_MATCH_LENGTH: usize = 3;
+const CHARACTER_CODE_COUNT: usize = 256 + MAX_MATCH_LENGTH - MIN_MATCH_LENGTH + 1;
+const CHARACTER_CODE_BITS: usize = 9;
+const LENGTH_CODE_COUNT: usize = 19;
+const LENGTH_CODE_BITS: usize = 5;
+const MAX_CODE_LENGTH: usize = 16;
+
+/// A file that is stored in an LHA archive.
+pub struct LhaEntry {
+    /// The path of the file in the archive, with directories separated by a slash.
+    pub path: String,
+    pub data: Vec<u8>
+}
+
+/// Returns whether the data is an LHA archive, or a self-extracting archive that has an LHA archive after the
+/// extraction code, like the SFX archives of the C64.
+pub fn is_lha_file(source: &[u8]) -> bool {
+    find_archive_start(source).is_some()
+}
+
+/// Returns the total size of the files in the archive after extraction, or 0 when the headers can't be read.
+pub fn get_unpacked_size(source: &[u8]) -> usize {
+    read_headers(source).map_or(0, |headers| headers.iter().map(|header| header.original_size).sum())
+}
+
+/// Extracts the files of an LHA archive with header level 0, 1 or 2. Files that are stored without compression (-lh0-)
+/// or with the static Huffman compression of LHA (-lh5-, -lh6- and -lh7-) are supported, other methods give an error.
+/// `None` is returned when the deadline passes before all files are extracted.
+pub fn get_entries(source: &[u8], deadline: Option<Instant>) -> Result<Option<Vec<LhaEntry>>, String> {
+    let mut entries = vec![];
+
+    for header in read_headers(source)? {
+        let data = &source[header.data_offset..header.data_offset + header.compressed_size];
+        let data = match &header.method {
+            b"-lhd-" => continue,
+            b"-lh0-" => Some(data.to_vec()),
+            b"-lh5-" => decode(data, header.original_size, 13, deadline)?,
+            b"-lh6-" => decode(data, header.original_size, 15, deadline)?,
+            b"-lh7-" => decode(data, header.original_size, 16, deadline)?,
+            method => return Err(format!("Unsupported LHA compression method: {}", String::from_utf8_lossy(method)))
+        };
+        let Some(data) = data else {
+            return Ok(None);
+        };
+        if data.len() != header.original_size || get_crc(&data) != header.crc {
+            return Err(format!("CRC error in LHA archive for file: {}", header.path));
+        }
+
+        entries.push(LhaEntry { path: header.path, data });
+    }
+    Ok(Some(entries))
+}
+
+struct LhaHeader {
+    method: [u8; METHOD_SIZE],
+    path: String,
+    compressed_size: usize,
+    original_size: usize,
+    crc: u16,
+    data_offset: usize
+}
+
+/// Reads the headers of the files in the archive. The original size of a file may not exceed what its compressed size
+/// can be decoded to, so that a corrupt header can't make the decoder produce more data than the archive can hold.
+fn read_headers(source: &[u8]) -> Result<Vec<LhaHeader>, String> {
+    let mut offset = find_archive_start(source).ok_or("Not an LHA archive.")?;
+    let mut headers = vec![];
+
+    while offset + MIN_HEADER_SIZE <= source.len() && source[offset] != 0 {
+        let header = read_header(source, offset)?;
+        if header.data_offset + header.compressed_size > source.len() {
+            return Err("LHA archive is truncated.".to_string());
+        }
+        if header.original_size > header.compressed_size.max(1).saturating_mul(MAX_COMPRESSION_RATIO) {
+            return Err(format!("Invalid original size in LHA archive for file: {}", header.path));
+        }
+
+        offset = header.data_offset + header.compressed_size;
+        headers.push(header);
+    }
+    Ok(headers)
+}
+
+/// Finds the first header of the archive, which is at the start of an LHA file, or after the extraction code that
+/// follows the BASIC line of a self-extracting archive of the C64. The checksum of headers of level 0 and 1 and the
+/// CRC of headers of level 2 must be valid, so that data that happens to contain the name of a compression method
+/// is not taken for an archive.
+fn find_archive_start(source: &[u8]) -> Option<usize> {
+    if is_header(source, 0) {
+        return Some(0);
+    }
+
+    let code_start = get_basic_program_end(source)?;
+    (code_start..source.len().saturating_sub(MIN_HEADER_SIZE).min(code_start + MAX_SFX_CODE_SIZE))
+        .find(|&offset| is_header(source, offset))
+}
+
+/// Returns the offset after the end of the BASIC program of a PRG file that is loaded at the start of BASIC memory.
+fn get_basic_program_end(source: &[u8]) -> Option<usize> {
+    if read_u16(source.get(..2)?, 0) as usize != BASIC_START_ADDRESS {
+        return None;
+    }
+
+    let mut offset = 2;
+    loop {
+        let next_line_address = read_u16(source.get(offset..offset + 2)?, 0) as usize;
+        if next_line_address == 0 {
+            return Some(offset + 2);
+        }
+
+        let next_offset = (next_line_address + 2).checked_sub(BASIC_START_ADDRESS).filter(|&next_offset| next_offset > offset)?;
+        offset = next_offset;
+    }
+}
+
+fn is_header(source: &[u8], offset: usize) -> bool {
+    source.len() >= offset + MIN_HEADER_SIZE &&
+        is_method(&source[offset + METHOD_OFFSET..offset + METHOD_OFFSET + METHOD_SIZE]) &&
+        is_valid_header(&source[offset..])
+}
+
+fn is_valid_header(header: &[u8]) -> bool {
+    match header[20] {
+        0 | 1 => {
+            let header_size = header[0] as usize + 2;
+            header.len() >= header_size && header[METHOD_OFFSET..header_size].iter().fold(0u8, |checksum, &value| checksum.wrapping_add(value)) == header[1]
+        },
+        2 => is_valid_level_2_header(header),
+        _ => false
+    }
+}
+
+/// Checks the CRC in the common extended header of a level 2 header, which is taken over the whole header with the
+/// CRC itself set to zero.
+fn is_valid_level_2_header(header: &[u8]) -> bool {
+    let header_size = read_u16(header, 0) as usize;
+    let Some(header) = header.get(..header_size) else {
+        return false;
+    };
+
+    let mut offset = LEVEL_2_EXTENDED_HEADER_OFFSET + 2;
+    let mut size = read_u16(header, LEVEL_2_EXTENDED_HEADER_OFFSET) as usize;
+    while size >= 3 && offset + size <= header_size {
+        if header[offset] == EXTENDED_HEADER_COMMON && size >= 5 {
+            let mut unchecked_header = header.to_vec();
+            unchecked_header[offset + 1..offset + 3].fill(0);
+            return get_crc(&unchecked_header) == read_u16(header, offset + 1);
+        }
+        offset += size;
+        size = read_u16(header, offset - 2) as usize;
+    }
+    false
+}
+
+fn is_method(method: &[u8]) -> bool {
+    method.starts_with(b"-lh") && method[4] == b'-' && (method[3].is_ascii_digit() || method[3] == b'd')
+}
+
+fn read_header(source: &[u8], offset: usize) -> Result<LhaHeader, String> {
+    let header = &source[offset..];
+    let mut method = [0; METHOD_SIZE];
+    method.copy_from_slice(&header[METHOD_OFFSET..METHOD_OFFSET + METHOD_SIZE]);
+    let compressed_size = read_u32(header, 7) as usize;
+    let original_size = read_u32(header, 11) as usize;
+
+    match header[20] {
+        level @ (0 | 1) => {
+            let header_size = header[0] as usize + 2;
+            let name_size = header[21] as usize;
+            if header_size < LEVEL_0_1_NAME_OFFSET + name_size + 2 || header_size > header.len() {
+                return Err("Invalid LHA header.".to_string());
+            }
+            let name = &header[LEVEL_0_1_NAME_OFFSET..LEVEL_0_1_NAME_OFFSET + name_size];
+            let crc = read_u16(header, LEVEL_0_1_NAME_OFFSET + name_size);
+
+            let mut data_offset = offset + header_size;
+            let mut compressed_size = compressed_size;
+            let mut path = get_path(&[], name);
+            if level == 1 {
+                // the extended headers of level 1 are counted as compressed data
+                let (extended_headers_size, extended_path) = read_extended_headers(source, data_offset, read_u16(header, header_size - 2) as usize)?;
+                data_offset += extended_headers_size;
+                compressed_size = compressed_size.checked_sub(extended_headers_size).ok_or("Invalid LHA header.")?;
+                path = extended_path.unwrap_or(path);
+            }
+            Ok(LhaHeader { method, path, compressed_size, original_size, crc, data_offset })
+        },
+        2 => {
+            let header_size = read_u16(header, 0) as usize;
+            if header_size < LEVEL_2_EXTENDED_HEADER_OFFSET + 2 || header_size > header.len() {
+                return Err("Invalid LHA header.".to_string());
+            }
+            let crc = read_u16(header, LEVEL_2_CRC_OFFSET);
+            let (_, path) = read_extended_headers(source, offset + LEVEL_2_EXTENDED_HEADER_OFFSET + 2, read_u16(header, LEVEL_2_EXTENDED_HEADER_OFFSET) as usize)?;
+            Ok(LhaHeader { method, path: path.unwrap_or_default(), compressed_size, original_size, crc, data_offset: offset + header_size })
+        },
+        level => Err(format!("Unsupported LHA header level: {level}"))
+    }
+}
+
+/// Reads the extended headers, which each end with the size of the next one, and returns their total size together
+/// with the path of the file when it is in the headers.
+fn read_extended_headers(source: &[u8], mut offset: usize, mut size: usize) -> Result<(usize, Option<String>), String> {
+    let start_offset = offset;
+    let (mut name, mut directory) = (None, vec![]);
+
+    while size > 0 {
+        let extended_header = source.get(offset..offset + size).filter(|header| header.len() >= 3).ok_or("LHA header is truncated.")?;
+        let content = &extended_header[1..size - 2];
+        match extended_header[0] {
+            EXTENDED_HEADER_FILENAME => name = Some(content),
+            EXTENDED_HEADER_DIRECTORY => directory = content.to_vec(),
+            _ => {}
+        }
+        offset += size;
+        size = read_u16(extended_header, size - 2) as usize;
+    }
+    Ok((offset - start_offset, name.map(|name| get_path(&directory, name))))
+}
+
+fn get_path(directory: &[u8], name: &[u8]) -> String {
+    let mut path = directory.to_vec();
+    if !path.is_empty() && path.last() != Some(&DIRECTORY_SEPARATOR) {
+        path.push(DIRECTORY_SEPARATOR);
+    }
+    path.extend_from_slice(name);
+    path.iter().map(|&value| if value == DIRECTORY_SEPARATOR || value == b'\\' { '/' } else { value as char }).collect()
+}
+
+fn read_u16(source: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([source[offset], source[offset + 1]])
+}
+
+fn read_u32(source: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([source[offset], source[offset + 1], source[offset + 2], source[offset + 3]])
+}
+
+/// Returns the CRC-16 (polynomial $A001) that LHA stores of the original data of a file.
+fn get_crc(data: &[u8]) -> u16 {
+    data.iter().fold(0, |crc, &value| {
+        (0..8).fold(crc ^ value as u16, |crc, _| if crc & 1 == 1 { (crc >> 1) ^ 0xA001 } else { crc >> 1 })
+    })
+}
+
+/// Reads the bits of the compressed data from the most significant bit of each byte. Bits after the end of the data
+/// are read as zero, which the decoder treats as truncated data.
+struct BitReader<'a> {
+    source: &'a [u8],
+    position: usize
+}
+
+impl BitReader<'_> {
+    fn read_bit(&mut self) -> usize {
+        let bit = self.source.get(self.position / 8).map_or(0, |value| (value >> (7 - self.position % 8)) & 1);
+        self.position += 1;
+        bit as usize
+    }
+
+    fn read_bits(&mut self, bit_count: usize) -> usize {
+        (0..bit_count).fold(0, |value, _| (value << 1) | self.read_bit())
+    }
+
+    /// Reads a code length as a 3-bit value, where 7 is followed by a 1 bit for each further length and a 0 bit.
+    fn read_code_length(&mut self) -> Result<usize, String> {
+        let mut code_length = self.read_bits(3);
+        if code_length == 7 {
+            while self.read_bit() == 1 {
+                code_length += 1;
+                if code_length > MAX_CODE_LENGTH {
+                    return Err("Invalid code length in LHA data.".to_string());
+                }
+            }
+        }
+        Ok(code_length)
+    }
+}
+
+/// A canonical Huffman code, where codes of the same length are assigned in the order of the symbols.
+struct HuffmanCode {
+    symbols: Vec<usize>,
+    counts: [usize; MAX_CODE_LENGTH + 1],
+    single_symbol: Option<usize>
+}
+
+impl HuffmanCode {
+    fn new(code_lengths: &[usize]) -> HuffmanCode {
+        let mut counts = [0; MAX_CODE_LENGTH + 1];
+        code_lengths.iter().for_each(|&code_length| counts[code_length] += 1);
+        counts[0] = 0;
+
+        let symbols = (1..=MAX_CODE_LENGTH)
+            .flat_map(|code_length| (0..code_lengths.len()).filter(move |&symbol| code_lengths[symbol] == code_length))
+            .collect();
+        HuffmanCode { symbols, counts, single_symbol: None }
+    }
+
+    fn single(symbol: usize) -> HuffmanCode {
+        HuffmanCode { symbols: vec![], counts: [0; MAX_CODE_LENGTH + 1], single_symbol: Some(symbol) }
+    }
+
+    fn read_symbol(&self, reader: &mut BitReader) -> Result<usize, String> {
+        if let Some(symbol) = self.single_symbol {
+            return Ok(symbol);
+        }
+
+        let (mut code, mut first_code, mut index) = (0, 0, 0);
+        for count in &self.counts[1..] {
+            code |= reader.read_bit();
+            if code < first_code + count {
+                return Ok(self.symbols[index + code - first_code]);
+            }
+            index += count;
+            first_code = (first_code + count) << 1;
+            code <<= 1;
+        }
+        Err("Invalid Huffman code in LHA data.".to_string())
+    }
+}
+
+/// Reads the code lengths of the code lengths or of the positions. After the code length at the special index, a 2-bit
+/// value tells how many of the next code lengths are zero.
+fn read_code_lengths(reader: &mut BitReader, symbol_count: usize, count_bits: usize, special_index: Option<usize>) -> Result<HuffmanCode, String> {
+    let count = reader.read_bits(count_bits);
+    if count == 0 {
+        return Ok(HuffmanCode::single(reader.read_bits(count_bits)));
+    }
+    if count > symbol_count {
+        return Err("Invalid code length count in LHA data.".to_string());
+    }
+
+    let mut code_lengths = vec![0; symbol_count];
+    let mut index = 0;
+    while index < count {
+        code_lengths[index] = reader.read_code_length()?;
+        index += 1;
+        if Some(index) == special_index {
+            index += reader.read_bits(2);
+        }
+    }
+    Ok(HuffmanCode::new(&code_lengths))
+}
+
+/// Reads the code lengths of the characters and match lengths, which are coded with the given code, where the codes
+/// 0 to 2 are runs of zero code lengths.
+fn read_character_code_lengths(reader: &mut BitReader, length_code: &HuffmanCode) -> Result<HuffmanCode, String> {
+    let count = reader.read_bits(CHARACTER_CODE_BITS);
+    if count == 0 {
+        return Ok(HuffmanCode::single(reader.read_bits(CHARACTER_CODE_BITS)));
+    }
+    if count > CHARACTER_CODE_COUNT {
+        return Err("Invalid code length count in LHA data.".to_string());
+    }
+
+    let mut code_lengths = vec![0; CHARACTER_CODE_COUNT];
+    let mut index = 0;
+    while index < count {
+        match length_code.read_symbol(reader)? {
+            0 => index += 1,
+            1 => index += reader.read_bits(4) + 3,
+            2 => index += reader.read_bits(CHARACTER_CODE_BITS) + 20,
+            code_length => {
+                code_lengths[index] = code_length - 2;
+                index += 1;
+            }
+        }
+    }
+    if index > count {
+        return Err("Invalid code lengths in LHA data.".to_string());
+    }
+    Ok(HuffmanCode::new(&code_lengths))
+}
+
+/// Decodes data that is compressed with the static Huffman coding of LHA, with a dictionary of 2^dictionary_bits bytes.
+/// `None` is returned when the deadline passes, which is checked at the start of each block.
+fn decode(source: &[u8], original_size: usize, dictionary_bits: usize, deadline: Option<Instant>) -> Result<Option<Vec<u8>>, String> {
+    let position_code_count = dictionary_bits + 1;
+    let position_code_bits = if dictionary_bits == 13 { 4 } else { 5 };
+
+    let mut reader = BitReader { source, position: 0 };
+    let mut data = Vec::with_capacity(original_size);
+    let mut block_size = 0;
+    let (mut character_code, mut position_code) = (HuffmanCode::single(0), HuffmanCode::single(0));
+
+    while data.len() < original_size {
+        if block_size == 0 {
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Ok(None);
+            }
+            block_size = reader.read_bits(16);
+            if block_size == 0 {
+                return Err("Invalid block size in LHA data.".to_string());
+            }
+            let length_code = read_code_lengths(&mut reader, LENGTH_CODE_COUNT, LENGTH_CODE_BITS, Some(3))?;
+            character_code = read_character_code_lengths(&mut reader, &length_code)?;
+            position_code = read_code_lengths(&mut reader, position_code_count, position_code_bits, None)?;
+        }
+        block_size -= 1;
+
+        let symbol = character_code.read_symbol(&mut reader)?;
+        if reader.position > source.len() * 8 {
+            return Err("LHA data is truncated.".to_string());
+        }
+        if symbol < 256 {
+            data.push(symbol as u8);
+            continue;
+        }
+
+        let match_length = symbol - 256 + MIN_MATCH_LENGTH;
+        let position_bits = position_code.read_symbol(&mut reader)?;
+        let distance = if position_bits == 0 { 1 } else { (1 << (position_bits - 1)) + reader.read_bits(position_bits - 1) + 1 };
+        if distance > data.len() {
+            return Err("Invalid match distance in LHA data.".to_string());
+        }
+        if reader.position > source.len() * 8 {
+            return Err("LHA data is truncated.".to_string());
+        }
+
+        for _ in 0..match_length.min(original_size - data.len()) {
+            data.push(data[data.len() - distance]);
+        }
+    }
+    Ok(Some(data))
+}
+
+#[cfg(test)]
+#[path = "./lha_file_test.rs"]
+mod lha_file_test;
@@ -0,0 +1,106 @@
+use super::*;
+use std::time::Instant;
+
+// an archive with TUNE.PRG compressed with -lh5- and README.TXT stored with -lh0-
+const LHA_ARCHIVE: [u8; 152] = [
+    0x1E, 0x57, 0x2D, 0x6C, 0x68, 0x35, 0x2D, 0x3E, 0x00, 0x00, 0x00, 0x47, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x5A, 0x20,
+    0x00, 0x08, 0x54, 0x55, 0x4E, 0x45, 0x2E, 0x50, 0x52, 0x47, 0x89, 0x11, 0x00, 0x34, 0x48, 0x8F, 0x88, 0xC4, 0xBC, 0x00,
+    0x04, 0x02, 0x00, 0x2A, 0xAC, 0x24, 0xB0, 0x62, 0xC0, 0x0B, 0x8E, 0xC1, 0x6B, 0x00, 0x6C, 0x12, 0x80, 0x80, 0x4C, 0x05,
+    0x0D, 0xAE, 0x4A, 0xC1, 0xF0, 0x82, 0x18, 0xA3, 0x92, 0x59, 0xA7, 0xA2, 0x9A, 0xAB, 0xB2, 0xDB, 0x85, 0x7E, 0x18, 0xE5,
+    0x9E, 0x9A, 0x8F, 0x6D, 0xF8, 0xE7, 0xAE, 0xFC, 0xF7, 0xEF, 0xC8, 0x53, 0x1D, 0x00, 0x20, 0xCB, 0x2D, 0x6C, 0x68, 0x30,
+    0x2D, 0x17, 0x00, 0x00, 0x00, 0x17, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x5A, 0x20, 0x00, 0x0A, 0x52, 0x45, 0x41, 0x44,
+    0x4D, 0x45, 0x2E, 0x54, 0x58, 0x54, 0x55, 0x8A, 0x68, 0x65, 0x6C, 0x6C, 0x6F, 0x20, 0x68, 0x65, 0x6C, 0x6C, 0x6F, 0x20,
+    0x68, 0x65, 0x6C, 0x6C, 0x6F, 0x20, 0x77, 0x6F, 0x72, 0x6C, 0x64, 0x00
+];
+
+/// Creates an LHA archive with header level 2 in which the file is stored without compression.
+fn create_level_2_archive(name: &str, data: &[u8]) -> Vec<u8> {
+    let header_size = LEVEL_2_EXTENDED_HEADER_OFFSET + 2 + 5 + name.len() + 3;
+    let mut header = (header_size as u16).to_le_bytes().to_vec();
+    header.extend_from_slice(b"-lh0-");
+    header.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    header.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    header.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x20, 0x02]);
+    header.extend_from_slice(&get_crc(data).to_le_bytes());
+    header.extend_from_slice(&[0x55, 0x05, 0x00]);
+    header.extend_from_slice(&[EXTENDED_HEADER_COMMON, 0x00, 0x00]);
+    header.extend_from_slice(&(name.len() as u16 + 3).to_le_bytes());
+    header.push(EXTENDED_HEADER_FILENAME);
+    header.extend_from_slice(name.as_bytes());
+    header.extend_from_slice(&[0x00, 0x00]);
+
+    let crc = get_crc(&header).to_le_bytes();
+    header[LEVEL_2_EXTENDED_HEADER_OFFSET + 3..LEVEL_2_EXTENDED_HEADER_OFFSET + 5].copy_from_slice(&crc);
+    [&header[..], data, &[0x00]].concat()
+}
+
+fn get_tune_data() -> Vec<u8> {
+    [&[0x00, 0x10, 0xA9, 0x00, 0x8D, 0x18, 0xD4][..], &[0xEA, 0x60, 0xA2, 0x00].repeat(6), &(0..40).collect::<Vec<u8>>()].concat()
+}
+
+#[test]
+fn get_entries_of_compressed_and_stored_files() {
+    let entries = get_entries(&LHA_ARCHIVE, None).unwrap().unwrap();
+
+    assert_eq!(entries.iter().map(|entry| entry.path.as_str()).collect::<Vec<&str>>(), vec!["TUNE.PRG", "README.TXT"]);
+    assert_eq!(entries[0].data, get_tune_data());
+    assert_eq!(entries[1].data, b"hello hello hello world");
+}
+
+#[test]
+fn get_entries_of_self_extracting_archive() {
+    let sfx_archive = [&[0x01, 0x08, 0x0B, 0x08, 0x0A, 0x00, 0x9E, 0x32, 0x30, 0x36, 0x31, 0x00, 0x00, 0x00][..], &LHA_ARCHIVE].concat();
+
+    assert!(is_lha_file(&sfx_archive));
+    assert_eq!(get_entries(&sfx_archive, None).unwrap().unwrap()[0].data, get_tune_data());
+}
+
+#[test]
+fn get_entries_of_unsupported_and_corrupt_archives() {
+    let mut unsupported_archive = LHA_ARCHIVE;
+    unsupported_archive[5] = b'1';
+    unsupported_archive[1] = unsupported_archive[1].wrapping_sub(4);
+    assert_eq!(get_entries(&unsupported_archive, None).err(), Some("Unsupported LHA compression method: -lh1-".to_string()));
+
+    let mut corrupt_archive = LHA_ARCHIVE;
+    corrupt_archive[140] = b'X';
+    assert_eq!(get_entries(&corrupt_archive, None).err(), Some("CRC error in LHA archive for file: README.TXT".to_string()));
+
+    assert!(!is_lha_file(&get_tune_data()));
+}
+
+#[test]
+fn get_entries_of_level_2_archive() {
+    let archive = create_level_2_archive("TUNE.PRG", &get_tune_data());
+
+    let entries = get_entries(&archive, None).unwrap().unwrap();
+    assert_eq!(entries[0].path, "TUNE.PRG");
+    assert_eq!(entries[0].data, get_tune_data());
+
+    let mut corrupt_archive = archive;
+    corrupt_archive[LEVEL_2_EXTENDED_HEADER_OFFSET + 3] ^= 0xFF;
+    assert!(!is_lha_file(&corrupt_archive));
+}
+
+#[test]
+fn archive_is_only_found_at_the_start_or_after_a_basic_line() {
+    assert!(!is_lha_file(&[&[0x12, 0x34, 0x56][..], &LHA_ARCHIVE].concat()));
+    assert!(!is_lha_file(&[&[0x01, 0x08, 0x00, 0x00][..], &[0xEA; MAX_SFX_CODE_SIZE], &LHA_ARCHIVE].concat()));
+    assert!(is_lha_file(&[&[0x01, 0x08, 0x00, 0x00][..], &[0xEA; 0x100], &LHA_ARCHIVE].concat()));
+}
+
+#[test]
+fn get_entries_with_invalid_sizes() {
+    let mut oversized_archive = LHA_ARCHIVE;
+    oversized_archive[14] = 0x01;
+    oversized_archive[1] = oversized_archive[1].wrapping_add(1);
+    assert_eq!(get_entries(&oversized_archive, None).err(), Some("Invalid original size in LHA archive for file: TUNE.PRG".to_string()));
+
+    assert_eq!(decode(&[0x00, 0x00, 0x00, 0x00], 10, 13, None).err(), Some("Invalid block size in LHA data.".to_string()));
+    assert!(decode(&LHA_ARCHIVE[32..52], 0x47, 13, None).is_err());
+}
+
+#[test]
+fn get_entries_stops_at_the_deadline() {
+    assert!(get_entries(&LHA_ARCHIVE, Some(Instant::now())).unwrap().is_none());
+}
@@ -0,0 +1,59 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static SILENT: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static BUFFER: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+pub fn set_silent(silent: bool) {
+    SILENT.store(silent, Ordering::Relaxed);
+}
+
+pub fn is_silent() -> bool {
+    SILENT.load(Ordering::Relaxed)
+}
+
+/// Writes a message to stderr, or to the buffer of the current thread when called within [`buffered`].
+pub fn write(message: String) {
+    BUFFER.with(|buffer| match buffer.borrow_mut().as_mut() {
+        Some(messages) => messages.push(message),
+        None => eprintln!("{message}")
+    });
+}
+
+/// Runs `f` while collecting all messages logged on the current thread instead of writing them to stderr.
+pub fn buffered<T>(f: impl FnOnce() -> T) -> (T, Vec<String>) {
+    let previous_buffer = BUFFER.with(|buffer| buffer.replace(Some(vec![])));
+    let result = f();
+    let messages = BUFFER.with(|buffer| buffer.replace(previous_buffer)).unwrap_or_default();
+    (result, messages)
+}
+
+pub fn flush(messages: &[String]) {
+    for message in messages {
+        write(message.to_string());
+    }
+}
+
+/// Writes an informational message to stderr, unless silent mode is enabled.
+#[macro_export]
+macro_rules! log_info {
+    ($($arg:tt)*) => {
+        if !$crate::log::is_silent() {
+            $crate::log::write(format!($($arg)*));
+        }
+    };
+}
+
+/// Writes an error or diagnostic message to stderr, also when silent mode is enabled.
+#[macro_export]
+macro_rules! log_error {
+    ($($arg:tt)*) => {
+        $crate::log::write(format!($($arg)*))
+    };
+}
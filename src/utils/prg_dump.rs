@@ -0,0 +1,95 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+use std::collections::HashMap;
+
+const ADDRESS_SIZE: usize = 2;
+const SEGMENT_HEADER_SIZE: usize = 2 * ADDRESS_SIZE;
+const MEMORY_SIZE: usize = 0x10000;
+const BASIC_START_ADDRESS: usize = 0x0801;
+
+/// Returns the data of each program in a dump of concatenated programs. Each program starts with a header with its load
+/// address followed by its end address, both as 2 bytes in little endian, where the end address is the address after
+/// the last byte, like the addresses of a SAVE. A header is only accepted when the end address is above the load address
+/// and the data fits in the rest of the dump, so that a file that is not a dump is rejected. Less bytes than a header at
+/// the end of the dump are ignored as padding.
+///
+/// When the dump doesn't consist of such headers, the programs are taken to start with only their load address, like
+/// PRG files. Their length is then guessed: each program is as long as possible while still fitting in the 64 KB of
+/// memory above its load address, and the next program must start with a load address at the start of a page or at the
+/// start of BASIC.
+pub fn get_segments(source: &[u8]) -> Result<Vec<&[u8]>, String> {
+    get_segments_with_end_address(source).or_else(|error| get_segments_with_load_address(source).ok_or(error))
+}
+
+fn get_segments_with_end_address(source: &[u8]) -> Result<Vec<&[u8]>, String> {
+    let mut segments = vec![];
+    let mut offset = 0;
+
+    while source.len() - offset >= SEGMENT_HEADER_SIZE {
+        let load_address = read_address(source, offset);
+        let end_address = read_address(source, offset + ADDRESS_SIZE);
+        let data_offset = offset + SEGMENT_HEADER_SIZE;
+
+        if end_address <= load_address || data_offset + end_address - load_address > source.len() {
+            return Err(format!("Invalid segment header at offset ${offset:04X} in dump."));
+        }
+
+        offset = data_offset + end_address - load_address;
+        segments.push(&source[data_offset..offset]);
+    }
+
+    if segments.is_empty() {
+        return Err("Dump does not contain any segment.".to_string());
+    }
+    Ok(segments)
+}
+
+fn get_segments_with_load_address(source: &[u8]) -> Option<Vec<&[u8]>> {
+    let mut segment_ends = HashMap::new();
+    find_segment_end(source, 0, &mut segment_ends)?;
+
+    let mut segments = vec![];
+    let mut offset = 0;
+    while offset < source.len() {
+        let end_offset = segment_ends[&offset]?;
+        segments.push(&source[offset + ADDRESS_SIZE..end_offset]);
+        offset = end_offset;
+    }
+    Some(segments)
+}
+
+/// Returns the end of the longest program at the offset after which the rest of the dump also consists of programs,
+/// which is remembered per offset so that each offset is only tried once.
+fn find_segment_end(source: &[u8], offset: usize, segment_ends: &mut HashMap<usize, Option<usize>>) -> Option<usize> {
+    if let Some(&segment_end) = segment_ends.get(&offset) {
+        return segment_end;
+    }
+
+    let data_offset = offset + ADDRESS_SIZE;
+    let segment_end = (data_offset < source.len()).then(|| {
+        let max_end = source.len().min(data_offset + MEMORY_SIZE - read_address(source, offset));
+        (data_offset + 1..=max_end).rev().find(|&end_offset| {
+            end_offset == source.len() ||
+                (is_segment_start(source, end_offset) && find_segment_end(source, end_offset, segment_ends).is_some())
+        })
+    }).flatten();
+
+    segment_ends.insert(offset, segment_end);
+    segment_end
+}
+
+fn is_segment_start(source: &[u8], offset: usize) -> bool {
+    source.len() > offset + ADDRESS_SIZE && {
+        let load_address = read_address(source, offset);
+        load_address & 0xFF == 0 || load_address == BASIC_START_ADDRESS
+    }
+}
+
+fn read_address(source: &[u8], offset: usize) -> usize {
+    u16::from_le_bytes([source[offset], source[offset + 1]]) as usize
+}
+
+#[cfg(test)]
+#[path = "./prg_dump_test.rs"]
+mod prg_dump_test;
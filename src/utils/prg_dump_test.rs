@@ -0,0 +1,28 @@
+use super::*;
+
+#[test]
+fn get_segments_of_concatenated_programs() {
+    let dump = [&[0x00, 0x10, 0x03, 0x10, 0xA9, 0x00, 0x60][..], &[0x00, 0xC0, 0x02, 0xC0, 0xEA, 0xEA, 0x00]].concat();
+
+    assert_eq!(get_segments(&dump), Ok(vec![&[0xA9, 0x00, 0x60][..], &[0xEA, 0xEA]]));
+}
+
+#[test]
+fn get_segments_of_concatenated_programs_with_load_address_only() {
+    let first_program = [0xEA; 0x1000];
+    let dump = [&[0x00, 0xF0][..], &first_program, &[0x01, 0x08, 0xA9, 0x00, 0x60]].concat();
+
+    assert_eq!(get_segments(&dump), Ok(vec![&first_program[..], &[0xA9, 0x00, 0x60]]));
+    assert_eq!(get_segments(&[0x00, 0x10, 0xA9, 0x00, 0x8D, 0x00, 0x20, 0x60]), Ok(vec![&[0xA9, 0x00, 0x8D, 0x00, 0x20, 0x60][..]]));
+}
+
+#[test]
+fn get_segments_of_invalid_dumps() {
+    assert!(get_segments(&[]).is_err());
+    assert!(get_segments(&[0x00, 0x10]).is_err());
+    assert!(get_segments(&[0xFF, 0xFF, 0xA9, 0x00, 0x60]).is_err());
+
+    assert!(get_segments_with_end_address(&[0x00, 0x10, 0x00, 0x10, 0xA9]).is_err());
+    assert!(get_segments_with_end_address(&[0x00, 0x10, 0x08, 0x10, 0xA9, 0x00, 0x60]).is_err());
+    assert!(get_segments_with_end_address(&[0x00, 0x10, 0x01, 0x10, 0x60, 0x00, 0x20, 0x00, 0x10]).is_err());
+}
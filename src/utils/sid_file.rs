@@ -5,18 +5,63 @@ const MIN_SID_HEADER_SIZE: usize = 0x76;
 const DATA_OFFSET_OFFSET: usize = 0x06;
 const LOAD_ADDRESS_OFFSET: usize = 0x08;
 const LOAD_ADDRESS_SIZE: usize = 2;
+const INIT_ADDRESS_OFFSET: usize = 0x0A;
+const SONGS_OFFSET: usize = 0x0E;
 
 pub fn is_sid_file(source: &[u8]) -> bool {
     source.len() >= MIN_SID_HEADER_SIZE && matches!(&source[0..4], b"RSID" | b"PSID")
 }
 
+/// Returns the offset of the C64 data in the file, which is never past the end of the file, or 0 when the header has
+/// no valid data offset.
 pub fn get_data_offset(source: &[u8]) -> usize {
-    let mut data_offset = u16::from_be_bytes([source[DATA_OFFSET_OFFSET], source[DATA_OFFSET_OFFSET + 1]]) as usize;
+    let mut data_offset = read_address(source, DATA_OFFSET_OFFSET);
     if data_offset >= MIN_SID_HEADER_SIZE && data_offset <= source.len() {
-        if source[LOAD_ADDRESS_OFFSET] == 0 && source[LOAD_ADDRESS_OFFSET + 1] == 0 {
+        if read_address(source, LOAD_ADDRESS_OFFSET) == 0 {
             data_offset += LOAD_ADDRESS_SIZE;
         }
-        return data_offset
+        return data_offset.min(source.len())
     }
     0
 }
+
+/// Returns the offset of the init routine in the file, or `None` when it doesn't point into the data of the file.
+pub fn get_init_offset(source: &[u8]) -> Option<usize> {
+    let data_offset = get_data_offset(source);
+    if data_offset == 0 {
+        return None;
+    }
+
+    let load_address = get_load_address(source)?;
+    let init_address = match read_address(source, INIT_ADDRESS_OFFSET) {
+        0 => load_address,
+        init_address => init_address
+    };
+
+    let init_offset = data_offset + init_address.checked_sub(load_address)?;
+    (init_offset < source.len()).then_some(init_offset)
+}
+
+/// Returns the load address from the header, or from the first two bytes of the data when it's not in the header.
+/// `None` is returned when the file ends before the load address in the data.
+pub fn get_load_address(source: &[u8]) -> Option<usize> {
+    match read_address(source, LOAD_ADDRESS_OFFSET) {
+        0 => {
+            let data_offset = read_address(source, DATA_OFFSET_OFFSET);
+            source.get(data_offset..data_offset + LOAD_ADDRESS_SIZE).map(|load_address| u16::from_le_bytes([load_address[0], load_address[1]]) as usize)
+        },
+        load_address => Some(load_address)
+    }
+}
+
+fn read_address(source: &[u8], offset: usize) -> usize {
+    u16::from_be_bytes([source[offset], source[offset + 1]]) as usize
+}
+
+pub fn get_song_count(source: &[u8]) -> usize {
+    u16::from_be_bytes([source[SONGS_OFFSET], source[SONGS_OFFSET + 1]]) as usize
+}
+
+#[cfg(test)]
+#[path = "./sid_file_test.rs"]
+mod sid_file_test;
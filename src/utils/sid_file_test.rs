@@ -0,0 +1,58 @@
+use super::*;
+
+fn create_sid_header(songs: u16) -> Vec<u8> {
+    let mut header = vec![0; MIN_SID_HEADER_SIZE];
+    header[0..4].copy_from_slice(b"PSID");
+    header[DATA_OFFSET_OFFSET..DATA_OFFSET_OFFSET + 2].copy_from_slice(&(MIN_SID_HEADER_SIZE as u16).to_be_bytes());
+    header[SONGS_OFFSET..SONGS_OFFSET + 2].copy_from_slice(&songs.to_be_bytes());
+    header
+}
+
+#[test]
+fn get_song_count_multiple_songs() {
+    let header = create_sid_header(12);
+
+    assert!(is_sid_file(&header));
+    assert_eq!(get_song_count(&header), 12);
+}
+
+#[test]
+fn get_song_count_more_than_255_songs() {
+    assert_eq!(get_song_count(&create_sid_header(0x0100)), 256);
+}
+
+fn create_sid_file(load_address: u16, init_address: u16, data: &[u8]) -> Vec<u8> {
+    let mut sid_file = create_sid_header(1);
+    sid_file[INIT_ADDRESS_OFFSET..INIT_ADDRESS_OFFSET + 2].copy_from_slice(&init_address.to_be_bytes());
+    sid_file.extend_from_slice(&load_address.to_le_bytes());
+    sid_file.extend_from_slice(data);
+    sid_file
+}
+
+#[test]
+fn get_init_offset_from_init_address() {
+    let sid_file = create_sid_file(0x1000, 0x1003, &[0x4C, 0x00, 0x10, 0xA9, 0x00]);
+
+    assert_eq!(get_init_offset(&sid_file), Some(MIN_SID_HEADER_SIZE + 2 + 3));
+}
+
+#[test]
+fn truncated_header_without_load_address() {
+    let header = create_sid_header(1);
+
+    assert_eq!(get_data_offset(&header), MIN_SID_HEADER_SIZE);
+    assert_eq!(get_load_address(&header), None);
+    assert_eq!(get_init_offset(&header), None);
+
+    let header = [&header[..], &[0x00]].concat();
+    assert_eq!(get_data_offset(&header), MIN_SID_HEADER_SIZE + 1);
+    assert_eq!(get_load_address(&header), None);
+    assert_eq!(get_init_offset(&header), None);
+}
+
+#[test]
+fn get_init_offset_falls_back_to_data_offset() {
+    assert_eq!(get_init_offset(&create_sid_file(0x1000, 0, &[0xA9, 0x00])), Some(MIN_SID_HEADER_SIZE + 2));
+    assert_eq!(get_init_offset(&create_sid_file(0x1000, 0x0FFF, &[0xA9, 0x00])), None);
+    assert_eq!(get_init_offset(&create_sid_file(0x1000, 0x2000, &[0xA9, 0x00])), None);
+}
@@ -0,0 +1,38 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const SONGLENGTHS_FILENAMES: [&str; 2] = ["Songlengths.md5", "Songlengths.txt"];
+
+/// Loads the song lengths database of HVSC, which maps the MD5 of a SID file to the lengths of its songs.
+pub fn load_song_lengths(hvsc_root: &Path) -> Result<HashMap<String, String>, String> {
+    let documents_path = hvsc_root.join("DOCUMENTS");
+    let songlengths_path = SONGLENGTHS_FILENAMES.iter()
+        .map(|filename| documents_path.join(filename))
+        .find(|path| path.exists())
+        .ok_or_else(|| format!("Song lengths database not found in: {}", documents_path.display()))?;
+
+    let data = fs::read(&songlengths_path).map_err(|_| format!("Error reading file: {}", songlengths_path.display()))?;
+    Ok(parse_song_lengths(&String::from_utf8_lossy(&data)))
+}
+
+pub fn parse_song_lengths(text: &str) -> HashMap<String, String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with(';') && !line.starts_with('['))
+        .filter_map(|line| line.split_once('='))
+        .map(|(md5, song_lengths)| (md5.trim().to_ascii_lowercase(), song_lengths.trim().to_string()))
+        .collect()
+}
+
+/// Gets the song lengths of the file with the given MD5 digest of its content.
+pub fn get_song_lengths<'a>(song_lengths: &'a HashMap<String, String>, digest: &[u8; 16]) -> Option<&'a String> {
+    song_lengths.get(&format!("{:x}", md5::Digest(*digest)))
+}
+
+#[cfg(test)]
+#[path = "./songlengths_test.rs"]
+mod songlengths_test;
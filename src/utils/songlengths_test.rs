@@ -0,0 +1,27 @@
+use super::*;
+
+const SONGLENGTHS: &str = "\
+[Database]\r
+; /MUSICIANS/0-9/20CC/Dunkel.sid\r
+D41D8CD98F00B204E9800998ECF8427E=2:44 1:05.500\r
+; /MUSICIANS/0-9/20CC/Hell.sid\r
+0cc175b9c0f1b6a831c399e269772661=0:31\r
+";
+
+#[test]
+fn parse_song_lengths_skips_comments_and_sections() {
+    let song_lengths = parse_song_lengths(SONGLENGTHS);
+
+    assert_eq!(song_lengths.len(), 2);
+    assert_eq!(song_lengths.get("d41d8cd98f00b204e9800998ecf8427e"), Some(&"2:44 1:05.500".to_string()));
+    assert_eq!(song_lengths.get("0cc175b9c0f1b6a831c399e269772661"), Some(&"0:31".to_string()));
+}
+
+#[test]
+fn get_song_lengths_by_md5_of_data() {
+    let song_lengths = parse_song_lengths(SONGLENGTHS);
+
+    assert_eq!(get_song_lengths(&song_lengths, &md5::compute(b"").0), Some(&"2:44 1:05.500".to_string()));
+    assert_eq!(get_song_lengths(&song_lengths, &md5::compute(b"a").0), Some(&"0:31".to_string()));
+    assert_eq!(get_song_lengths(&song_lengths, &md5::compute(b"not in database").0), None);
+}
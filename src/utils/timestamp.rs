@@ -0,0 +1,66 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Parses a UTC date in the format YYYY-MM-DD with an optional time HH:MM or HH:MM:SS separated
+/// by a space or a T, or a number of seconds since 1970-01-01.
+pub fn parse_timestamp(text: &str) -> Option<SystemTime> {
+    let text = text.trim();
+    if let Ok(seconds) = text.parse::<u64>() {
+        return Some(UNIX_EPOCH + Duration::from_secs(seconds));
+    }
+
+    let (date, time) = match text.split_once([' ', 'T']) {
+        Some((date, time)) => (date, Some(time)),
+        None => (text, None)
+    };
+
+    let date_parts = parse_numbers(date, '-')?;
+    let [year, month, day] = date_parts[..] else {
+        return None;
+    };
+
+    if year < 1970 || !(1..=12).contains(&month) || day < 1 || day > get_days_in_month(year, month) {
+        return None;
+    }
+
+    let seconds_of_day = match time {
+        Some(time) => match parse_numbers(time, ':')?[..] {
+            [hours, minutes] if hours < 24 && minutes < 60 => hours * 3600 + minutes * 60,
+            [hours, minutes, seconds] if hours < 24 && minutes < 60 && seconds < 60 => hours * 3600 + minutes * 60 + seconds,
+            _ => return None
+        },
+        None => 0
+    };
+
+    Some(UNIX_EPOCH + Duration::from_secs(get_days_since_epoch(year, month, day) * SECONDS_PER_DAY + seconds_of_day))
+}
+
+fn parse_numbers(text: &str, separator: char) -> Option<Vec<u64>> {
+    text.split(separator).map(|number| number.parse::<u64>().ok()).collect()
+}
+
+fn get_days_in_month(year: u64, month: u64) -> u64 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31
+    }
+}
+
+fn get_days_since_epoch(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+#[cfg(test)]
+#[path = "./timestamp_test.rs"]
+mod timestamp_test;
@@ -0,0 +1,31 @@
+use super::*;
+
+fn to_seconds(timestamp: Option<SystemTime>) -> Option<u64> {
+    timestamp.map(|timestamp| timestamp.duration_since(UNIX_EPOCH).unwrap().as_secs())
+}
+
+#[test]
+fn parse_timestamp_date() {
+    assert_eq!(to_seconds(parse_timestamp("1970-01-01")), Some(0));
+    assert_eq!(to_seconds(parse_timestamp("2024-02-29")), Some(1709164800));
+}
+
+#[test]
+fn parse_timestamp_date_and_time() {
+    assert_eq!(to_seconds(parse_timestamp("2024-02-29 13:45")), Some(1709214300));
+    assert_eq!(to_seconds(parse_timestamp("2024-02-29T13:45:10")), Some(1709214310));
+}
+
+#[test]
+fn parse_timestamp_seconds() {
+    assert_eq!(to_seconds(parse_timestamp("1709164800")), Some(1709164800));
+}
+
+#[test]
+fn parse_timestamp_invalid() {
+    assert_eq!(parse_timestamp("2023-02-29"), None);
+    assert_eq!(parse_timestamp("2024-13-01"), None);
+    assert_eq!(parse_timestamp("2024-01-01 24:00"), None);
+    assert_eq!(parse_timestamp("01-01-2024"), None);
+    assert_eq!(parse_timestamp("yesterday"), None);
+}
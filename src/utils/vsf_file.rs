@@ -0,0 +1,70 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+const VSF_MAGIC: &[u8] = b"VICE Snapshot File\x1a";
+const VSF_VERSION_MAGIC: &[u8] = b"VICE Version\x1a";
+const VSF_VERSION_INFO_SIZE: usize = 8;
+const MACHINE_NAME_SIZE: usize = 16;
+const MODULE_NAME_SIZE: usize = 16;
+const MODULE_HEADER_SIZE: usize = MODULE_NAME_SIZE + 6;
+const MAX_SUPPORTED_SNAPSHOT_VERSION: u8 = 2;
+const MEMORY_MODULE_NAME: &[u8] = b"C64MEM";
+const MAX_SUPPORTED_MEMORY_MODULE_VERSION: u8 = 0;
+const MEMORY_MODULE_RAM_OFFSET: usize = 4;
+const RAM_SIZE: usize = 0x10000;
+
+pub fn is_vsf_file(source: &[u8]) -> bool {
+    source.starts_with(VSF_MAGIC)
+}
+
+pub fn get_ram(source: &[u8]) -> Result<&[u8], String> {
+    if !is_vsf_file(source) {
+        return Err("Not a VICE snapshot file.".to_string());
+    }
+
+    let mut offset = VSF_MAGIC.len();
+    let major_version = *source.get(offset).ok_or("VICE snapshot header is truncated.")?;
+    if major_version == 0 || major_version > MAX_SUPPORTED_SNAPSHOT_VERSION {
+        return Err(format!("Unsupported VICE snapshot version: {major_version}"));
+    }
+    offset += 2;
+
+    let machine_name = source.get(offset..offset + MACHINE_NAME_SIZE).ok_or("VICE snapshot header is truncated.")?;
+    if !machine_name.starts_with(b"C64") {
+        return Err(format!("Unsupported machine in VICE snapshot: {}", get_name(machine_name)));
+    }
+    offset += MACHINE_NAME_SIZE;
+
+    if source[offset..].starts_with(VSF_VERSION_MAGIC) {
+        offset += VSF_VERSION_MAGIC.len() + VSF_VERSION_INFO_SIZE;
+    }
+
+    while let Some(module_header) = source.get(offset..offset + MODULE_HEADER_SIZE) {
+        let module_name = &module_header[..MODULE_NAME_SIZE];
+        let module_major_version = module_header[MODULE_NAME_SIZE];
+        let module_size = u32::from_le_bytes(module_header[MODULE_NAME_SIZE + 2..].try_into().unwrap()) as usize;
+
+        if module_size < MODULE_HEADER_SIZE {
+            return Err(format!("Invalid module size in VICE snapshot for module: {}", get_name(module_name)));
+        }
+
+        if get_name(module_name).as_bytes() == MEMORY_MODULE_NAME {
+            if module_major_version > MAX_SUPPORTED_MEMORY_MODULE_VERSION {
+                return Err(format!("Unsupported memory module version in VICE snapshot: {module_major_version}"));
+            }
+
+            let ram_offset = offset + MODULE_HEADER_SIZE + MEMORY_MODULE_RAM_OFFSET;
+            return source.get(ram_offset..ram_offset + RAM_SIZE).ok_or_else(|| "Memory module in VICE snapshot is truncated.".to_string());
+        }
+        offset += module_size;
+    }
+    Err("No C64 memory module found in VICE snapshot.".to_string())
+}
+
+fn get_name(name: &[u8]) -> String {
+    name.iter().take_while(|&&char| char != 0).map(|&char| char as char).collect()
+}
+
+#[cfg(test)]
+#[path = "./vsf_file_test.rs"]
+mod vsf_file_test;
@@ -0,0 +1,71 @@
+use super::*;
+
+fn create_module(name: &str, major_version: u8, data: &[u8]) -> Vec<u8> {
+    let mut module = vec![0; MODULE_HEADER_SIZE];
+    module[..name.len()].copy_from_slice(name.as_bytes());
+    module[MODULE_NAME_SIZE] = major_version;
+    module[MODULE_NAME_SIZE + 2..].copy_from_slice(&((MODULE_HEADER_SIZE + data.len()) as u32).to_le_bytes());
+    module.extend_from_slice(data);
+    module
+}
+
+fn create_snapshot(memory_module_version: u8, with_version_info: bool) -> Vec<u8> {
+    let mut snapshot = VSF_MAGIC.to_vec();
+    snapshot.extend_from_slice(&[2, 0]);
+
+    let mut machine_name = [0; MACHINE_NAME_SIZE];
+    machine_name[..3].copy_from_slice(b"C64");
+    snapshot.extend_from_slice(&machine_name);
+
+    if with_version_info {
+        snapshot.extend_from_slice(VSF_VERSION_MAGIC);
+        snapshot.extend_from_slice(&[3, 8, 0, 0, 0, 0, 0, 0]);
+    }
+
+    snapshot.extend(create_module("MAINCPU", 1, &[0; 32]));
+
+    let mut memory = vec![0x2F, 0x37, 0x00, 0x00];
+    memory.resize(MEMORY_MODULE_RAM_OFFSET + RAM_SIZE, 0);
+    memory[MEMORY_MODULE_RAM_OFFSET + 0x1003..MEMORY_MODULE_RAM_OFFSET + 0x1006].copy_from_slice(&[0xA9, 0x0F, 0x8D]);
+    snapshot.extend(create_module("C64MEM", memory_module_version, &memory));
+    snapshot
+}
+
+#[test]
+fn get_ram_from_snapshot() {
+    for with_version_info in [false, true] {
+        let snapshot = create_snapshot(0, with_version_info);
+
+        assert!(is_vsf_file(&snapshot));
+
+        let ram = get_ram(&snapshot).unwrap();
+        assert_eq!(ram.len(), RAM_SIZE);
+        assert_eq!(&ram[0x1003..0x1006], &[0xA9, 0x0F, 0x8D]);
+    }
+}
+
+#[test]
+fn get_ram_unsupported_memory_module_version() {
+    let snapshot = create_snapshot(1, false);
+
+    assert_eq!(get_ram(&snapshot), Err("Unsupported memory module version in VICE snapshot: 1".to_string()));
+}
+
+#[test]
+fn get_ram_truncated_snapshot() {
+    let snapshot = create_snapshot(0, false);
+
+    assert!(get_ram(&snapshot[..snapshot.len() - 1]).is_err());
+    assert!(get_ram(&snapshot[..VSF_MAGIC.len() + 4]).is_err());
+}
+
+#[test]
+fn find_players_in_snapshot_at_memory_address() {
+    let lines = vec!["Test_Player".to_string(), "A9 0F 8D".to_string()];
+    let signature_ids = crate::PlayerId::load_config_lines(&lines, None, crate::ConfigSyntax::default()).unwrap();
+
+    let matches = crate::PlayerId::find_players_in_vsf(&create_snapshot(0, true), &signature_ids, false).unwrap();
+
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].indexes, vec![0x1003]);
+}
@@ -0,0 +1,27 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use common::{create_prg_file, run_executable, Fixture};
+
+#[test]
+fn scan_uses_builtin_config_when_no_config_file_is_found() {
+    let fixture = Fixture::new();
+    let executable_path = Path::new(env!("CARGO_BIN_EXE_player-id"));
+    let executable = fixture.path.join(executable_path.file_name().unwrap());
+    fs::copy(executable_path, &executable).unwrap();
+    fixture.write_file("tune.prg", &create_prg_file(0x1000, &[0xA9, 0x1F, 0x8D, 0x18, 0xD4, 0xA5, 0x02, 0x25, 0x03, 0xD0, 0x3F]));
+
+    let output = run_executable(&executable, &fixture.path, &["*.prg"]);
+    assert!(output.success);
+    assert!(output.stderr.contains("No config file found, using built-in config."));
+    assert!(output.stdout.lines().any(|line| line.starts_with("tune.prg") && line.ends_with("128bytes_tiny")));
+
+    let output = run_executable(&executable, &fixture.path, &["--no-builtin", "*.prg"]);
+    assert!(!output.success);
+    assert!(output.stderr.contains("File doesn't exist: sidid.cfg"));
+}
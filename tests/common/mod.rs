@@ -0,0 +1,76 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+#![allow(dead_code)]
+
+#[path = "../../src/utils/fixture.rs"] mod fixture;
+
+use std::ffi::OsStr;
+use std::path::Path;
+use std::process::Command;
+
+pub use fixture::Fixture;
+
+const SID_HEADER_SIZE: usize = 0x7C;
+
+/// The exit status and the output of a run of player-id.
+pub struct Output {
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String
+}
+
+/// Runs player-id with the arguments and waits for it to finish.
+pub fn run<S: AsRef<OsStr>>(arguments: &[S]) -> Output {
+    run_in(Path::new("."), arguments)
+}
+
+/// Runs player-id in the directory with the arguments and waits for it to finish.
+pub fn run_in<S: AsRef<OsStr>>(directory: &Path, arguments: &[S]) -> Output {
+    run_executable(Path::new(env!("CARGO_BIN_EXE_player-id")), directory, arguments)
+}
+
+/// Runs a copy of player-id in the directory with the arguments and waits for it to finish. The SIDIDCFG environment
+/// variable is removed, so that the config file is found the same way on every machine.
+pub fn run_executable<S: AsRef<OsStr>>(executable: &Path, directory: &Path, arguments: &[S]) -> Output {
+    let output = Command::new(executable)
+        .current_dir(directory)
+        .env_remove("SIDIDCFG")
+        .args(arguments)
+        .output()
+        .unwrap();
+
+    Output {
+        success: output.status.success(),
+        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        stderr: String::from_utf8_lossy(&output.stderr).to_string()
+    }
+}
+
+impl Fixture {
+    /// Runs player-id with the arguments on the files in the fixture that match the pattern, using the config file in the fixture.
+    pub fn scan(&self, arguments: &[&str], config_file: &str, pattern: &str) -> Output {
+        let mut scan_arguments = arguments.iter().map(|argument| argument.to_string()).collect::<Vec<String>>();
+        scan_arguments.push(format!("-f{}", self.path.join(config_file).display()));
+        scan_arguments.push(self.path.join(pattern).display().to_string());
+        run(&scan_arguments)
+    }
+}
+
+/// Creates a PSID file with the given songs and C64 data. The load address is stored in the first two bytes of the data.
+pub fn create_sid_file(songs: u16, load_address: u16, data: &[u8]) -> Vec<u8> {
+    let mut sid_file = vec![0; SID_HEADER_SIZE];
+    sid_file[0..4].copy_from_slice(b"PSID");
+    sid_file[0x04..0x06].copy_from_slice(&2u16.to_be_bytes());
+    sid_file[0x06..0x08].copy_from_slice(&(SID_HEADER_SIZE as u16).to_be_bytes());
+    sid_file[0x0E..0x10].copy_from_slice(&songs.to_be_bytes());
+    sid_file[0x10..0x12].copy_from_slice(&1u16.to_be_bytes());
+    sid_file.extend_from_slice(&create_prg_file(load_address, data));
+    sid_file
+}
+
+pub fn create_prg_file(load_address: u16, data: &[u8]) -> Vec<u8> {
+    let mut prg_file = load_address.to_le_bytes().to_vec();
+    prg_file.extend_from_slice(data);
+    prg_file
+}
@@ -0,0 +1,54 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+mod common;
+
+use common::Fixture;
+
+fn create_segment(load_address: u16, data: &[u8]) -> Vec<u8> {
+    let end_address = load_address + data.len() as u16;
+    [&load_address.to_le_bytes()[..], &end_address.to_le_bytes(), data].concat()
+}
+
+#[test]
+fn concatenated_programs_are_scanned_separately() {
+    let fixture = Fixture::new();
+    fixture.write_file("test.cfg", b"First_Player\r\nA9 00 8D 18 D4\r\n\r\nSecond_Player\r\nA2 00 BD 00 10\r\n");
+    let dump = [create_segment(0x1000, &[0xA9, 0x00, 0x8D, 0x18, 0xD4, 0x60]), create_segment(0xC000, &[0xA2, 0x00, 0xBD, 0x00, 0x10, 0x60])].concat();
+    fixture.write_file("music.bin", &dump);
+
+    let output = fixture.scan(&["--concatenated"], "test.cfg", "*.bin");
+
+    assert!(output.success);
+    assert!(output.stdout.lines().any(|line| line.contains("music.bin#1") && line.ends_with("First_Player")));
+    assert!(output.stdout.lines().any(|line| line.contains("music.bin#2") && line.ends_with("Second_Player")));
+}
+
+#[test]
+fn concatenated_programs_with_load_address_only_are_scanned_separately() {
+    let fixture = Fixture::new();
+    fixture.write_file("test.cfg", b"First_Player\r\nA9 00 8D 18 D4\r\n\r\nSecond_Player\r\nA2 00 BD 00 10\r\n");
+    let first_program = [&[0xA9, 0x00, 0x8D, 0x18, 0xD4, 0x60][..], &[0xEA; 0x0FFA]].concat();
+    let dump = [&[0x00, 0xF0][..], &first_program, &[0x00, 0xC0, 0xA2, 0x00, 0xBD, 0x00, 0x10, 0x60]].concat();
+    fixture.write_file("music.bin", &dump);
+
+    let output = fixture.scan(&["--concatenated"], "test.cfg", "*.bin");
+
+    assert!(output.success);
+    assert!(output.stdout.lines().any(|line| line.contains("music.bin#1") && line.ends_with("First_Player")));
+    assert!(output.stdout.lines().any(|line| line.contains("music.bin#2") && line.ends_with("Second_Player")));
+    assert!(output.stdout.contains("Identified files              2"));
+}
+
+#[test]
+fn concatenated_scan_fails_when_no_file_is_a_dump() {
+    let fixture = Fixture::new();
+    fixture.write_file("test.cfg", b"First_Player\r\nA9 00 8D 18 D4\r\n");
+    fixture.write_file("short.bin", &[0x00, 0x10]);
+
+    let output = fixture.scan(&["--concatenated"], "test.cfg", "*.bin");
+
+    assert!(!output.success);
+    assert!(output.stderr.lines().any(|line| line.contains("short.bin") && line.ends_with("Dump does not contain any segment.")));
+    assert!(output.stderr.contains("None of the files is a dump of concatenated programs."));
+}
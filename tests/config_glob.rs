@@ -0,0 +1,23 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+mod common;
+
+use common::{create_sid_file, Fixture};
+
+#[test]
+fn config_glob_merges_matching_config_files() {
+    let fixture = Fixture::new();
+    let first_config = fixture.write_file("configs/first.cfg", b"First_Player\r\nA9 00 8D 18 D4\r\n");
+    let second_config = fixture.write_file("configs/second.cfg", b"Second_Player\r\nA2 00 BD 00 10\r\n");
+    fixture.write_file("configs/notes.txt", b"Not a config file\r\n");
+    fixture.write_file("first.sid", &create_sid_file(1, 0x1000, &[0xA9, 0x00, 0x8D, 0x18, 0xD4]));
+    fixture.write_file("second.sid", &create_sid_file(1, 0x1000, &[0xA2, 0x00, 0xBD, 0x00, 0x10]));
+
+    let output = fixture.scan(&[], "configs/*.cfg", "*.sid");
+
+    assert!(output.success);
+    assert!(output.stderr.contains(&format!("Using config file: {}\r\nUsing config file: {}\r\n", first_config.display(), second_config.display())));
+    assert!(output.stdout.lines().any(|line| line.contains("first.sid") && line.ends_with("First_Player")));
+    assert!(output.stdout.lines().any(|line| line.contains("second.sid") && line.ends_with("Second_Player")));
+}
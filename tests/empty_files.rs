@@ -0,0 +1,21 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+mod common;
+
+use common::{create_sid_file, Fixture};
+
+#[test]
+fn empty_files_are_reported_as_empty() {
+    let fixture = Fixture::new();
+    fixture.write_file("test.cfg", b"Test_Player\r\nA9 00 8D 18 D4\r\n");
+    fixture.write_file("empty.sid", &[]);
+    fixture.write_file("tune.sid", &create_sid_file(1, 0x1000, &[0xA9, 0x00, 0x8D, 0x18, 0xD4]));
+
+    let output = fixture.scan(&[], "test.cfg", "*.sid");
+
+    assert!(output.success);
+    assert!(output.stdout.lines().any(|line| line.contains("empty.sid") && line.contains(">> EMPTY <<")));
+    assert!(!output.stdout.contains("UNIDENTIFIED"));
+    assert!(output.stdout.contains("Unidentified files            1"));
+}
@@ -0,0 +1,15 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+mod common;
+
+use common::run;
+
+#[test]
+fn examples_are_shown() {
+    let output = run(&["--examples"]);
+
+    assert!(output.success);
+    assert!(output.stdout.starts_with("Examples:"));
+    assert!(output.stdout.contains("    player-id verify"));
+}
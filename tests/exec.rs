@@ -0,0 +1,33 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+#![cfg(unix)]
+
+mod common;
+
+use common::{create_prg_file, Fixture};
+
+#[test]
+fn exec_runs_command_for_identified_files() {
+    let fixture = Fixture::new();
+    fixture.write_file("test.cfg", b"Test_Player\r\nA9 00 8D 18 D4\r\n");
+    let tune_path = fixture.write_file("tune.prg", &create_prg_file(0x1000, &[0xA9, 0x00, 0x8D, 0x18, 0xD4]));
+    fixture.write_file("unidentified.prg", &create_prg_file(0x1000, &[0xEA, 0xEA, 0xEA]));
+
+    let output = fixture.scan(&["--exec=echo tagged {file} as {player}"], "test.cfg", "*.prg");
+
+    assert!(output.success);
+    assert!(output.stdout.contains(&format!("tagged {} as Test_Player\n", tune_path.display())));
+    assert!(!output.stdout.contains("unidentified.prg as"));
+}
+
+#[test]
+fn exec_reports_failing_command() {
+    let fixture = Fixture::new();
+    fixture.write_file("test.cfg", b"Test_Player\r\nA9 00 8D 18 D4\r\n");
+    fixture.write_file("tune.prg", &create_prg_file(0x1000, &[0xA9, 0x00, 0x8D, 0x18, 0xD4]));
+
+    let output = fixture.scan(&["--exec=false {file}"], "test.cfg", "*.prg");
+
+    assert!(output.stderr.contains("command failed with exit code 1"));
+}
@@ -0,0 +1,41 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+mod common;
+
+use common::{create_prg_file, Fixture};
+
+/// The last file is too short for the skip region, so that an error is reported for it when it is scanned.
+#[test]
+fn fail_fast_stops_at_unidentified_file() {
+    let fixture = Fixture::new();
+    fixture.write_file("test.cfg", b"Test_Player\r\nA9 00 8D 18 D4\r\n");
+    let mut data = vec![0xA9, 0x00, 0x8D, 0x18, 0xD4];
+    data.resize(0x20, 0xEA);
+    fixture.write_file("tune00.prg", &create_prg_file(0x1000, &[0xEA; 0x20]));
+    for index in 1..63 {
+        fixture.write_file(&format!("tune{index:02}.prg"), &create_prg_file(0x1000, &data));
+    }
+    fixture.write_file("tune63.prg", &create_prg_file(0x1000, &data[..5]));
+
+    let output = fixture.scan(&["--fail-fast", "-c1", "--skip-region=$10:$11"], "test.cfg", "*.prg");
+
+    assert!(!output.success);
+    assert!(output.stderr.contains("ERROR: Unidentified file found:"));
+    assert!(output.stderr.contains("tune00.prg"));
+    assert!(!output.stderr.contains("tune63.prg"), "a file after the unidentified file was scanned: {}", output.stderr);
+    assert!(!output.stdout.contains("Test_Player"));
+    assert!(!output.stdout.contains("Summary:"));
+}
+
+#[test]
+fn fail_fast_succeeds_when_all_files_are_identified() {
+    let fixture = Fixture::new();
+    fixture.write_file("test.cfg", b"Test_Player\r\nA9 00 8D 18 D4\r\n");
+    fixture.write_file("tune.prg", &create_prg_file(0x1000, &[0xA9, 0x00, 0x8D, 0x18, 0xD4]));
+
+    let output = fixture.scan(&["--fail-fast"], "test.cfg", "*.prg");
+
+    assert!(output.success);
+    assert!(output.stdout.contains("Test_Player"));
+}
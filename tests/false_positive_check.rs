@@ -0,0 +1,48 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+mod common;
+
+use common::{create_prg_file, create_sid_file, run, Fixture};
+
+fn check_false_positives(fixture: &Fixture, config: &[u8]) -> (bool, String) {
+    let config_path = fixture.write_file("test.cfg", config);
+    let output = run(&[format!("--false-positive-check={}", fixture.path.join("negatives").display()), format!("-f{}", config_path.display())]);
+
+    (output.success, output.stdout)
+}
+
+#[test]
+fn false_positive_check_reports_matching_signatures() {
+    let fixture = Fixture::new();
+    fixture.write_file("negatives/game.prg", &create_prg_file(0x0801, &[0xA9, 0x00, 0x8D, 0x20, 0xD0, 0x60]));
+    fixture.write_file("negatives/tool.sid", &create_sid_file(1, 0x1000, &[0xA2, 0x00, 0xBD, 0x00, 0x10, 0x60]));
+
+    let (success, stdout) = check_false_positives(&fixture, b"Test_Player\r\nA9 00 8D 18 D4\r\n");
+    assert!(success);
+    assert!(stdout.contains("0 false positive(s) in 2 file(s)"));
+
+    let (success, stdout) = check_false_positives(&fixture, b"Test_Player\r\nA9 00 8D 18 D4\r\n\r\nBroad_Player\r\nA9 00 8D\r\n");
+    assert!(!success);
+    assert!(stdout.lines().any(|line| line.starts_with("FALSE POSITIVE: Broad_Player in: ") && line.contains("game.prg")));
+    assert!(!stdout.contains("Test_Player in: "));
+    assert!(stdout.contains("1 false positive(s) in 2 file(s)"));
+}
+
+#[test]
+fn false_positive_check_fails_when_a_file_is_not_checked() {
+    let fixture = Fixture::new();
+    fixture.write_file("negatives/game.prg", &create_prg_file(0x0801, &[0xA9, 0x00, 0x8D, 0x20, 0xD0, 0x60]));
+
+    let mut header = b"-lh1-".to_vec();
+    header.extend_from_slice(&[0x00; 8]);
+    header.extend_from_slice(&[0x00, 0x00, 0x00, 0x5A, 0x20, 0x00, 0x01, b'A', 0x00, 0x00]);
+    let checksum = header.iter().fold(0u8, |checksum, &value| checksum.wrapping_add(value));
+    fixture.write_file("negatives/packed.lha", &[&[header.len() as u8, checksum][..], &header, &[0x00]].concat());
+
+    let (success, stdout) = check_false_positives(&fixture, b"Test_Player\r\nA9 00 8D 18 D4\r\n");
+    assert!(!success);
+    assert!(stdout.lines().any(|line| line.starts_with("NOT CHECKED: ") && line.contains("packed.lha") && line.ends_with("(Unsupported LHA compression method: -lh1-)")));
+    assert!(stdout.contains("0 false positive(s) in 2 file(s)"));
+    assert!(stdout.contains("1 file(s) not checked"));
+}
@@ -0,0 +1,20 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+mod common;
+
+use common::{create_sid_file, Fixture};
+
+#[test]
+fn heuristic_labels_files_with_sid_writes_as_likely_sid_player() {
+    let fixture = Fixture::new();
+    fixture.write_file("test.cfg", b"Test_Player\r\nA2 00 BD 00 10\r\n");
+    fixture.write_file("writes.sid", &create_sid_file(1, 0x1000, &[0xA9, 0x0F, 0x8D, 0x18, 0xD4, 0x8D, 0x05, 0xD4, 0x99, 0x00, 0xD4]));
+    fixture.write_file("no_writes.sid", &create_sid_file(1, 0x1000, &[0xA9, 0x0F, 0x8D, 0x00, 0x04, 0x60]));
+
+    let output = fixture.scan(&["--heuristic", "-o"], "test.cfg", "*.sid");
+
+    assert!(output.success);
+    assert!(output.stdout.lines().any(|line| line.contains("writes.sid") && !line.contains("no_writes.sid") && line.ends_with(">> likely SID player (unidentified) <<")));
+    assert!(output.stdout.lines().any(|line| line.contains("no_writes.sid") && line.ends_with(">> UNIDENTIFIED <<")));
+}
@@ -0,0 +1,26 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+mod common;
+
+use common::{create_sid_file, Fixture};
+
+#[test]
+fn custom_labels_replace_the_markers_and_keep_the_columns_aligned() {
+    let fixture = Fixture::new();
+    fixture.write_file("test.cfg", b"Test_Player\r\nA9 00 8D 18 D4\r\n");
+    fixture.write_file("empty.sid", &[]);
+    fixture.write_file("tune.sid", &create_sid_file(1, 0x1000, &[0xA9, 0x00, 0x8D, 0x18, 0xD4]));
+    fixture.write_file("unknown.sid", &create_sid_file(1, 0x1000, &[0xA2, 0x00, 0xBD, 0x00, 0x10]));
+
+    let output = fixture.scan(&["-u", "--unidentified-label=?", "--skipped-label=SKIPPED_FILE"], "test.cfg", "*.sid");
+
+    assert!(output.success);
+    assert!(!output.stdout.contains("UNIDENTIFIED <<") && !output.stdout.contains("EMPTY <<"));
+
+    let get_column = |filename: &str, label: &str| output.stdout.lines().find(|line| line.contains(filename)).and_then(|line| line.find(label));
+    let player_column = get_column("tune.sid", "Test_Player");
+    assert!(player_column.is_some());
+    assert_eq!(get_column("unknown.sid", "?"), player_column);
+    assert_eq!(get_column("empty.sid", "SKIPPED_FILE"), player_column);
+}
@@ -0,0 +1,78 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+mod common;
+
+use common::{create_prg_file, create_sid_file, Fixture};
+
+fn get_crc(data: &[u8]) -> u16 {
+    data.iter().fold(0, |crc, &value| {
+        (0..8).fold(crc ^ value as u16, |crc, _| if crc & 1 == 1 { (crc >> 1) ^ 0xA001 } else { crc >> 1 })
+    })
+}
+
+/// Creates an LHA archive with header level 0 in which the files are stored with the given method, without compression.
+fn create_lha_file(method: &str, files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut archive = vec![];
+    for (name, data) in files {
+        let mut header = method.as_bytes().to_vec();
+        header.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        header.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        header.extend_from_slice(&[0x00, 0x00, 0x00, 0x5A, 0x20, 0x00, name.len() as u8]);
+        header.extend_from_slice(name.as_bytes());
+        header.extend_from_slice(&get_crc(data).to_le_bytes());
+
+        archive.push(header.len() as u8);
+        archive.push(header.iter().fold(0u8, |checksum, &value| checksum.wrapping_add(value)));
+        archive.extend_from_slice(&header);
+        archive.extend_from_slice(data);
+    }
+    archive.push(0);
+    archive
+}
+
+#[test]
+fn files_in_lha_archives_are_scanned_with_their_path() {
+    let fixture = Fixture::new();
+    fixture.write_file("test.cfg", b"First_Player\r\nA9 00 8D 18 D4\r\n\r\nSecond_Player\r\nA2 00 BD 00 10\r\n");
+    let tune = create_prg_file(0x1000, &[0xA9, 0x00, 0x8D, 0x18, 0xD4, 0x60]);
+    fixture.write_file("music.lha", &create_lha_file("-lh0-", &[("README.TXT", b"Read me"), ("TUNE.PRG", &tune)]));
+    fixture.write_file("packed.lha", &create_lha_file("-lh1-", &[("TUNE.PRG", &tune)]));
+    fixture.write_file("tune.sid", &create_sid_file(1, 0x1000, &[0xA2, 0x00, 0xBD, 0x00, 0x10]));
+
+    let output = fixture.scan(&[], "test.cfg", "*.*");
+
+    assert!(output.success);
+    assert!(output.stdout.lines().any(|line| line.contains("music.lha") && line.ends_with("First_Player [music.lha/TUNE.PRG]")));
+    assert!(output.stdout.lines().any(|line| line.contains("tune.sid") && line.ends_with("Second_Player")));
+    assert!(output.stderr.lines().any(|line| line.contains("packed.lha") && line.ends_with("Unsupported LHA compression method: -lh1-")));
+}
+
+#[test]
+fn scan_options_are_applied_to_files_in_lha_archives() {
+    let fixture = Fixture::new();
+    fixture.write_file("test.cfg", b"First_Player\r\nA9 00 8D 18 D4\r\n");
+    let tune = create_prg_file(0x1000, &[0xA9, 0xEA, 0x00, 0xEA, 0x8D, 0xEA, 0x18, 0xEA, 0xD4, 0x60]);
+    fixture.write_file("music.lha", &create_lha_file("-lh0-", &[("TUNE.PRG", &tune)]));
+
+    let output = fixture.scan(&["-u"], "test.cfg", "*.lha");
+    assert!(output.stdout.contains(">> UNIDENTIFIED <<"));
+
+    let output = fixture.scan(&["--stride=2"], "test.cfg", "*.lha");
+    assert!(output.stdout.lines().any(|line| line.ends_with("First_Player [music.lha/TUNE.PRG]")));
+
+    let output = fixture.scan(&["--skip-region=$00:$01"], "test.cfg", "*.lha");
+    assert!(output.stderr.lines().any(|line| line.contains("music.lha") && line.ends_with("Skip regions are not supported for LHA archives")));
+}
+
+#[test]
+fn lha_archive_after_other_data_is_not_extracted() {
+    let fixture = Fixture::new();
+    fixture.write_file("test.cfg", b"First_Player\r\nA9 00 8D 18 D4\r\n");
+    let tune = create_prg_file(0x1000, &[0xA9, 0x00, 0x8D, 0x18, 0xD4, 0x60]);
+    fixture.write_file("data.bin", &[&[0x12, 0x34, 0x56][..], &create_lha_file("-lh0-", &[("TUNE.PRG", &tune)])].concat());
+
+    let output = fixture.scan(&[], "test.cfg", "*.bin");
+
+    assert!(output.stdout.lines().any(|line| line.contains("data.bin") && line.ends_with("First_Player")));
+}
@@ -0,0 +1,36 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+mod common;
+
+use common::{create_sid_file, Fixture};
+
+const MIN_SID_HEADER_SIZE: usize = 0x76;
+
+/// Creates a SID file that only has a header, with a data offset at the end of the file and without a load address,
+/// so that the load address would have to be read after the end of the file.
+fn create_truncated_sid_file() -> Vec<u8> {
+    let mut sid_file = vec![0; MIN_SID_HEADER_SIZE];
+    sid_file[0..4].copy_from_slice(b"PSID");
+    sid_file[0x04..0x06].copy_from_slice(&2u16.to_be_bytes());
+    sid_file[0x06..0x08].copy_from_slice(&(MIN_SID_HEADER_SIZE as u16).to_be_bytes());
+    sid_file
+}
+
+#[test]
+fn malformed_file_is_reported_as_unidentified() {
+    let fixture = Fixture::new();
+    fixture.write_file("test.cfg", b"Test_Player\r\nA9 00 8D 18 D4\r\n");
+    fixture.write_file("malformed.sid", &create_truncated_sid_file());
+    fixture.write_file("tune.sid", &create_sid_file(1, 0x1000, &[0xA9, 0x00, 0x8D, 0x18, 0xD4]));
+
+    for arguments in [["--from-init", "-u"], ["--both-offsets", "-u"]] {
+        let output = fixture.scan(&arguments, "test.cfg", "*.sid");
+
+        assert!(output.success);
+        assert!(!output.stderr.contains("malformed.sid"), "unexpected error: {}", output.stderr);
+        assert!(output.stdout.lines().any(|line| line.contains("malformed.sid") && line.ends_with(">> UNIDENTIFIED <<")));
+        assert!(output.stdout.lines().any(|line| line.contains("tune.sid") && line.contains("Test_Player")));
+        assert!(output.stdout.contains("Total files processed         2"));
+    }
+}
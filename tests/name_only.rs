@@ -0,0 +1,46 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+mod common;
+
+use common::{create_sid_file, Fixture};
+
+fn scan_names(fixture: &Fixture, arguments: &[&str]) -> Vec<String> {
+    let output = fixture.scan(&[&["--name-only"], arguments].concat(), "test.cfg", "*.sid");
+
+    assert!(output.success);
+    output.stdout.lines().map(|line| line.trim_end().to_string()).collect()
+}
+
+#[test]
+fn name_only_prints_only_the_player_names() {
+    let fixture = Fixture::new();
+    fixture.write_file("test.cfg", b"First_Player\r\nA9 00 8D 18 D4\r\nSecond_Player\r\nA2 00 BD 00 10\r\n");
+    fixture.write_file("a.sid", &create_sid_file(1, 0x1000, &[0xA9, 0x00, 0x8D, 0x18, 0xD4]));
+    fixture.write_file("b.sid", &create_sid_file(1, 0x1000, &[0xA2, 0x00, 0xBD, 0x00, 0x10]));
+    fixture.write_file("c.sid", &create_sid_file(1, 0x1000, &[0xA0, 0x00, 0xB9, 0x00, 0x10]));
+
+    let mut names = scan_names(&fixture, &[]);
+    names.sort();
+    assert_eq!(names, vec!["First_Player", "Second_Player"]);
+
+    let mut names = scan_names(&fixture, &["-u", "--unidentified-label=?"]);
+    names.sort();
+    assert_eq!(names, vec!["?", "First_Player", "Second_Player"]);
+}
+
+#[test]
+fn name_only_prints_empty_files_only_when_unidentified_files_are_listed() {
+    let fixture = Fixture::new();
+    fixture.write_file("test.cfg", b"First_Player\r\nA9 00 8D 18 D4\r\n");
+    fixture.write_file("a.sid", &create_sid_file(1, 0x1000, &[0xA9, 0x00, 0x8D, 0x18, 0xD4]));
+    fixture.write_file("empty.sid", b"");
+
+    assert_eq!(scan_names(&fixture, &[]), vec!["First_Player"]);
+
+    let mut names = scan_names(&fixture, &["-u"]);
+    names.sort();
+    assert_eq!(names, vec![">> EMPTY <<", "First_Player"]);
+
+    assert_eq!(scan_names(&fixture, &["-u", "--skipped-label=-"]).iter().filter(|name| *name == "-").count(), 1);
+}
@@ -0,0 +1,23 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+mod common;
+
+use common::{create_sid_file, Fixture};
+
+#[test]
+fn repeat_scans_the_files_several_times() {
+    let fixture = Fixture::new();
+    fixture.write_file("test.cfg", b"Test_Player\r\nA9 00 8D 18 D4\r\n");
+    fixture.write_file("tune.sid", &create_sid_file(1, 0x1000, &[0xA9, 0x00, 0x8D, 0x18, 0xD4]));
+    fixture.write_file("other.sid", &create_sid_file(1, 0x1000, &[0xEA, 0xEA, 0xEA]));
+
+    let output = fixture.scan(&["--repeat=2"], "test.cfg", "*.sid");
+
+    let iterations = output.stderr.lines().filter(|line| line.starts_with("Iteration ")).collect::<Vec<&str>>();
+    assert!(output.success);
+    assert_eq!(iterations.len(), 2);
+    assert!(iterations[0].starts_with("Iteration 1: 1 of 2 files identified in "));
+    assert!(iterations[1].starts_with("Iteration 2: 1 of 2 files identified in "));
+    assert!(output.stderr.lines().any(|line| line.starts_with("Average time: ")));
+}
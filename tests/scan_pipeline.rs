@@ -0,0 +1,188 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+mod common;
+
+use common::{create_prg_file, create_sid_file, Fixture};
+use player_id::{ConfigSyntax, PlayerId, ScanStart, Scanner, SignatureConfig, SignatureMatch};
+
+const TEST_CONFIG: &str = "\
+Exact_Player\r
+A9 00 8D 18 D4 60\r
+\r
+Wildcard_Player\r
+A2 ?? BD ?? 10 9D 00 D4\r
+\r
+AndChain_Player\r
+78 A9 7F 8D 0D DC && AD 0D DC && 58 60\r
+";
+
+fn load_test_config(fixture: &Fixture) -> Vec<SignatureConfig> {
+    let config_path = fixture.write_file("test.cfg", TEST_CONFIG.as_bytes());
+    PlayerId::load_config_file(&config_path, None, ConfigSyntax::default()).unwrap()
+}
+
+fn to_name_and_indexes(matches: Vec<SignatureMatch>) -> Vec<(String, Vec<usize>)> {
+    matches.into_iter().map(|signature_match| (signature_match.signature_name.to_string(), signature_match.indexes)).collect()
+}
+
+#[test]
+fn load_config_file_compiles_all_signatures() {
+    let fixture = Fixture::new();
+    let signature_ids = load_test_config(&fixture);
+
+    let names = signature_ids.iter().map(|signature| signature.signature_name.to_string()).collect::<Vec<String>>();
+    assert_eq!(names, vec!["Exact_Player", "Wildcard_Player", "AndChain_Player"]);
+    assert_eq!(signature_ids[2].bndm_configs.len(), 3);
+}
+
+#[test]
+fn find_players_in_buffer_with_wildcard() {
+    let fixture = Fixture::new();
+    let signature_ids = load_test_config(&fixture);
+    let buffer = [0xEA, 0xEA, 0xA2, 0x03, 0xBD, 0x40, 0x10, 0x9D, 0x00, 0xD4];
+
+    let matches = PlayerId::find_players_in_buffer(&buffer, &signature_ids, true);
+
+    assert_eq!(to_name_and_indexes(matches), vec![("Wildcard_Player".to_string(), vec![2])]);
+}
+
+#[test]
+fn find_players_in_sid_file() {
+    let fixture = Fixture::new();
+    let signature_ids = load_test_config(&fixture);
+    let data = [
+        0xA9, 0x00, 0x8D, 0x18, 0xD4, 0x60,
+        0x78, 0xA9, 0x7F, 0x8D, 0x0D, 0xDC, 0xEA, 0xAD, 0x0D, 0xDC, 0xEA, 0xEA, 0x58, 0x60
+    ];
+    let sid_path = fixture.write_file("tune.sid", &create_sid_file(3, 0x1000, &data));
+
+    let matches = PlayerId::find_players_in_file(&sid_path, &signature_ids, true);
+    assert_eq!(to_name_and_indexes(matches), vec![
+        ("Exact_Player".to_string(), vec![0x7E]),
+        ("AndChain_Player".to_string(), vec![0x84, 0x8B, 0x90])
+    ]);
+
+    let matches = PlayerId::find_players_in_file(&sid_path, &signature_ids, false);
+    assert_eq!(to_name_and_indexes(matches), vec![("Exact_Player".to_string(), vec![0x7E])]);
+}
+
+#[test]
+fn find_players_in_prg_file_skips_load_address() {
+    let fixture = Fixture::new();
+    let signature_ids = load_test_config(&fixture);
+    let data = [0x78, 0xA9, 0x7F, 0x8D, 0x0D, 0xDC, 0xAD, 0x0D, 0xDC, 0x58, 0x60];
+
+    let prg_path = fixture.write_file("tune.prg", &create_prg_file(0x78A9, &data));
+    let matches = PlayerId::find_players_in_file(&prg_path, &signature_ids, true);
+    assert_eq!(to_name_and_indexes(matches), vec![("AndChain_Player".to_string(), vec![2, 8, 11])]);
+}
+
+#[test]
+fn and_chain_requires_order() {
+    let fixture = Fixture::new();
+    let signature_ids = load_test_config(&fixture);
+    let data = [0xAD, 0x0D, 0xDC, 0x78, 0xA9, 0x7F, 0x8D, 0x0D, 0xDC, 0x58, 0x60];
+
+    let prg_path = fixture.write_file("tune.prg", &create_prg_file(0x1000, &data));
+    assert!(PlayerId::find_players_in_file(&prg_path, &signature_ids, true).is_empty());
+}
+
+#[test]
+fn find_players_in_sid_file_from_init() {
+    let fixture = Fixture::new();
+    let signature_ids = load_test_config(&fixture);
+    let data = [
+        0xA9, 0x00, 0x8D, 0x18, 0xD4, 0x60,
+        0xEA, 0xEA,
+        0x78, 0xA9, 0x7F, 0x8D, 0x0D, 0xDC, 0xAD, 0x0D, 0xDC, 0x58, 0x60
+    ];
+    let mut sid_file = create_sid_file(1, 0x1000, &data);
+    sid_file[0x0A..0x0C].copy_from_slice(&0x1008u16.to_be_bytes());
+    let sid_path = fixture.write_file("tune.sid", &sid_file);
+
+    let matches = PlayerId::try_find_players_in_file_from_init(&sid_path, &signature_ids, true, 1).unwrap();
+    assert_eq!(to_name_and_indexes(matches), vec![("AndChain_Player".to_string(), vec![0, 6, 9])]);
+
+    let matches = PlayerId::try_find_players_in_file_at_stride(&sid_path, &signature_ids, true, 1).unwrap();
+    assert_eq!(matches.len(), 2);
+}
+
+#[test]
+fn find_players_in_prg_file_from_init_uses_data_offset() {
+    let fixture = Fixture::new();
+    let signature_ids = load_test_config(&fixture);
+    let prg_path = fixture.write_file("tune.prg", &create_prg_file(0x1000, &[0xEA, 0xA9, 0x00, 0x8D, 0x18, 0xD4, 0x60]));
+
+    let matches = PlayerId::try_find_players_in_file_from_init(&prg_path, &signature_ids, true, 1).unwrap();
+    assert_eq!(to_name_and_indexes(matches), vec![("Exact_Player".to_string(), vec![1])]);
+}
+
+#[test]
+fn find_players_in_program_file_skips_load_address() {
+    let fixture = Fixture::new();
+    let signature_ids = load_test_config(&fixture);
+    let ted_path = fixture.write_file("tune.ted", &create_prg_file(0x1001, &[0xA9, 0x00, 0x8D, 0x18, 0xD4, 0x60]));
+
+    let matches = PlayerId::try_find_players_in_file_from(&ted_path, &signature_ids, true, 1, ScanStart::Program).unwrap();
+    assert_eq!(to_name_and_indexes(matches), vec![("Exact_Player".to_string(), vec![2])]);
+
+    let empty_path = fixture.write_file("empty.ted", &[0x01]);
+    assert!(PlayerId::try_find_players_in_file_from(&empty_path, &signature_ids, true, 1, ScanStart::Program).unwrap().is_empty());
+}
+
+#[test]
+fn get_load_address_of_sid_and_prg_files() {
+    let fixture = Fixture::new();
+    let sid_path = fixture.write_file("tune.sid", &create_sid_file(1, 0x1000, &[0xA9, 0x00]));
+    let prg_path = fixture.write_file("tune.prg", &create_prg_file(0x0801, &[0xA9, 0x00]));
+    let dat_path = fixture.write_file("tune.dat", &create_prg_file(0x0801, &[0xA9, 0x00]));
+
+    assert_eq!(PlayerId::get_load_address(&sid_path, ScanStart::Data), Some((0x7E, 0x1000)));
+    assert_eq!(PlayerId::get_load_address(&sid_path, ScanStart::Init), None);
+    assert_eq!(PlayerId::get_load_address(&prg_path, ScanStart::Data), Some((2, 0x0801)));
+    assert_eq!(PlayerId::get_load_address(&dat_path, ScanStart::Data), None);
+    assert_eq!(PlayerId::get_load_address(&dat_path, ScanStart::Program), Some((2, 0x0801)));
+
+    let truncated_path = fixture.write_file("truncated.sid", &create_sid_file(1, 0x1000, &[])[..0x7D]);
+    assert_eq!(PlayerId::get_load_address(&truncated_path, ScanStart::Data), None);
+}
+
+#[test]
+fn find_players_with_custom_data_offset_handler() {
+    let mut scanner = Scanner::new();
+    scanner.register_data_offset_handler(|data, filename| {
+        (data.starts_with(b"FAKE") && filename.ends_with(".fake")).then_some(10)
+    });
+
+    let fixture = Fixture::new();
+    let signature_ids = load_test_config(&fixture);
+    let mut data = b"FAKE".to_vec();
+    data.extend_from_slice(&[0xA9, 0x00, 0x8D, 0x18, 0xD4, 0x60, 0xA9, 0x00, 0x8D, 0x18, 0xD4, 0x60]);
+    let fake_path = fixture.write_file("tune.fake", &data);
+    let raw_path = fixture.write_file("tune.raw", &data);
+
+    let matches = scanner.try_find_players_in_file(&fake_path, &signature_ids, true).unwrap().unwrap();
+    assert_eq!(to_name_and_indexes(matches), vec![("Exact_Player".to_string(), vec![10])]);
+
+    let matches = scanner.try_find_players_in_file(&raw_path, &signature_ids, true).unwrap().unwrap();
+    assert_eq!(to_name_and_indexes(matches), vec![("Exact_Player".to_string(), vec![4])]);
+
+    let matches = PlayerId::find_players_in_file(&fake_path, &signature_ids, true);
+    assert_eq!(to_name_and_indexes(matches), vec![("Exact_Player".to_string(), vec![4])]);
+}
+
+#[test]
+fn find_players_and_digest_of_file_content() {
+    let fixture = Fixture::new();
+    let signature_ids = load_test_config(&fixture);
+    let data = create_sid_file(1, 0x1000, &[0xA9, 0x00, 0x8D, 0x18, 0xD4, 0x60]);
+    let sid_path = fixture.write_file("tune.sid", &data);
+
+    let scanner = Scanner::new();
+    let (matches, digest) = scanner.try_find_players_and_digest_in_file(&sid_path, &signature_ids, true).unwrap();
+
+    let expected_matches = scanner.try_find_players_in_file(&sid_path, &signature_ids, true).unwrap().unwrap();
+    assert_eq!(to_name_and_indexes(matches.unwrap()), to_name_and_indexes(expected_matches));
+    assert_eq!(digest, md5::compute(&data).0);
+}
@@ -0,0 +1,22 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+mod common;
+
+use common::{create_prg_file, create_sid_file, Fixture};
+
+#[test]
+fn sid_only_skips_files_without_sid_header() {
+    let fixture = Fixture::new();
+    fixture.write_file("test.cfg", b"Test_Player\r\nA9 00 8D 18 D4\r\n");
+    fixture.write_file("tune.sid", &create_sid_file(1, 0x1000, &[0xA9, 0x00, 0x8D, 0x18, 0xD4]));
+    fixture.write_file("tune.prg", &create_prg_file(0x1000, &[0xA9, 0x00, 0x8D, 0x18, 0xD4]));
+
+    let output = fixture.scan(&["--sid-only", "-u"], "test.cfg", "tune.*");
+
+    assert!(output.success);
+    assert!(output.stdout.contains("tune.sid"));
+    assert!(!output.stdout.contains("tune.prg"));
+    assert!(output.stdout.contains("Total files processed         1"));
+    assert!(output.stderr.contains("Skipped 1 file(s) that aren't SID files"));
+}
@@ -0,0 +1,43 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+mod common;
+
+use common::{create_prg_file, run, Fixture};
+
+#[test]
+fn silent_mode_writes_nothing_to_stderr_on_success() {
+    let fixture = Fixture::new();
+    fixture.write_file("test.cfg", b"Test_Player\r\nA9 00 8D 18 D4\r\n");
+    fixture.write_file("tune.prg", &create_prg_file(0x1000, &[0xA9, 0x00, 0x8D, 0x18, 0xD4]));
+
+    let output = fixture.scan(&["--silent"], "test.cfg", "*.prg");
+
+    assert!(output.success);
+    assert!(output.stderr.is_empty(), "unexpected stderr output: {}", output.stderr);
+    assert!(output.stdout.contains("Test_Player"));
+    assert!(!output.stdout.contains("Using config file"));
+
+    let output = fixture.scan(&[], "test.cfg", "*.prg");
+    assert!(output.stderr.contains("Using config file: "));
+    assert!(!output.stdout.contains("Using config file"));
+}
+
+#[test]
+fn silent_mode_writes_no_config_file_banner_when_testing_the_config() {
+    let fixture = Fixture::new();
+    let config_path = fixture.write_file("test.cfg", b"Test_Player\r\nA9 00 8D 18 D4\r\n");
+
+    let output = run(&["--silent".to_string(), "--test-config".to_string(), format!("-f{}", config_path.display())]);
+
+    assert!(!output.stdout.contains("Using config file"));
+    assert!(!output.stderr.contains("Using config file"));
+}
+
+#[test]
+fn silent_mode_still_reports_errors() {
+    let output = run(&["--silent", "--unknown-option"]);
+
+    assert!(!output.success);
+    assert!(output.stderr.contains("ERROR: Unknown option: --unknown-option"));
+}
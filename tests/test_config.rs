@@ -0,0 +1,24 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+mod common;
+
+use common::{create_sid_file, run, Fixture};
+
+#[test]
+fn test_config_reports_passed_and_failed_expectations() {
+    let fixture = Fixture::new();
+    fixture.write_file("tunes/first.sid", &create_sid_file(1, 0x1000, &[0xA9, 0x00, 0x8D, 0x18, 0xD4]));
+    fixture.write_file("tunes/more/second.sid", &create_sid_file(1, 0x1000, &[0xEA, 0xA9, 0x00, 0x8D, 0x18, 0xD4]));
+    fixture.write_file("tunes/other.sid", &create_sid_file(1, 0x1000, &[0xA2, 0x00, 0xBD, 0x00, 0x10]));
+    let config_path = fixture.write_file("test.cfg", b"; EXPECT Test_Player 2 tunes\r\n; EXPECT Other_Player 2 tunes\r\n\r\n\
+        Test_Player\r\nA9 00 8D 18 D4\r\n\r\nOther_Player\r\nA2 00 BD 00 10\r\n");
+
+    let output = run(&["--test-config".to_string(), format!("-f{}", config_path.display())]);
+
+    assert!(!output.success);
+    assert!(output.stdout.contains("PASS: Test_Player matches 2 file(s) in: "));
+    assert!(output.stdout.contains("FAIL: Other_Player matches 1 file(s) instead of 2 in: "));
+    assert!(output.stdout.contains("1 passed, 1 failed"));
+    assert!(output.stderr.contains("Config test failed."));
+}
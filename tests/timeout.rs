@@ -0,0 +1,19 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+mod common;
+
+use common::{create_prg_file, Fixture};
+
+#[test]
+fn timeout_marks_file_that_takes_too_long() {
+    let fixture = Fixture::new();
+    let signatures = (0..32).map(|index| format!("Test_Player_{index:02}\r\nA9 {index:02X} ?? 8D 18 D4 ?? ?? 60\r\n")).collect::<Vec<String>>();
+    fixture.write_file("test.cfg", signatures.join("\r\n").as_bytes());
+    fixture.write_file("large.prg", &create_prg_file(0x1000, &vec![0xA9; 16 * 1024 * 1024]));
+
+    let output = fixture.scan(&["--timeout-ms=1"], "test.cfg", "*.prg");
+
+    assert!(output.success);
+    assert!(output.stdout.lines().any(|line| line.contains("large.prg") && line.contains(">> TIMEOUT <<")));
+}
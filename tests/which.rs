@@ -0,0 +1,29 @@
+// Copyright (C) 2024 Wilfred Bos
+// Licensed under the MIT license. See the LICENSE file for the terms and conditions.
+
+mod common;
+
+use std::path::Path;
+
+use common::{run_in, Fixture};
+
+#[test]
+fn which_prefers_the_current_directory_over_the_executable_directory() {
+    let fixture = Fixture::new();
+    let executable_directory = Path::new(env!("CARGO_BIN_EXE_player-id")).parent().unwrap();
+
+    let output = run_in(&fixture.path, &["--which"]);
+    assert!(output.success);
+    assert_eq!(output.stdout, format!("Config file: {}\r\nInfo file:   {}\r\n",
+        executable_directory.join("sidid.cfg").display(), executable_directory.join("sidid.nfo").display()));
+
+    fixture.write_file("sidid.cfg", b"Test_Player\r\nA9 00 8D 18 D4\r\n");
+    fixture.write_file("sidid.nfo", b"Test_Player\r\n     NAME: Test\r\n");
+
+    let output = run_in(&fixture.path, &["--which"]);
+    assert!(output.success);
+    assert_eq!(output.stdout, "Config file: sidid.cfg\r\nInfo file:   sidid.nfo\r\n");
+
+    let output = run_in(&fixture.path, &["--which", "-fmissing.cfg"]);
+    assert_eq!(output.stdout, "Config file: File doesn't exist: missing.cfg\r\nInfo file:   File doesn't exist: missing.cfg\r\n");
+}